@@ -0,0 +1,100 @@
+use crate::{
+    any::map_any_yaml_data_to_lidy_data,
+    error::{AnyBoxedError, JoinError, SimpleError},
+    expression::apply_expression,
+    parser::Parser,
+    result::{Data, MapData},
+    LidyResult,
+};
+use lidy__yaml::{Yaml, YamlData};
+
+pub fn apply_all_of_matcher<TV>(
+    parser: &mut Parser<TV>,
+    node: &Yaml,
+    content: &Yaml,
+) -> Result<LidyResult<TV>, AnyBoxedError>
+where
+{
+    let items = match &node.data {
+        YamlData::List(list) => list,
+        _ => panic!("expected schema node to be a sequence for (_allOf: <x>)"),
+    };
+
+    // Every expression is applied, so that the error lists all the failures
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    for schema in items {
+        match apply_expression(parser, schema, content) {
+            Ok(result) => results.push(result),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    if !errors.is_empty() {
+        let mut join_error = JoinError::default();
+        join_error.add(
+            SimpleError::from_check(
+                "_allOf",
+                &format!(
+                    "{} of the {} expressions did not match",
+                    errors.len(),
+                    items.len()
+                ),
+                content,
+            )
+            .into(),
+        );
+        errors.into_iter().for_each(|error| join_error.add(error));
+        return Err(join_error.into());
+    }
+
+    // An empty _allOf accepts any value
+    let data = if results.is_empty() {
+        map_any_yaml_data_to_lidy_data(
+            &parser.content_file_name,
+            parser.rule_trace.last().unwrap(),
+            content,
+        )
+    } else {
+        combine(results)
+    };
+    Ok(LidyResult::create(parser, content, data))
+}
+
+// When every expression gives a map, the result holds the entries of all of
+// them, the first expression giving the result of a key. Otherwise, the result
+// is the one of the first expression.
+fn combine<TV>(results: Vec<LidyResult<TV>>) -> Data<TV> {
+    if !results.iter().all(|r| matches!(r.data, Data::MapData(_))) {
+        return results.into_iter().next().unwrap().data;
+    }
+
+    let mut combined = MapData {
+        map: Default::default(),
+        map_of: Vec::new(),
+    };
+    for result in results {
+        let Data::MapData(map_data) = result.data else {
+            continue;
+        };
+        for (key, value) in map_data.map {
+            combined.map.entry(key).or_insert(value);
+        }
+        for entry in map_data.map_of {
+            let position = &entry.key.position;
+            let is_known = combined.map_of.iter().any(|other| {
+                (other.key.position.line, other.key.position.column)
+                    == (position.line, position.column)
+            });
+            if !is_known {
+                combined.map_of.push(entry);
+            }
+        }
+    }
+    // A key which an expression declares is not an association of another
+    combined.map_of.retain(|entry| match &entry.key.data {
+        Data::String(key) => !combined.map.contains_key(key),
+        _ => true,
+    });
+    Data::MapData(combined)
+}
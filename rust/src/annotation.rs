@@ -0,0 +1,105 @@
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::parser::Parser;
+use crate::syaml::extract_kv_entry;
+
+/// The documentation attached to a checker form of the schema with the
+/// `_title`, `_description`, `_examples` and `_deprecated` keywords
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Annotations {
+    pub title: Option<Box<str>>,
+    pub description: Option<Box<str>>,
+    pub examples: Vec<Yaml>,
+    pub deprecated: bool,
+}
+
+impl Annotations {
+    /// Read the annotations of a lidy expression. Rule references and
+    /// expressions without annotation keywords give empty annotations.
+    pub fn from_expression(expression: &Yaml) -> Annotations {
+        let mapping = match &expression.data {
+            YamlData::Mapping(mapping) => mapping,
+            _ => return Annotations::default(),
+        };
+        let text = |keyword: &str| {
+            extract_kv_entry(mapping, keyword)
+                .and_then(|node| node.data.as_str())
+                .map(Box::from)
+        };
+        Annotations {
+            title: text("_title"),
+            description: text("_description"),
+            examples: match extract_kv_entry(mapping, "_examples").map(|node| &node.data) {
+                Some(YamlData::List(examples)) => examples.clone(),
+                _ => Vec::new(),
+            },
+            deprecated: extract_kv_entry(mapping, "_deprecated")
+                .and_then(|node| node.data.as_bool())
+                .unwrap_or(false),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Annotations::default()
+    }
+}
+
+impl<'a, TV> Parser<'a, TV> {
+    /// The annotations of a rule of the schema. When the rule is an alias of
+    /// another rule, the annotations of the target rule are returned. Returns
+    /// `None` for predefined rules and unknown names.
+    pub fn rule_annotations(&self, rule_name: &str) -> Option<Annotations> {
+        let node = self.resolve_alias(&self.rule_set.get(rule_name)?.node);
+        Some(Annotations::from_expression(node))
+    }
+
+    /// The annotations of the expression of a key declared by the map checker
+    /// of a rule, in `_map`, `_mapFacultative` or one of the merged checkers.
+    /// If the key expression is a rule reference, the annotations of that rule
+    /// are returned.
+    pub fn key_annotations(&self, rule_name: &str, key: &str) -> Option<Annotations> {
+        let checker = self.resolve_alias(&self.rule_set.get(rule_name)?.node);
+        let expression = self.find_key_expression(checker, key)?;
+        Some(Annotations::from_expression(self.resolve_alias(expression)))
+    }
+
+    // Follow rule references until a checker form or a predefined rule is
+    // reached. The metaparser rejects reference cycles, but stay bounded
+    // anyway.
+    fn resolve_alias<'b>(&'b self, mut node: &'b Yaml) -> &'b Yaml {
+        for _ in 0..=self.rule_set.len() {
+            match node.data.as_str().and_then(|name| self.rule_set.get(name)) {
+                Some(rule) => node = &rule.node,
+                None => break,
+            }
+        }
+        node
+    }
+
+    fn find_key_expression<'b>(&'b self, checker: &'b Yaml, key: &str) -> Option<&'b Yaml> {
+        let mapping = match &checker.data {
+            YamlData::Mapping(mapping) => mapping,
+            _ => return None,
+        };
+        for keyword in ["_map", "_mapFacultative"] {
+            if let Some(YamlData::Mapping(entries)) =
+                extract_kv_entry(mapping, keyword).map(|node| &node.data)
+            {
+                if let Some(expression) = extract_kv_entry(entries, key) {
+                    return Some(expression);
+                }
+            }
+        }
+        if let Some(YamlData::List(merge_list)) =
+            extract_kv_entry(mapping, "_merge").map(|n| &n.data)
+        {
+            for merged in merge_list {
+                if let Some(expression) = self.find_key_expression(self.resolve_alias(merged), key)
+                {
+                    return Some(expression);
+                }
+            }
+        }
+        None
+    }
+}
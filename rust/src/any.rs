@@ -6,7 +6,9 @@ use std::{collections::HashMap, rc::Rc};
 use lidy__yaml::{Yaml, YamlData};
 
 use crate::{
-    result::Data, syaml::must_parse_float, KeyValueData, LidyResult, ListData, MapData, Position,
+    result::Data,
+    syaml::{must_parse_float, parse_timestamp},
+    KeyValueData, LidyResult, ListData, MapData, Position,
 };
 
 // Info:
@@ -22,8 +24,18 @@ where
     match &content.data {
         YamlData::String(value) => Data::String(value.clone().into()),
         YamlData::Integer(value) => Data::Integer(*value),
+        // Integers beyond 64 bits stay exact as text without the bigint feature
+        YamlData::BigInteger(text) => {
+            Data::from_big_integer(text).unwrap_or_else(|| Data::String(text.clone().into()))
+        }
         YamlData::Real(value) => Data::Float(must_parse_float(value)),
         YamlData::Boolean(value) => Data::Boolean(*value),
+        // TOML local times are not timestamps
+        YamlData::Timestamp(value) => match parse_timestamp(value) {
+            Some(timestamp) => Data::Timestamp(timestamp),
+            None => Data::String(value.clone().into()),
+        },
+        YamlData::Binary(bytes) => Data::Binary(bytes.clone()),
         YamlData::Null => Data::Null,
         YamlData::Mapping(value_mapping) => {
             let mut map_data = MapData {
@@ -0,0 +1,43 @@
+use crate::{
+    any::map_any_yaml_data_to_lidy_data,
+    error::AnyBoxedError,
+    parser::Parser,
+    syaml::{first_difference, to_flow_yaml},
+    LidyResult, SimpleError,
+};
+use lidy__yaml::Yaml;
+
+pub fn apply_const_matcher<TV>(
+    parser: &mut Parser<TV>,
+    node: &Yaml,
+    content: &Yaml,
+) -> Result<LidyResult<TV>, AnyBoxedError>
+where
+{
+    if let Some(difference) = first_difference(node, content) {
+        let location = match difference.path.as_str() {
+            "" => String::new(),
+            path => format!(" at {path}"),
+        };
+        return Err(SimpleError::from_check(
+            "_const",
+            &format!(
+                "must be {}, but differs{location}: {}",
+                to_flow_yaml(node),
+                difference.description
+            ),
+            difference.node,
+        )
+        .into());
+    }
+
+    Ok(LidyResult::create(
+        parser,
+        content,
+        map_any_yaml_data_to_lidy_data(
+            &parser.content_file_name,
+            parser.rule_trace.last().unwrap(),
+            content,
+        ),
+    ))
+}
@@ -0,0 +1,687 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use lidy__yaml::{LineCol, Yaml, YamlData};
+
+use crate::expression::apply_expression;
+use crate::format::is_string_format;
+use crate::key_constraint::describe_key_constraints;
+use crate::parser::Parser;
+use crate::range::{RangeBound, RangeKind, RangePattern};
+use crate::reference::CrossReferences;
+use crate::rule::Rule;
+use crate::sample::form::{map_keys, read_form, Form, ListForm, MapForm, Size};
+use crate::syaml::{same_data, same_value, to_flow_yaml};
+
+/// A difference between two versions of a schema, positioned in the versions
+/// which contain the changed construct
+#[derive(Clone, Debug)]
+pub struct SchemaChange {
+    pub rule_name: Box<str>,
+    pub old_line_col: Option<LineCol>,
+    pub new_line_col: Option<LineCol>,
+    pub message: Box<str>,
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.rule_name, self.message)?;
+        match (self.old_line_col, self.new_line_col) {
+            (Some(old), Some(new)) => write!(f, " (old {old}, new {new})"),
+            (Some(old), None) => write!(f, " (old {old})"),
+            (None, Some(new)) => write!(f, " (new {new})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// The changes from an old version of a schema to a new one. A change is
+/// breaking when a document accepted by the old version may be rejected by
+/// the new one.
+#[derive(Clone, Debug, Default)]
+pub struct SchemaDiff {
+    pub breaking: Vec<SchemaChange>,
+    pub compatible: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    pub fn is_compatible(&self) -> bool {
+        self.breaking.is_empty()
+    }
+}
+
+/// Compare the rules of two versions of a schema structurally. Rules of the
+/// same name are compared with each other, and so are the keys, the list
+/// items and the `_oneOf` alternatives of their checkers.
+///
+/// Changes which the comparison cannot prove compatible, such as a changed
+/// `_regex`, are reported as breaking.
+pub fn diff_rule_sets(old: &HashMap<Box<str>, Rule>, new: &HashMap<Box<str>, Rule>) -> SchemaDiff {
+    let mut differ = Differ {
+        old,
+        new,
+        new_parser: Parser {
+            content_file_name: "".into(),
+            content_source: "".into(),
+            schema_file_name: "".into(),
+            rule_set: new.clone(),
+            builder_callback: Box::new(|_, lidy_result| Ok(lidy_result.data.clone())),
+            rule_trace: Vec::new(),
+            rule_is_matching_node: HashMap::new(),
+            skip_builders: false,
+            cross_references: CrossReferences::default(),
+        },
+        rule_name: "".into(),
+        compared: HashSet::new(),
+        diff: SchemaDiff::default(),
+    };
+
+    let mut names: Vec<&Box<str>> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        differ.rule_name = name.clone();
+        match (old.get(name), new.get(name)) {
+            (Some(old_rule), Some(new_rule)) => differ.compare(&old_rule.node, &new_rule.node),
+            (Some(old_rule), None) => differ.report(
+                true,
+                Some(&old_rule.node),
+                None,
+                format!("rule '{name}' was removed"),
+            ),
+            (None, Some(new_rule)) => differ.report(
+                false,
+                None,
+                Some(&new_rule.node),
+                format!("rule '{name}' was added"),
+            ),
+            (None, None) => {}
+        }
+    }
+
+    let mut diff = differ.diff;
+    for changes in [&mut diff.breaking, &mut diff.compatible] {
+        changes.sort_by(|a, b| {
+            (&a.rule_name, a.new_line_col, a.old_line_col, &a.message).cmp(&(
+                &b.rule_name,
+                b.new_line_col,
+                b.old_line_col,
+                &b.message,
+            ))
+        });
+    }
+    diff
+}
+
+struct Differ<'a> {
+    old: &'a HashMap<Box<str>, Rule>,
+    new: &'a HashMap<Box<str>, Rule>,
+    // Used to check which old `_in` values a new expression accepts
+    new_parser: Parser<'static, ()>,
+    rule_name: Box<str>,
+    // Pairs of differently named rules already compared, which bounds the
+    // comparison of recursive rules
+    compared: HashSet<(&'a str, &'a str)>,
+    diff: SchemaDiff,
+}
+
+impl<'a> Differ<'a> {
+    fn report(
+        &mut self,
+        is_breaking: bool,
+        old: Option<&Yaml>,
+        new: Option<&Yaml>,
+        message: String,
+    ) {
+        let change = SchemaChange {
+            rule_name: self.rule_name.clone(),
+            old_line_col: old.map(|node| node.line_col),
+            new_line_col: new.map(|node| node.line_col),
+            message: message.into(),
+        };
+        if is_breaking {
+            self.diff.breaking.push(change);
+        } else {
+            self.diff.compatible.push(change);
+        }
+    }
+
+    fn breaking(&mut self, old: &Yaml, new: &Yaml, message: String) {
+        self.report(true, Some(old), Some(new), message);
+    }
+
+    fn compatible(&mut self, old: &Yaml, new: &Yaml, message: String) {
+        self.report(false, Some(old), Some(new), message);
+    }
+
+    // Follow references to rules of the schema until a checker or a
+    // predefined rule
+    fn resolve(rule_set: &'a HashMap<Box<str>, Rule>, mut node: &'a Yaml) -> &'a Yaml {
+        for _ in 0..=rule_set.len() {
+            match node.data.as_str().and_then(|name| rule_set.get(name)) {
+                Some(rule) => node = &rule.node,
+                None => break,
+            }
+        }
+        node
+    }
+
+    fn compare(&mut self, old: &'a Yaml, new: &'a Yaml) {
+        if same_data(old, new) {
+            let name = old.data.as_str();
+            // A rule which only one version defines stands for different
+            // expressions in the two versions
+            let is_same_rule =
+                name.is_none_or(|name| self.old.contains_key(name) == self.new.contains_key(name));
+            if is_same_rule {
+                return;
+            }
+        }
+        let old_name = old
+            .data
+            .as_str()
+            .filter(|name| self.old.contains_key(*name));
+        let new_name = new
+            .data
+            .as_str()
+            .filter(|name| self.new.contains_key(*name));
+        if old_name.is_some() || new_name.is_some() {
+            if !self
+                .compared
+                .insert((old_name.unwrap_or(""), new_name.unwrap_or("")))
+            {
+                return;
+            }
+            let (old, new) = (Self::resolve(self.old, old), Self::resolve(self.new, new));
+            return self.compare(old, new);
+        }
+
+        if matches!(new.data.as_str(), Some("any" | "anyData")) {
+            let message = format!("{} became any value", describe(old));
+            return self.compatible(old, new, message);
+        }
+        let (Ok(old_form), Ok(new_form)) = (read_form(old), read_form(new)) else {
+            let message = format!("{} became {}", describe(old), describe(new));
+            return self.breaking(old, new, message);
+        };
+        match (old_form, new_form) {
+            (Form::In(values), _) => self.in_values(old, values, new),
+            (Form::Const(old_value), Form::Const(new_value)) => {
+                if !same_value(old_value, new_value) {
+                    let message = format!(
+                        "the constant {} became {}",
+                        to_flow_yaml(old_value),
+                        to_flow_yaml(new_value)
+                    );
+                    self.breaking(old, new, message);
+                }
+            }
+            (Form::Const(value), _) => self.in_values(old, std::slice::from_ref(value), new),
+            (Form::Rule(old_name), Form::Rule(new_name)) => {
+                let message = format!("'{old_name}' became '{new_name}'");
+                let is_wider = old_name == "never"
+                    || (is_string_format(old_name) && new_name == "string")
+                    || matches!(
+                        (old_name, new_name),
+                        ("int", "float") | ("port", "int" | "float")
+                    );
+                if is_wider {
+                    self.compatible(old, new, message);
+                } else {
+                    self.breaking(old, new, message);
+                }
+            }
+            (Form::Regex(..) | Form::String(..), Form::Rule("string"))
+            | (Form::Range(_), Form::Rule("float")) => {
+                let message = format!("{} became {}", describe(old), describe(new));
+                self.compatible(old, new, message);
+            }
+            (Form::Regex(old_checker, old_size), Form::Regex(new_checker, new_size)) => {
+                let message = format!(
+                    "the pattern {} became {}",
+                    old_checker.display(),
+                    new_checker.display()
+                );
+                if old_checker.effective_pattern() != new_checker.effective_pattern() {
+                    self.breaking(old, new, message);
+                }
+                self.size(old, &old_size, new, &new_size);
+            }
+            (Form::String(old_expression, old_size), Form::String(new_expression, new_size)) => {
+                self.compare(old_expression, new_expression);
+                self.size(old, &old_size, new, &new_size);
+            }
+            (Form::Range(old_pattern), Form::Range(new_pattern)) => {
+                self.range(old, old_pattern, new, new_pattern)
+            }
+            (Form::OneOf(old_alternatives), Form::OneOf(new_alternatives)) => {
+                self.one_of(old, old_alternatives, new, new_alternatives)
+            }
+            (_, Form::OneOf(new_alternatives)) => {
+                match new_alternatives.iter().position(|n| same_data(old, n)) {
+                    Some(k) => self.compatible(
+                        old,
+                        new,
+                        format!("{} became alternative {k} of a _oneOf", describe(old)),
+                    ),
+                    None => self.breaking(
+                        old,
+                        new,
+                        format!("{} became a _oneOf checker", describe(old)),
+                    ),
+                }
+            }
+            (Form::OneOf(old_alternatives), _) => {
+                for (k, alternative) in old_alternatives.iter().enumerate() {
+                    if !same_data(alternative, new) {
+                        self.breaking(alternative, new, format!("alternative {k} was removed"));
+                    }
+                }
+            }
+            (Form::AllOf(old_expressions), Form::AllOf(new_expressions)) => {
+                for (k, expression) in new_expressions.iter().enumerate() {
+                    if !old_expressions.iter().any(|e| same_data(e, expression)) {
+                        let message = format!("expression {k} was added to the _allOf");
+                        self.breaking(old, expression, message);
+                    }
+                }
+                for (k, expression) in old_expressions.iter().enumerate() {
+                    if !new_expressions.iter().any(|e| same_data(e, expression)) {
+                        let message = format!("expression {k} was removed from the _allOf");
+                        self.compatible(expression, new, message);
+                    }
+                }
+            }
+            (Form::Not(old_inner), Form::Not(new_inner)) => {
+                // Widening the negated expression narrows the checker, so any
+                // change may reject values
+                if !same_data(old_inner, new_inner) {
+                    let message = format!(
+                        "the negated expression {} became {}",
+                        to_flow_yaml(old_inner),
+                        to_flow_yaml(new_inner)
+                    );
+                    self.breaking(old, new, message);
+                }
+            }
+            (Form::Defines(old_space), Form::Defines(new_space)) if old_space != new_space => {
+                // The references to the old key space lose this name
+                let message = format!(
+                    "the defined name moved from the key space '{old_space}' to '{new_space}'"
+                );
+                self.breaking(old, new, message);
+            }
+            (Form::References(old_space), Form::References(new_space))
+                if old_space != new_space =>
+            {
+                let message = format!(
+                    "the reference moved from the key space '{old_space}' to '{new_space}'"
+                );
+                self.breaking(old, new, message);
+            }
+            (Form::Defines(_), Form::Defines(_)) | (Form::References(_), Form::References(_)) => {}
+            (Form::Discriminator(old_key, old_cases), Form::Discriminator(new_key, new_cases)) => {
+                self.discriminator(old, old_key, old_cases, new, new_key, new_cases)
+            }
+            (Form::Map(old_form), Form::Map(new_form)) => self.map(old, &old_form, new, &new_form),
+            (Form::List(old_form), Form::List(new_form)) => {
+                self.list(old, &old_form, new, &new_form)
+            }
+            _ => {
+                let message = format!("{} became {}", describe(old), describe(new));
+                self.breaking(old, new, message);
+            }
+        }
+    }
+
+    fn in_values(&mut self, old: &Yaml, values: &'a [Yaml], new: &'a Yaml) {
+        self.new_parser.rule_trace.push(self.rule_name.clone());
+        for value in values {
+            if apply_expression(&mut self.new_parser, new, value).is_err() {
+                let message = format!("value {} is no longer accepted", to_flow_yaml(value));
+                self.breaking(value, new, message);
+            }
+        }
+        self.new_parser.rule_trace.pop();
+
+        match read_form(new) {
+            Ok(Form::In(new_values)) => {
+                for value in new_values {
+                    if !values.iter().any(|v| same_data(v, value)) {
+                        let message = format!("value {} is now accepted", to_flow_yaml(value));
+                        self.compatible(old, value, message);
+                    }
+                }
+            }
+            _ => {
+                let message = format!("{} became {}", describe(old), describe(new));
+                self.compatible(old, new, message);
+            }
+        }
+    }
+
+    fn range(&mut self, old: &Yaml, old_pattern: &str, new: &Yaml, new_pattern: &str) {
+        let (Some(old_range), Some(new_range)) = (
+            RangePattern::parse(old_pattern),
+            RangePattern::parse(new_pattern),
+        ) else {
+            return;
+        };
+        let is_narrower = (new_range.kind != old_range.kind
+            && (old_range.kind, new_range.kind) != (RangeKind::Int, RangeKind::Float))
+            || is_tighter(old_range.minimum, new_range.minimum, 1.0)
+            || is_tighter(old_range.maximum, new_range.maximum, -1.0);
+        let message = format!("the range `{old_pattern}` became `{new_pattern}`");
+        if is_narrower {
+            self.breaking(old, new, message);
+        } else {
+            self.compatible(old, new, message);
+        }
+    }
+
+    fn one_of(
+        &mut self,
+        old: &Yaml,
+        old_alternatives: &'a [Yaml],
+        new: &Yaml,
+        new_alternatives: &'a [Yaml],
+    ) {
+        // Pair the alternatives which did not change, then the changed ones
+        // in order
+        let mut unmatched: Vec<usize> = (0..new_alternatives.len()).collect();
+        let mut removed = Vec::new();
+        for (i, alternative) in old_alternatives.iter().enumerate() {
+            match unmatched
+                .iter()
+                .position(|&j| same_data(alternative, &new_alternatives[j]))
+            {
+                Some(position) => {
+                    unmatched.remove(position);
+                }
+                None => removed.push(i),
+            }
+        }
+        if removed.len() == unmatched.len() {
+            for (&i, &j) in removed.iter().zip(&unmatched) {
+                self.compare(&old_alternatives[i], &new_alternatives[j]);
+            }
+            return;
+        }
+        for i in removed {
+            self.breaking(
+                &old_alternatives[i],
+                new,
+                format!("alternative {i} was removed"),
+            );
+        }
+        for j in unmatched {
+            self.compatible(
+                old,
+                &new_alternatives[j],
+                format!("alternative {j} was added"),
+            );
+        }
+    }
+
+    // The cases are matched by their discriminator value
+    fn discriminator(
+        &mut self,
+        old: &'a Yaml,
+        old_key: &str,
+        old_cases: &'a [(Yaml, Yaml)],
+        new: &'a Yaml,
+        new_key: &str,
+        new_cases: &'a [(Yaml, Yaml)],
+    ) {
+        if old_key != new_key {
+            let message = format!("the discriminator key '{old_key}' became '{new_key}'");
+            return self.breaking(old, new, message);
+        }
+        for (old_value, old_expression) in old_cases {
+            let value = to_flow_yaml(old_value);
+            match new_cases.iter().find(|(v, _)| same_value(v, old_value)) {
+                Some((_, new_expression)) => self.compare(old_expression, new_expression),
+                None => self.breaking(old_value, new, format!("case {value} was removed")),
+            }
+        }
+        for (new_value, _) in new_cases {
+            if !old_cases.iter().any(|(v, _)| same_value(v, new_value)) {
+                let message = format!("new case {}", to_flow_yaml(new_value));
+                self.compatible(old, new_value, message);
+            }
+        }
+    }
+
+    fn map(
+        &mut self,
+        old: &'a Yaml,
+        old_form: &MapForm<'a>,
+        new: &'a Yaml,
+        new_form: &MapForm<'a>,
+    ) {
+        let (Ok(old_keys), Ok(new_keys)) =
+            (map_keys(self.old, old_form), map_keys(self.new, new_form))
+        else {
+            return;
+        };
+        for old_key in &old_keys {
+            let name = old_key.name;
+            let Some(new_key) = new_keys.iter().find(|key| key.name == name) else {
+                self.breaking(old_key.expression, new, format!("key '{name}' was removed"));
+                continue;
+            };
+            match (old_key.mandatory, new_key.mandatory) {
+                (false, true) => self.breaking(
+                    old_key.expression,
+                    new_key.expression,
+                    format!("key '{name}' became mandatory"),
+                ),
+                (true, false) => self.compatible(
+                    old_key.expression,
+                    new_key.expression,
+                    format!("key '{name}' became facultative"),
+                ),
+                _ => {}
+            }
+            self.compare(old_key.expression, new_key.expression);
+        }
+        for new_key in &new_keys {
+            let name = new_key.name;
+            if old_keys.iter().any(|key| key.name == name) {
+                continue;
+            }
+            if new_key.mandatory {
+                self.breaking(
+                    old,
+                    new_key.expression,
+                    format!("new mandatory key '{name}'"),
+                );
+            } else {
+                self.compatible(
+                    old,
+                    new_key.expression,
+                    format!("new facultative key '{name}'"),
+                );
+            }
+        }
+
+        match (old_form.map_of, new_form.map_of) {
+            ([], []) => {}
+            ([], _) => self.compatible(old, new, "_mapOf now accepts the undeclared keys".into()),
+            (_, []) => self.breaking(
+                old,
+                new,
+                "_mapOf was removed, undeclared keys are no longer accepted".into(),
+            ),
+            (old_map_of, new_map_of) if old_map_of.len() == new_map_of.len() => {
+                for ((old_key, old_value), (new_key, new_value)) in
+                    old_map_of.iter().zip(new_map_of)
+                {
+                    self.compare(old_key, new_key);
+                    self.compare(old_value, new_value);
+                }
+            }
+            _ => self.breaking(old, new, "the _mapOf associations changed".into()),
+        }
+
+        self.size(old, &old_form.size, new, &new_form.size);
+
+        // A new key constraint rejects some maps, a removed one accepts them
+        if let (YamlData::Mapping(old_mapping), YamlData::Mapping(new_mapping)) =
+            (&old.data, &new.data)
+        {
+            let old_constraints = describe_key_constraints(self.old, old_mapping);
+            let new_constraints = describe_key_constraints(self.new, new_mapping);
+            for constraint in &new_constraints {
+                if !old_constraints.contains(constraint) {
+                    self.breaking(old, new, format!("new key constraint: {constraint}"));
+                }
+            }
+            for constraint in &old_constraints {
+                if !new_constraints.contains(constraint) {
+                    self.compatible(old, new, format!("key constraint removed: {constraint}"));
+                }
+            }
+        }
+    }
+
+    fn list(
+        &mut self,
+        old: &'a Yaml,
+        old_form: &ListForm<'a>,
+        new: &'a Yaml,
+        new_form: &ListForm<'a>,
+    ) {
+        let item = |form: &ListForm<'a>, k: usize| {
+            if k < form.list.len() {
+                Some(&form.list[k])
+            } else if k < form.list.len() + form.list_facultative.len() {
+                Some(&form.list_facultative[k - form.list.len()])
+            } else {
+                form.list_of
+            }
+        };
+        let old_fixed = old_form.list.len() + old_form.list_facultative.len();
+        let new_fixed = new_form.list.len() + new_form.list_facultative.len();
+        // The last index stands for all the _listOf items
+        for k in 0..=old_fixed.max(new_fixed) {
+            let is_mandatory = |form: &ListForm| k < form.list.len();
+            match (item(old_form, k), item(new_form, k)) {
+                (Some(old_item), Some(new_item)) => {
+                    match (is_mandatory(old_form), is_mandatory(new_form)) {
+                        (false, true) => {
+                            self.breaking(old_item, new_item, format!("item {k} became mandatory"))
+                        }
+                        (true, false) => self.compatible(
+                            old_item,
+                            new_item,
+                            format!("item {k} became facultative"),
+                        ),
+                        _ => {}
+                    }
+                    self.compare(old_item, new_item);
+                }
+                (Some(old_item), None) => {
+                    self.breaking(old_item, new, format!("item {k} is no longer accepted"))
+                }
+                (None, Some(new_item)) if is_mandatory(new_form) => {
+                    self.breaking(old, new_item, format!("new mandatory item {k}"))
+                }
+                (None, Some(new_item)) => {
+                    self.compatible(old, new_item, format!("new facultative item {k}"))
+                }
+                (None, None) => {}
+            }
+        }
+
+        self.size(old, &old_form.size, new, &new_form.size);
+
+        match (old_form.unique, new_form.unique) {
+            (false, true) => self.breaking(old, new, "the items must now be unique".into()),
+            (true, false) => self.compatible(old, new, "the items may now repeat".into()),
+            _ => {}
+        }
+        if old_form.unique_by != new_form.unique_by {
+            let describe = |key: Option<&str>| match key {
+                Some(key) => format!("unique by {key}"),
+                None => "not unique by a key".into(),
+            };
+            let message = format!(
+                "the items became {} instead of {}",
+                describe(new_form.unique_by),
+                describe(old_form.unique_by)
+            );
+            // Dropping the key is the only change which rejects no list
+            if new_form.unique_by.is_none() {
+                self.compatible(old, new, message);
+            } else {
+                self.breaking(old, new, message);
+            }
+        }
+    }
+
+    fn size(&mut self, old: &Yaml, old_size: &Size, new: &Yaml, new_size: &Size) {
+        let lower = |size: &Size| size.nb.or(size.min).unwrap_or(0);
+        let upper = |size: &Size| size.nb.or(size.max);
+        let (old_lower, new_lower) = (lower(old_size), lower(new_size));
+        if new_lower > old_lower {
+            let message = format!("the minimum size rose from {old_lower} to {new_lower}");
+            self.breaking(old, new, message);
+        } else if new_lower < old_lower {
+            let message = format!("the minimum size fell from {old_lower} to {new_lower}");
+            self.compatible(old, new, message);
+        }
+        match (upper(old_size), upper(new_size)) {
+            (Some(old_upper), Some(new_upper)) if new_upper < old_upper => {
+                let message = format!("the maximum size fell from {old_upper} to {new_upper}");
+                self.breaking(old, new, message);
+            }
+            (Some(old_upper), Some(new_upper)) if new_upper > old_upper => {
+                let message = format!("the maximum size rose from {old_upper} to {new_upper}");
+                self.compatible(old, new, message);
+            }
+            (None, Some(new_upper)) => {
+                let message = format!("the size is now limited to {new_upper}");
+                self.breaking(old, new, message);
+            }
+            (Some(old_upper), None) => {
+                let message = format!("the size is no longer limited to {old_upper}");
+                self.compatible(old, new, message);
+            }
+            _ => {}
+        }
+    }
+}
+
+// Whether the new bound excludes numbers the old one accepts. The direction
+// is 1 for minimums and -1 for maximums.
+fn is_tighter(old: Option<RangeBound>, new: Option<RangeBound>, direction: f64) -> bool {
+    match (old, new) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(old), Some(new)) => {
+            let (old_value, new_value) = (old.value * direction, new.value * direction);
+            new_value > old_value || (new_value == old_value && old.inclusive && !new.inclusive)
+        }
+    }
+}
+
+fn describe(node: &Yaml) -> String {
+    match read_form(node) {
+        Ok(Form::Rule(name)) => format!("'{name}'"),
+        Ok(Form::Regex(..)) => "a _regex checker".into(),
+        Ok(Form::String(..)) => "a _string checker".into(),
+        Ok(Form::In(_)) => "an _in checker".into(),
+        Ok(Form::Const(_)) => "a _const checker".into(),
+        Ok(Form::Range(_)) => "a _range checker".into(),
+        Ok(Form::OneOf(_)) => "a _oneOf checker".into(),
+        Ok(Form::AllOf(_)) => "an _allOf checker".into(),
+        Ok(Form::Not(_)) => "a _not checker".into(),
+        Ok(Form::Defines(_)) => "a _defines checker".into(),
+        Ok(Form::References(_)) => "a _references checker".into(),
+        Ok(Form::Discriminator(..)) => "a _discriminator checker".into(),
+        Ok(Form::Map(_)) => "a map checker".into(),
+        Ok(Form::List(_)) => "a list checker".into(),
+        Err(_) => "a checker".into(),
+    }
+}
@@ -0,0 +1,65 @@
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::{
+    error::AnyBoxedError,
+    expression::apply_expression,
+    parser::Parser,
+    syaml::{extract_kv_entry, same_value, to_flow_yaml},
+    LidyResult, SimpleError,
+};
+
+// The discriminator key of a _discriminator checker and its _cases, as pairs
+// of a value of the key and the expression applied to the maps which hold it
+pub fn read_discriminator(mapping: &Vec<(Yaml, Yaml)>) -> Option<(&str, &[(Yaml, Yaml)])> {
+    let key = extract_kv_entry(mapping, "_discriminator")?.data.as_str()?;
+    match &extract_kv_entry(mapping, "_cases")?.data {
+        YamlData::Mapping(cases) => Some((key, cases)),
+        _ => None,
+    }
+}
+
+pub fn describe_cases(cases: &[(Yaml, Yaml)]) -> String {
+    let values: Vec<String> = cases.iter().map(|(value, _)| to_flow_yaml(value)).collect();
+    values.join(", ")
+}
+
+// Only the case which the discriminator value selects is applied, so that its
+// errors are reported alone
+pub fn apply_discriminator_matcher<TV>(
+    parser: &mut Parser<TV>,
+    mapping: &Vec<(Yaml, Yaml)>,
+    content: &Yaml,
+) -> Result<LidyResult<TV>, AnyBoxedError>
+where
+{
+    let Some((key, cases)) = read_discriminator(mapping) else {
+        return Err(SimpleError::from_message("_discriminator: _cases is missing".into()).into());
+    };
+    let YamlData::Mapping(entries) = &content.data else {
+        return Err(SimpleError::from_check("_discriminator", "expected a map", content).into());
+    };
+    let Some(value) = extract_kv_entry(entries, key) else {
+        return Err(SimpleError::from_check(
+            "_discriminator",
+            &format!(
+                "missing the key '{key}', expected one of {}",
+                describe_cases(cases)
+            ),
+            content,
+        )
+        .into());
+    };
+    match cases.iter().find(|(case, _)| same_value(case, value)) {
+        Some((_, expression)) => apply_expression(parser, expression, content),
+        None => Err(SimpleError::from_check(
+            "_discriminator",
+            &format!(
+                "{key}: {} is not one of {}",
+                to_flow_yaml(value),
+                describe_cases(cases)
+            ),
+            value,
+        )
+        .into()),
+    }
+}
@@ -0,0 +1,574 @@
+use std::collections::HashMap;
+
+use lidy__yaml::{Yaml, YamlData};
+
+use super::render::{render_html, render_markdown};
+use super::{Block, Inline, Item};
+use crate::annotation::Annotations;
+use crate::discriminator::read_discriminator;
+use crate::format::FORMAT_RULES;
+use crate::key_constraint::describe_key_constraints;
+use crate::map::resolve_merge_reference;
+use crate::range::{RangeKind, RangePattern};
+use crate::regex::RegexChecker;
+use crate::rule::Rule;
+use crate::syaml::{extract_kv_entry, to_flow_yaml};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+/// Render the reference documentation of a rule set, with one section per
+/// rule, in schema order. Rule references become links between the sections.
+pub fn generate_documentation(
+    rule_set: &HashMap<Box<str>, Rule>,
+    title: &str,
+    format: DocFormat,
+) -> String {
+    let mut rule_list: Vec<&Rule> = rule_set.values().collect();
+    rule_list.sort_by_key(|rule| rule.node.line_col);
+
+    let generator = Generator { rule_set };
+    let mut blocks = vec![Block::Heading(1, title.into(), None)];
+    for rule in rule_list {
+        blocks.extend(generator.rule(rule));
+    }
+
+    match format {
+        DocFormat::Markdown => render_markdown(&blocks),
+        DocFormat::Html => render_html(title, &blocks),
+    }
+}
+
+fn predefined_rule_description(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "string" => "a string",
+        "int" => "an integer",
+        "float" => "a number",
+        "binary" => "base64-encoded binary data",
+        "boolean" => "a boolean",
+        "nullType" => "null",
+        "timestamp" => "a timestamp (a date, or a date and time)",
+        "any" | "anyData" => "any value",
+        "never" => "no value at all",
+        _ => {
+            let (_, expected) = FORMAT_RULES.iter().find(|(format, _)| *format == name)?;
+            expected
+        }
+    })
+}
+
+fn text(value: &str) -> Inline {
+    Inline::Text(value.into())
+}
+
+// Turn a phrase into a sentence
+fn sentence(mut phrase: Vec<Inline>) -> Block {
+    if let Some(Inline::Text(first)) = phrase.first_mut() {
+        let mut chars = first.chars();
+        if let Some(c) = chars.next() {
+            *first = c.to_uppercase().chain(chars).collect();
+        }
+    }
+    phrase.push(text("."));
+    Block::Paragraph(phrase)
+}
+
+fn code_list(values: &[Yaml]) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    for (k, value) in values.iter().enumerate() {
+        if k > 0 {
+            inlines.push(text(", "));
+        }
+        inlines.push(Inline::Code(to_flow_yaml(value)));
+    }
+    inlines
+}
+
+fn range_phrase(pattern: &str) -> Vec<Inline> {
+    let Some(range) = RangePattern::parse(pattern) else {
+        return vec![text("a number in the range "), Inline::Code(pattern.into())];
+    };
+    let (mut phrase, after, before) = match range.kind {
+        RangeKind::Int => (String::from("an integer"), "greater than", "less than"),
+        RangeKind::Float => (String::from("a number"), "greater than", "less than"),
+        RangeKind::Timestamp => (String::from("a timestamp"), "later than", "earlier than"),
+    };
+    if let Some(minimum) = range.minimum {
+        phrase.push_str(&format!(" {after} "));
+        if minimum.inclusive {
+            phrase.push_str("or equal to ");
+        }
+        phrase.push_str(&range.format_bound(&minimum));
+    }
+    if let Some(maximum) = range.maximum {
+        if range.minimum.is_some() {
+            phrase.push_str(" and");
+        }
+        phrase.push_str(&format!(" {before} "));
+        if maximum.inclusive {
+            phrase.push_str("or equal to ");
+        }
+        phrase.push_str(&range.format_bound(&maximum));
+    }
+    vec![text(&phrase)]
+}
+
+// A key of a map checker, with the merged checkers flattened
+struct KeyDoc<'a> {
+    name: &'a str,
+    expression: &'a Yaml,
+    required: bool,
+    default: Option<&'a Yaml>,
+    // The merged rule which declares the key
+    origin: Option<&'a str>,
+}
+
+struct Generator<'a> {
+    rule_set: &'a HashMap<Box<str>, Rule>,
+}
+
+impl<'a> Generator<'a> {
+    fn rule(&self, rule: &Rule) -> Vec<Block> {
+        let mut blocks = vec![Block::Heading(
+            2,
+            rule.name.to_string(),
+            Some(rule.name.to_string()),
+        )];
+        let annotations = Annotations::from_expression(&rule.node);
+        if let Some(title) = &annotations.title {
+            blocks.push(Block::Paragraph(vec![Inline::Strong(title.to_string())]));
+        }
+        if annotations.deprecated {
+            blocks.push(Block::Paragraph(vec![Inline::Strong("Deprecated.".into())]));
+        }
+        if let Some(description) = &annotations.description {
+            blocks.push(Block::Paragraph(vec![text(description)]));
+        }
+
+        if let Some(name) = rule
+            .node
+            .data
+            .as_str()
+            .filter(|n| self.rule_set.contains_key(*n))
+        {
+            blocks.push(Block::Paragraph(vec![
+                text("Same as "),
+                Inline::RuleLink(name.into()),
+                text("."),
+            ]));
+        } else {
+            let (summary, details) = self.describe(&rule.node);
+            blocks.push(sentence(summary));
+            blocks.extend(details);
+        }
+
+        if !annotations.examples.is_empty() {
+            let label = if annotations.examples.len() == 1 {
+                "Example:"
+            } else {
+                "Examples:"
+            };
+            blocks.push(Block::Paragraph(vec![text(label)]));
+            let examples: Vec<String> = annotations.examples.iter().map(to_flow_yaml).collect();
+            blocks.push(Block::Code(examples.join("\n")));
+        }
+        blocks
+    }
+
+    // A phrase summarizing the expression, and the blocks detailing it
+    fn describe(&self, node: &Yaml) -> (Vec<Inline>, Vec<Block>) {
+        let mapping = match &node.data {
+            YamlData::String(name) => {
+                let summary = if self.rule_set.contains_key(name.as_str()) {
+                    Inline::RuleLink(name.clone())
+                } else if let Some(description) = predefined_rule_description(name) {
+                    text(description)
+                } else {
+                    Inline::Code(name.clone())
+                };
+                return (vec![summary], Vec::new());
+            }
+            YamlData::Mapping(mapping) => mapping,
+            _ => return (vec![Inline::Code(to_flow_yaml(node))], Vec::new()),
+        };
+        let keyword = |name: &str| extract_kv_entry(mapping, name);
+
+        if let Some(checker) = RegexChecker::from_mapping(mapping) {
+            let mut summary = vec![
+                text(if checker.full_match {
+                    "a string fully matching the regular expression "
+                } else {
+                    "a string matching the regular expression "
+                }),
+                Inline::Code(checker.pattern.into()),
+            ];
+            if !checker.flags.is_empty() {
+                summary.push(text(" with the flags "));
+                summary.push(Inline::Code(checker.flags.into()));
+            }
+            let blocks = self.size(mapping, ("character", "characters"));
+            return (summary, blocks.into_iter().collect());
+        }
+        if let Some(expression) = keyword("_string") {
+            let (summary, mut blocks) = self.describe(expression);
+            blocks.extend(self.size(mapping, ("character", "characters")));
+            return (summary, blocks);
+        }
+        if let Some(Yaml {
+            data: YamlData::List(values),
+            ..
+        }) = keyword("_in")
+        {
+            let mut summary = vec![text("one of ")];
+            summary.extend(code_list(values));
+            return (summary, Vec::new());
+        }
+        if let Some(value) = keyword("_const") {
+            let summary = vec![text("exactly "), Inline::Code(to_flow_yaml(value))];
+            return (summary, Vec::new());
+        }
+        if let Some(space) = keyword("_defines") {
+            let summary = vec![
+                text("a name of a "),
+                Inline::Code(space.data.as_str().unwrap_or_default().into()),
+            ];
+            return (summary, Vec::new());
+        }
+        if let Some(space) = keyword("_references") {
+            let summary = vec![
+                text("the name of a "),
+                Inline::Code(space.data.as_str().unwrap_or_default().into()),
+                text(" defined in the document"),
+            ];
+            return (summary, Vec::new());
+        }
+        if let Some(range) = keyword("_range") {
+            return (
+                range_phrase(range.data.as_str().unwrap_or_default()),
+                Vec::new(),
+            );
+        }
+        if let Some(Yaml {
+            data: YamlData::List(alternatives),
+            ..
+        }) = keyword("_oneOf")
+        {
+            let items = alternatives
+                .iter()
+                .map(|alternative| self.item(Vec::new(), alternative))
+                .collect();
+            return (
+                vec![text("one of the alternatives below")],
+                vec![Block::List(items)],
+            );
+        }
+        if let Some(Yaml {
+            data: YamlData::List(expressions),
+            ..
+        }) = keyword("_allOf")
+        {
+            let items = expressions
+                .iter()
+                .map(|expression| self.item(Vec::new(), expression))
+                .collect();
+            return (
+                vec![text("a value matching all the expressions below")],
+                vec![Block::List(items)],
+            );
+        }
+        if let Some(expression) = keyword("_not") {
+            let (inner, blocks) = self.describe(expression);
+            let mut summary = vec![text("anything but ")];
+            summary.extend(inner);
+            return (summary, blocks);
+        }
+        if let Some((key, cases)) = read_discriminator(mapping) {
+            let items = cases
+                .iter()
+                .map(|(value, expression)| {
+                    let prefix = vec![
+                        text("when "),
+                        Inline::Code(key.into()),
+                        text(" is "),
+                        Inline::Code(to_flow_yaml(value)),
+                        text(": "),
+                    ];
+                    self.item(prefix, expression)
+                })
+                .collect();
+            return (
+                vec![
+                    text("a mapping whose "),
+                    Inline::Code(key.into()),
+                    text(" key selects one of the cases below"),
+                ],
+                vec![Block::List(items)],
+            );
+        }
+        if let Some(Yaml {
+            data: YamlData::List(pairs),
+            ..
+        }) = keyword("_ifThen")
+        {
+            let mut items = Vec::new();
+            for pair in pairs {
+                if let YamlData::List(pair) = &pair.data {
+                    if let [test, then] = &pair[..] {
+                        let (test_summary, _) = self.describe(test);
+                        let mut prefix = vec![text("when the value is ")];
+                        prefix.extend(test_summary);
+                        prefix.push(text(": "));
+                        items.push(self.item(prefix, then));
+                    }
+                }
+            }
+            return (
+                vec![text("the first matching case below")],
+                vec![Block::List(items)],
+            );
+        }
+
+        let is_map = ["_map", "_mapFacultative", "_mapOf", "_merge"]
+            .iter()
+            .any(|k| keyword(k).is_some());
+        if is_map {
+            return (vec![text("a mapping")], self.map_checker(mapping));
+        }
+        let is_list = ["_list", "_listFacultative", "_listOf"]
+            .iter()
+            .any(|k| keyword(k).is_some());
+        if is_list {
+            return (vec![text("a list")], self.list_checker(mapping));
+        }
+        (vec![text("an invalid expression")], Vec::new())
+    }
+
+    // A list item describing an expression, with its annotations
+    fn item(&self, mut prefix: Vec<Inline>, node: &Yaml) -> Item {
+        let (summary, children) = self.describe(node);
+        prefix.extend(summary);
+
+        let annotations = Annotations::from_expression(node);
+        if annotations.deprecated {
+            prefix.push(text(" "));
+            prefix.push(Inline::Strong("(deprecated)".into()));
+        }
+        if let Some(title) = &annotations.title {
+            prefix.push(text(" — "));
+            prefix.push(Inline::Strong(title.to_string()));
+        }
+        if let Some(description) = &annotations.description {
+            prefix.push(text(" — "));
+            prefix.push(text(description));
+        }
+        if !annotations.examples.is_empty() {
+            prefix.push(text(" Examples: "));
+            prefix.extend(code_list(&annotations.examples));
+        }
+        Item {
+            text: prefix,
+            children,
+        }
+    }
+
+    fn collect_keys(
+        &self,
+        mapping: &'a Vec<(Yaml, Yaml)>,
+        origin: Option<&'a str>,
+        keys: &mut Vec<KeyDoc<'a>>,
+        map_of: &mut Vec<(&'a Yaml, &'a Yaml, Option<&'a str>)>,
+    ) {
+        let defaults = match extract_kv_entry(mapping, "_mapDefault") {
+            Some(Yaml {
+                data: YamlData::Mapping(defaults),
+                ..
+            }) => Some(defaults),
+            _ => None,
+        };
+        for (keyword, required) in [("_map", true), ("_mapFacultative", false)] {
+            if let Some(Yaml {
+                data: YamlData::Mapping(entries),
+                ..
+            }) = extract_kv_entry(mapping, keyword)
+            {
+                for (key, expression) in entries {
+                    let Some(name) = key.data.as_str() else {
+                        continue;
+                    };
+                    if let Some(known) = keys.iter_mut().find(|k| k.name == name) {
+                        // _map entries win over _mapFacultative ones
+                        if required && !known.required {
+                            known.required = true;
+                            known.expression = expression;
+                        }
+                        continue;
+                    }
+                    keys.push(KeyDoc {
+                        name,
+                        expression,
+                        required,
+                        default: defaults.and_then(|d| extract_kv_entry(d, name)),
+                        origin,
+                    });
+                }
+            }
+        }
+        if let Some(Yaml {
+            data: YamlData::Mapping(associations),
+            ..
+        }) = extract_kv_entry(mapping, "_mapOf")
+        {
+            for (key, value) in associations {
+                map_of.push((key, value, origin));
+            }
+        }
+        if let Some(Yaml {
+            data: YamlData::List(merge_list),
+            ..
+        }) = extract_kv_entry(mapping, "_merge")
+        {
+            for merged in merge_list {
+                if let Ok(merged_mapping) = resolve_merge_reference(self.rule_set, merged) {
+                    let merged_origin = merged.data.as_str().or(origin);
+                    self.collect_keys(merged_mapping, merged_origin, keys, map_of);
+                }
+            }
+        }
+    }
+
+    fn map_checker(&self, mapping: &'a Vec<(Yaml, Yaml)>) -> Vec<Block> {
+        let mut keys = Vec::new();
+        let mut map_of = Vec::new();
+        self.collect_keys(mapping, None, &mut keys, &mut map_of);
+
+        let mut blocks = Vec::new();
+        for (required, label) in [(true, "Required keys:"), (false, "Optional keys:")] {
+            let items: Vec<Item> = keys
+                .iter()
+                .filter(|key| key.required == required)
+                .map(|key| {
+                    let mut item = self.item(
+                        vec![Inline::Code(key.name.into()), text(": ")],
+                        key.expression,
+                    );
+                    if let Some(default) = key.default {
+                        item.text.push(text(" (default "));
+                        item.text.push(Inline::Code(to_flow_yaml(default)));
+                        item.text.push(text(")"));
+                    }
+                    if let Some(origin) = key.origin {
+                        item.text.push(text(" (from "));
+                        item.text.push(Inline::RuleLink(origin.into()));
+                        item.text.push(text(")"));
+                    }
+                    item
+                })
+                .collect();
+            if !items.is_empty() {
+                blocks.push(Block::Paragraph(vec![text(label)]));
+                blocks.push(Block::List(items));
+            }
+        }
+        if !map_of.is_empty() {
+            let items = map_of
+                .iter()
+                .map(|(key, value, origin)| {
+                    let (key_summary, _) = self.describe(key);
+                    let mut prefix = vec![text("keys: ")];
+                    prefix.extend(key_summary);
+                    prefix.push(text(", values: "));
+                    let mut item = self.item(prefix, value);
+                    if let Some(origin) = origin {
+                        item.text.push(text(" (from "));
+                        item.text.push(Inline::RuleLink((*origin).into()));
+                        item.text.push(text(")"));
+                    }
+                    item
+                })
+                .collect();
+            blocks.push(Block::Paragraph(vec![text("Other entries:")]));
+            blocks.push(Block::List(items));
+        }
+        blocks.extend(self.size(mapping, ("entry", "entries")));
+        let constraints: Vec<Item> = describe_key_constraints(self.rule_set, mapping)
+            .into_iter()
+            .map(|constraint| Item {
+                text: vec![text(&constraint)],
+                children: Vec::new(),
+            })
+            .collect();
+        if !constraints.is_empty() {
+            blocks.push(Block::Paragraph(vec![text("Key constraints:")]));
+            blocks.push(Block::List(constraints));
+        }
+        blocks
+    }
+
+    fn list_checker(&self, mapping: &Vec<(Yaml, Yaml)>) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        let mut position = 0;
+        for (keyword, label) in [
+            ("_list", "Items, in order:"),
+            ("_listFacultative", "Optional items, in order, after these:"),
+        ] {
+            if let Some(Yaml {
+                data: YamlData::List(expressions),
+                ..
+            }) = extract_kv_entry(mapping, keyword)
+            {
+                let items: Vec<Item> = expressions
+                    .iter()
+                    .map(|expression| {
+                        position += 1;
+                        self.item(vec![text(&format!("item {position}: "))], expression)
+                    })
+                    .collect();
+                if keyword == "_listFacultative" && position == items.len() {
+                    blocks.push(Block::Paragraph(vec![text("Optional items, in order:")]));
+                } else {
+                    blocks.push(Block::Paragraph(vec![text(label)]));
+                }
+                blocks.push(Block::List(items));
+            }
+        }
+        if let Some(expression) = extract_kv_entry(mapping, "_listOf") {
+            let label = if position > 0 {
+                "Each other item:"
+            } else {
+                "Each item:"
+            };
+            blocks.push(Block::Paragraph(vec![text(label)]));
+            blocks.push(Block::List(vec![self.item(Vec::new(), expression)]));
+        }
+        blocks.extend(self.size(mapping, ("item", "items")));
+        if let Some(YamlData::Boolean(true)) = extract_kv_entry(mapping, "_unique").map(|u| &u.data)
+        {
+            blocks.push(Block::Paragraph(vec![text("No two items are equal.")]));
+        }
+        if let Some(key) = extract_kv_entry(mapping, "_uniqueBy").and_then(|k| k.data.as_str()) {
+            blocks.push(Block::Paragraph(vec![
+                text("No two items have the same "),
+                Inline::Code(key.into()),
+                text("."),
+            ]));
+        }
+        blocks
+    }
+
+    // `unit` is the singular and plural name of what is counted
+    fn size(&self, mapping: &Vec<(Yaml, Yaml)>, unit: (&str, &str)) -> Option<Block> {
+        let size = |keyword: &str| extract_kv_entry(mapping, keyword).and_then(|n| n.data.as_i64());
+        let count = |n: i64| format!("{n} {}", if n == 1 { unit.0 } else { unit.1 });
+        let phrase = match (size("_nb"), size("_min"), size("_max")) {
+            (Some(nb), _, _) => format!("exactly {}", count(nb)),
+            (None, Some(min), Some(max)) => format!("between {min} and {max} {}", unit.1),
+            (None, Some(min), None) => format!("at least {}", count(min)),
+            (None, None, Some(max)) => format!("at most {}", count(max)),
+            (None, None, None) => return None,
+        };
+        Some(sentence(vec![text(&phrase)]))
+    }
+}
@@ -0,0 +1,27 @@
+mod generate;
+mod render;
+
+pub use generate::{generate_documentation, DocFormat};
+
+// A format-neutral model of the generated documentation
+
+enum Inline {
+    Text(String),
+    Strong(String),
+    Code(String),
+    // A link to the section of a rule
+    RuleLink(String),
+}
+
+struct Item {
+    text: Vec<Inline>,
+    children: Vec<Block>,
+}
+
+enum Block {
+    // A heading, with the anchor other sections can link to
+    Heading(usize, String, Option<String>),
+    Paragraph(Vec<Inline>),
+    List(Vec<Item>),
+    Code(String),
+}
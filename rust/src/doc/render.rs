@@ -0,0 +1,144 @@
+use super::{Block, Inline};
+
+pub fn render_markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    markdown_blocks(&mut out, blocks, "");
+    out
+}
+
+fn markdown_blocks(out: &mut String, blocks: &[Block], indent: &str) {
+    for (k, block) in blocks.iter().enumerate() {
+        if k > 0 {
+            out.push('\n');
+        }
+        match block {
+            Block::Heading(level, title, anchor) => {
+                if let Some(anchor) = anchor {
+                    out.push_str(&format!("{indent}<a id=\"{anchor}\"></a>\n\n"));
+                }
+                out.push_str(&format!("{indent}{} {title}\n", "#".repeat(*level)));
+            }
+            Block::Paragraph(inlines) => {
+                out.push_str(indent);
+                out.push_str(&markdown_inlines(inlines));
+                out.push('\n');
+            }
+            Block::List(items) => {
+                for item in items {
+                    out.push_str(&format!("{indent}- {}\n", markdown_inlines(&item.text)));
+                    if !item.children.is_empty() {
+                        out.push('\n');
+                        markdown_blocks(out, &item.children, &format!("{indent}  "));
+                    }
+                }
+            }
+            Block::Code(code) => {
+                out.push_str(&format!("{indent}```yaml\n"));
+                for line in code.lines() {
+                    out.push_str(&format!("{indent}{line}\n"));
+                }
+                out.push_str(&format!("{indent}```\n"));
+            }
+        }
+    }
+}
+
+fn markdown_inlines(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => text.clone(),
+            Inline::Strong(text) => format!("**{text}**"),
+            Inline::Code(code) => markdown_code(code),
+            Inline::RuleLink(name) => format!("[{name}](#{name})"),
+        })
+        .collect()
+}
+
+// Use a fence longer than any run of backticks in the code
+fn markdown_code(code: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in code.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat(longest + 1);
+    if longest > 0 || code.starts_with(' ') || code.ends_with(' ') {
+        format!("{fence} {code} {fence}")
+    } else {
+        format!("{fence}{code}{fence}")
+    }
+}
+
+pub fn render_html(title: &str, blocks: &[Block]) -> String {
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>{}</title>\n", escape(title)));
+    out.push_str("</head>\n<body>\n");
+    html_blocks(&mut out, blocks);
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn html_blocks(out: &mut String, blocks: &[Block]) {
+    for block in blocks {
+        match block {
+            Block::Heading(level, title, anchor) => {
+                let id = match anchor {
+                    Some(anchor) => format!(" id=\"{}\"", escape(anchor)),
+                    None => String::new(),
+                };
+                out.push_str(&format!("<h{level}{id}>{}</h{level}>\n", escape(title)));
+            }
+            Block::Paragraph(inlines) => {
+                out.push_str(&format!("<p>{}</p>\n", html_inlines(inlines)));
+            }
+            Block::List(items) => {
+                out.push_str("<ul>\n");
+                for item in items {
+                    out.push_str("<li>");
+                    out.push_str(&html_inlines(&item.text));
+                    if !item.children.is_empty() {
+                        out.push('\n');
+                        html_blocks(out, &item.children);
+                    }
+                    out.push_str("</li>\n");
+                }
+                out.push_str("</ul>\n");
+            }
+            Block::Code(code) => {
+                out.push_str(&format!("<pre><code>{}</code></pre>\n", escape(code)));
+            }
+        }
+    }
+}
+
+fn html_inlines(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => escape(text),
+            Inline::Strong(text) => format!("<strong>{}</strong>", escape(text)),
+            Inline::Code(code) => format!("<code>{}</code>", escape(code)),
+            Inline::RuleLink(name) => {
+                let name = escape(name);
+                format!("<a href=\"#{name}\"><code>{name}</code></a>")
+            }
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
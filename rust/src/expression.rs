@@ -1,16 +1,25 @@
 use lidy__yaml::{Yaml, YamlData};
 
+use crate::all_of::apply_all_of_matcher;
+use crate::const_::apply_const_matcher;
+use crate::discriminator::apply_discriminator_matcher;
 use crate::error::{AnyBoxedError, JoinError, SimpleError};
+use crate::if_then::apply_if_then_matcher;
 use crate::in_::apply_in_matcher;
+use crate::key_constraint::apply_key_constraints;
 use crate::list::apply_list_matcher;
 use crate::map::apply_map_matcher;
+use crate::not::apply_not_matcher;
 use crate::one_of::apply_one_of_matcher;
 use crate::parser::Parser;
 use crate::range::apply_range_matcher;
+use crate::reference::{apply_defines_matcher, apply_references_matcher};
 use crate::regex::apply_regex_matcher;
 use crate::result::LidyResult;
 use crate::rule::apply_rule;
 use crate::size::apply_size_check;
+use crate::string::apply_string_matcher;
+use crate::unique::apply_unique_check;
 
 pub fn apply_expression<TV>(
     parser: &mut Parser<TV>,
@@ -26,30 +35,58 @@ where
             let mut map_facultative = None;
             let mut map_of = None;
             let mut merge = None;
+            let mut map_default = None;
             let mut list = None;
             let mut list_facultative = None;
             let mut list_of = None;
+            let mut unique = None;
+            let mut unique_by = None;
             let mut min = None;
             let mut max = None;
             let mut nb = None;
+            let mut regex = false;
+            let mut string = None;
 
             for (key, value) in mapping {
                 if let YamlData::String(key_str) = &key.data {
                     match key_str.as_str() {
-                        "_regex" => return apply_regex_matcher(parser, value, content),
+                        "_regex" => regex = true,
+                        // Options of _regex
+                        "_regexFlags" | "_regexFullMatch" => {}
+                        "_string" => string = Some(value),
                         "_in" => return apply_in_matcher(parser, value, content),
+                        "_const" => return apply_const_matcher(parser, value, content),
                         "_range" => return apply_range_matcher(parser, value, content),
                         "_oneOf" => return apply_one_of_matcher(parser, value, content),
+                        "_ifThen" => return apply_if_then_matcher(parser, value, content),
+                        "_allOf" => return apply_all_of_matcher(parser, value, content),
+                        "_not" => return apply_not_matcher(parser, value, content),
+                        "_defines" => return apply_defines_matcher(parser, value, content),
+                        "_references" => return apply_references_matcher(parser, value, content),
+                        "_discriminator" => {
+                            return apply_discriminator_matcher(parser, mapping, content)
+                        }
+                        // Read along with _discriminator
+                        "_cases" => {}
                         "_map" => map = Some(value),
                         "_mapFacultative" => map_facultative = Some(value),
                         "_mapOf" => map_of = Some(value),
                         "_merge" => merge = Some(value),
+                        "_mapDefault" => map_default = Some(value),
+                        // Read by apply_key_constraints, with those of the
+                        // merged checkers
+                        "_dependentRequired" | "_exactlyOneOf" | "_atMostOneOf"
+                        | "_mutuallyExclusive" => {}
                         "_list" => list = Some(value),
                         "_listFacultative" => list_facultative = Some(value),
                         "_listOf" => list_of = Some(value),
+                        "_unique" => unique = Some(value),
+                        "_uniqueBy" => unique_by = Some(value),
                         "_min" => min = Some(value),
                         "_max" => max = Some(value),
                         "_nb" => nb = Some(value),
+                        // Annotations do not constrain the content
+                        "_title" | "_description" | "_examples" | "_deprecated" => {}
                         _ => {
                             return Err(SimpleError::from_message(
                                 format!("Unknown keyword found in matcher: '{key_str}'").into(),
@@ -60,6 +97,18 @@ where
                 }
             }
 
+            // String checkers, whose size is their number of characters
+            if regex || string.is_some() {
+                let result = match string {
+                    Some(expression) => apply_string_matcher(parser, expression, content)?,
+                    None => apply_regex_matcher(parser, mapping, content)?,
+                };
+                if let Some(error) = apply_size_check(content, min, max, nb) {
+                    return Err(error);
+                }
+                return Ok(result);
+            }
+
             let mut join_error = JoinError::default();
 
             let is_mapping =
@@ -82,6 +131,7 @@ where
                     map_facultative,
                     map_of,
                     merge,
+                    map_default,
                     content,
                 )?)
             } else if is_list {
@@ -107,6 +157,16 @@ where
                 }
             }
 
+            if let Some(error) = apply_unique_check(content, unique, unique_by) {
+                join_error.add(error);
+            }
+
+            if is_mapping {
+                if let Some(error) = apply_key_constraints(&parser.rule_set, mapping, content) {
+                    join_error.add(error);
+                }
+            }
+
             join_error.into_result()?;
 
             Ok(result.unwrap())
@@ -2,10 +2,23 @@ use std::fs;
 
 use crate::error::AnyBoxedError;
 
+/// The syntax of a file's content. JSON and TOML documents are read by
+/// dedicated front ends rather than by the YAML scanner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FileFormat {
+    // Deduce the format from the extension of the file name, defaulting to YAML
+    #[default]
+    Auto,
+    Yaml,
+    Json,
+    Toml,
+}
+
 #[derive(Clone, Debug)]
 pub struct File {
     pub name: Box<str>,
     pub content: Box<str>,
+    pub format: FileFormat,
 }
 
 impl File {
@@ -14,8 +27,26 @@ impl File {
             Ok(content) => Ok(File {
                 name: path.into(),
                 content: content.into(),
+                format: FileFormat::Auto,
             }),
             Err(e) => Err(format!("Failed to read file {path}: {e}").into()),
         }
     }
+
+    // The format of the file, with `Auto` resolved using the file name
+    pub fn effective_format(&self) -> FileFormat {
+        match self.format {
+            FileFormat::Auto => {
+                let name = self.name.to_lowercase();
+                if name.ends_with(".json") {
+                    FileFormat::Json
+                } else if name.ends_with(".toml") {
+                    FileFormat::Toml
+                } else {
+                    FileFormat::Yaml
+                }
+            }
+            format => format,
+        }
+    }
 }
@@ -0,0 +1,104 @@
+// The fields of a cron schedule: name, minimum, maximum and value names
+const FIELDS: [(&str, u32, u32, &[&str]); 5] = [
+    ("minute", 0, 59, &[]),
+    ("hour", 0, 23, &[]),
+    ("day of month", 1, 31, &[]),
+    (
+        "month",
+        1,
+        12,
+        &[
+            "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+        ],
+    ),
+    // 7 is Sunday too
+    (
+        "day of week",
+        0,
+        7,
+        &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+    ),
+];
+
+const MACROS: [&str; 7] = [
+    "@yearly",
+    "@annually",
+    "@monthly",
+    "@weekly",
+    "@daily",
+    "@midnight",
+    "@hourly",
+];
+
+// A five-field cron schedule, as in crontab(5), or one of its @ macros
+pub fn check_cron(value: &str) -> Result<(), String> {
+    if value.starts_with('@') {
+        if !MACROS.contains(&value) {
+            return Err(format!("the macro '{value}' is unknown"));
+        }
+        return Ok(());
+    }
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    if fields.len() != FIELDS.len() {
+        return Err(format!(
+            "five fields are expected, minute, hour, day of month, month and day of week, got {}",
+            fields.len()
+        ));
+    }
+    for (field, (name, min, max, names)) in fields.into_iter().zip(FIELDS) {
+        for item in field.split(',') {
+            check_item(item, min, max, names).map_err(|e| format!("in the {name} field, {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+// *, a value or a range, with an optional step
+fn check_item(item: &str, min: u32, max: u32, names: &[&str]) -> Result<(), String> {
+    let (range, step) = match item.split_once('/') {
+        Some((range, step)) => (range, Some(step)),
+        None => (item, None),
+    };
+    if let Some(step) = step {
+        let is_positive = !step.is_empty()
+            && step.bytes().all(|b| b.is_ascii_digit())
+            && step.bytes().any(|b| b != b'0');
+        if !is_positive {
+            return Err(format!("the step '{step}' must be a positive number"));
+        }
+    }
+    if range == "*" {
+        return Ok(());
+    }
+    match range.split_once('-') {
+        Some((first, last)) => {
+            let (first, last) = (
+                value(first, min, max, names)?,
+                value(last, min, max, names)?,
+            );
+            if first > last {
+                return Err(format!("the range '{range}' is reversed"));
+            }
+        }
+        None => {
+            value(range, min, max, names)?;
+        }
+    }
+    Ok(())
+}
+
+fn value(text: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+    if let Some(k) = names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(text))
+    {
+        // The month names start at 1 and the day names at 0
+        return Ok(k as u32 + min);
+    }
+    match text.parse::<u32>() {
+        Ok(value) if text.bytes().all(|b| b.is_ascii_digit()) && (min..=max).contains(&value) => {
+            Ok(value)
+        }
+        _ => Err(format!("'{text}' is not a value from {min} to {max}")),
+    }
+}
@@ -0,0 +1,75 @@
+// Five groups of 8, 4, 4, 4 and 12 hexadecimal digits, separated by hyphens
+pub fn check_uuid(value: &str) -> Result<(), String> {
+    let groups: Vec<&str> = value.split('-').collect();
+    let lengths: Vec<usize> = groups.iter().map(|group| group.len()).collect();
+    if lengths != [8, 4, 4, 4, 12] {
+        return Err("five groups of 8, 4, 4, 4 and 12 hexadecimal digits are expected".into());
+    }
+    if !groups
+        .iter()
+        .all(|g| g.bytes().all(|b| b.is_ascii_hexdigit()))
+    {
+        return Err("only hexadecimal digits are expected between the hyphens".into());
+    }
+    Ok(())
+}
+
+// Semantic Versioning 2.0.0: MAJOR.MINOR.PATCH, then an optional pre-release
+// after a '-' and optional build metadata after a '+'
+pub fn check_semver(value: &str) -> Result<(), String> {
+    let (value, build) = match value.split_once('+') {
+        Some((value, build)) => (value, Some(build)),
+        None => (value, None),
+    };
+    let (version, pre_release) = match value.split_once('-') {
+        Some((version, pre_release)) => (version, Some(pre_release)),
+        None => (value, None),
+    };
+
+    let numbers: Vec<&str> = version.split('.').collect();
+    if numbers.len() != 3 {
+        return Err("the version must have three numbers, MAJOR.MINOR.PATCH".into());
+    }
+    for number in numbers {
+        check_numeric_identifier(number, "version number")?;
+    }
+    if let Some(pre_release) = pre_release {
+        for identifier in pre_release.split('.') {
+            check_identifier(identifier, "pre-release")?;
+            if identifier.bytes().all(|b| b.is_ascii_digit()) {
+                check_numeric_identifier(identifier, "numeric pre-release identifier")?;
+            }
+        }
+    }
+    if let Some(build) = build {
+        for identifier in build.split('.') {
+            check_identifier(identifier, "build metadata")?;
+        }
+    }
+    Ok(())
+}
+
+fn check_identifier(identifier: &str, part: &str) -> Result<(), String> {
+    if identifier.is_empty() {
+        return Err(format!("the {part} has an empty identifier"));
+    }
+    if !identifier
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    {
+        return Err(format!(
+            "the {part} identifier '{identifier}' has characters other than letters, digits and hyphens"
+        ));
+    }
+    Ok(())
+}
+
+fn check_numeric_identifier(number: &str, what: &str) -> Result<(), String> {
+    if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("the {what} '{number}' is not a decimal number"));
+    }
+    if number.len() > 1 && number.starts_with('0') {
+        return Err(format!("the {what} '{number}' has a leading zero"));
+    }
+    Ok(())
+}
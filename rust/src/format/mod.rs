@@ -0,0 +1,88 @@
+//! Predefined rules checking the format of strings, such as `email` or `uuid`
+//!
+//! Each format has its own parser rather than a regular expression, so that
+//! the error tells which part of the value is wrong.
+
+mod cron;
+mod id;
+mod net;
+mod size;
+mod time;
+
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::error::AnyBoxedError;
+use crate::result::Data;
+
+/// The predefined format rules, with what they expect
+pub const FORMAT_RULES: [(&str, &str); 14] = [
+    ("email", "an email address"),
+    ("uri", "an absolute URI"),
+    ("uuid", "a UUID"),
+    ("ipv4", "an IPv4 address"),
+    ("ipv6", "an IPv6 address"),
+    ("cidr", "an IP network in CIDR notation"),
+    ("hostname", "a host name"),
+    ("port", "a port number"),
+    ("semver", "a semantic version"),
+    ("date", "a YYYY-MM-DD date"),
+    ("time", "an HH:MM:SS time of day"),
+    ("duration", "a duration"),
+    ("byteSize", "a byte size"),
+    ("cron", "a cron schedule"),
+];
+
+pub fn is_format_rule(name: &str) -> bool {
+    FORMAT_RULES.iter().any(|(format, _)| *format == name)
+}
+
+// Whether the format rule only accepts strings
+pub fn is_string_format(name: &str) -> bool {
+    is_format_rule(name) && !matches!(name, "port" | "byteSize")
+}
+
+/// Check a value against a format rule. Port numbers are integers, byte
+/// sizes become their number of bytes and the other formats keep the string.
+pub fn apply_format_rule<TV>(name: &str, content: &Yaml) -> Result<Data<TV>, AnyBoxedError> {
+    let (_, expected) = FORMAT_RULES
+        .iter()
+        .find(|(format, _)| *format == name)
+        .unwrap_or_else(|| panic!("never, '{name}' is not a format rule"));
+    let error =
+        |reason: String| -> AnyBoxedError { format!("expected {expected} ({reason})").into() };
+
+    match (name, &content.data) {
+        ("port", YamlData::Integer(value)) => {
+            net::check_port(*value).map_err(error)?;
+            Ok(Data::Integer(*value))
+        }
+        ("port", _) => Err(format!("expected {expected}, as an integer").into()),
+        ("byteSize", YamlData::Integer(value)) if *value >= 0 => Ok(Data::Integer(*value)),
+        ("byteSize", YamlData::String(value)) => {
+            Ok(Data::Integer(size::parse_byte_size(value).map_err(error)?))
+        }
+        ("byteSize", _) => {
+            Err(format!("expected {expected}, as a string or a non-negative integer").into())
+        }
+        (_, YamlData::String(value)) => {
+            let check = match name {
+                "email" => net::check_email,
+                "uri" => net::check_uri,
+                "uuid" => id::check_uuid,
+                "ipv4" => net::check_ipv4,
+                "ipv6" => net::check_ipv6,
+                "cidr" => net::check_cidr,
+                "hostname" => net::check_hostname,
+                "semver" => id::check_semver,
+                "date" => time::check_date,
+                "time" => time::check_time,
+                "duration" => time::check_duration,
+                "cron" => cron::check_cron,
+                _ => panic!("never, '{name}' has no string check"),
+            };
+            check(value).map_err(error)?;
+            Ok(Data::String(value.clone().into()))
+        }
+        _ => Err(format!("expected {expected}, as a string").into()),
+    }
+}
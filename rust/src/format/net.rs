@@ -0,0 +1,171 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub fn check_ipv4(value: &str) -> Result<(), String> {
+    value
+        .parse::<Ipv4Addr>()
+        .map(|_| ())
+        .map_err(|_| "four decimal numbers from 0 to 255, separated by dots, are expected".into())
+}
+
+pub fn check_ipv6(value: &str) -> Result<(), String> {
+    value
+        .parse::<Ipv6Addr>()
+        .map(|_| ())
+        .map_err(|_| "eight groups of hexadecimal digits, separated by colons, are expected".into())
+}
+
+// An IPv4 or IPv6 address followed by the length of the network prefix
+pub fn check_cidr(value: &str) -> Result<(), String> {
+    let (address, prefix) = value
+        .split_once('/')
+        .ok_or("the prefix length, after a '/', is missing")?;
+    let max_prefix = if address.contains(':') {
+        check_ipv6(address)?;
+        128
+    } else {
+        check_ipv4(address)?;
+        32
+    };
+    let prefix = decimal(prefix).ok_or("the prefix length must be a decimal number")?;
+    if prefix > max_prefix {
+        return Err(format!("the prefix length must be at most {max_prefix}"));
+    }
+    Ok(())
+}
+
+// A decimal number without sign nor leading zero
+fn decimal(text: &str) -> Option<u64> {
+    let is_canonical = !text.is_empty()
+        && text.bytes().all(|b| b.is_ascii_digit())
+        && (text == "0" || !text.starts_with('0'));
+    is_canonical.then(|| text.parse().ok()).flatten()
+}
+
+// RFC 1123 host names: dot-separated labels of letters, digits and hyphens
+pub fn check_hostname(value: &str) -> Result<(), String> {
+    if value.len() > 253 {
+        return Err("a host name has at most 253 characters".into());
+    }
+    for label in value.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err("each label of a host name has from 1 to 63 characters".into());
+        }
+        if !label
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        {
+            return Err(format!(
+                "the label '{label}' has characters other than letters, digits and hyphens"
+            ));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(format!("the label '{label}' starts or ends with a hyphen"));
+        }
+    }
+    Ok(())
+}
+
+pub fn check_port(value: i64) -> Result<(), String> {
+    if (0..=65535).contains(&value) {
+        Ok(())
+    } else {
+        Err("a port number is from 0 to 65535".into())
+    }
+}
+
+// The addr-spec of RFC 5322, without comments nor quoted local parts
+pub fn check_email(value: &str) -> Result<(), String> {
+    let (local, domain) = value
+        .rsplit_once('@')
+        .ok_or("the '@' separator is missing")?;
+    if local.is_empty() || local.len() > 64 {
+        return Err("the local part, before the '@', has from 1 to 64 characters".into());
+    }
+    let is_atom_char = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c);
+    if local
+        .split('.')
+        .any(|atom| atom.is_empty() || !atom.chars().all(is_atom_char))
+    {
+        return Err("the local part, before the '@', is malformed".into());
+    }
+    if let Some(literal) = domain.strip_prefix('[').and_then(|d| d.strip_suffix(']')) {
+        return match literal.strip_prefix("IPv6:") {
+            Some(address) => check_ipv6(address),
+            None => check_ipv4(literal),
+        };
+    }
+    if !domain.contains('.') {
+        return Err("the domain must have at least two labels".into());
+    }
+    check_hostname(domain).map_err(|e| format!("in the domain, {e}"))
+}
+
+// An absolute URI, as defined by RFC 3986
+pub fn check_uri(value: &str) -> Result<(), String> {
+    let (scheme, rest) = value.split_once(':').ok_or("the scheme is missing")?;
+    let mut chars = scheme.chars();
+    let is_scheme = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+    if !is_scheme {
+        return Err(format!("'{scheme}' is not a valid scheme"));
+    }
+    let (rest, fragment) = rest.split_once('#').unwrap_or((rest, ""));
+    let (hierarchy, query) = rest.split_once('?').unwrap_or((rest, ""));
+    check_uri_chars(fragment, "/?:@")?;
+    check_uri_chars(query, "/?:@")?;
+
+    let path = match hierarchy.strip_prefix("//") {
+        Some(authority_and_path) => {
+            let end = authority_and_path
+                .find('/')
+                .unwrap_or(authority_and_path.len());
+            check_authority(&authority_and_path[..end])?;
+            &authority_and_path[end..]
+        }
+        None => hierarchy,
+    };
+    check_uri_chars(path, "/:@")
+}
+
+fn check_authority(authority: &str) -> Result<(), String> {
+    let (user_info, host_port) = match authority.rsplit_once('@') {
+        Some((user_info, host_port)) => (user_info, host_port),
+        None => ("", authority),
+    };
+    check_uri_chars(user_info, ":")?;
+    let port = if let Some(literal) = host_port.strip_prefix('[') {
+        let (address, port) = literal
+            .split_once(']')
+            .ok_or("the IP literal of the host is not closed")?;
+        check_ipv6(address)?;
+        port
+    } else {
+        let end = host_port.find(':').unwrap_or(host_port.len());
+        check_uri_chars(&host_port[..end], "")?;
+        &host_port[end..]
+    };
+    match port.strip_prefix(':') {
+        Some(port) if port.bytes().all(|b| b.is_ascii_digit()) => Ok(()),
+        None if port.is_empty() => Ok(()),
+        _ => Err("the port of the authority must be a decimal number".into()),
+    }
+}
+
+// Unreserved characters, sub-delimiters, percent-encoded bytes and the given
+// extra characters
+fn check_uri_chars(text: &str, extra: &str) -> Result<(), String> {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            let is_encoded = chars.next().is_some_and(|c| c.is_ascii_hexdigit())
+                && chars.next().is_some_and(|c| c.is_ascii_hexdigit());
+            if !is_encoded {
+                return Err("'%' must be followed by two hexadecimal digits".into());
+            }
+        } else if !(c.is_ascii_alphanumeric() || "-._~!$&'()*+,;=".contains(c) || extra.contains(c))
+        {
+            return Err(format!("the character '{c}' must be percent-encoded"));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// The multipliers of the byte size units. The B suffix is optional.
+const UNITS: [(&str, u128); 14] = [
+    ("", 1),
+    ("k", 1000),
+    ("K", 1000),
+    ("M", 1000_u128.pow(2)),
+    ("G", 1000_u128.pow(3)),
+    ("T", 1000_u128.pow(4)),
+    ("P", 1000_u128.pow(5)),
+    ("E", 1000_u128.pow(6)),
+    ("Ki", 1 << 10),
+    ("Mi", 1 << 20),
+    ("Gi", 1 << 30),
+    ("Ti", 1 << 40),
+    ("Pi", 1 << 50),
+    ("Ei", 1 << 60),
+];
+
+// A number of bytes, with an optional unit, such as 512Mi or 1.5GB. Returns
+// the number of bytes.
+pub fn parse_byte_size(value: &str) -> Result<i64, String> {
+    let end = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(end);
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let is_number = !integer.is_empty()
+        && fraction.bytes().all(|b| b.is_ascii_digit())
+        && !(number.contains('.') && fraction.is_empty());
+    if !is_number {
+        return Err("a byte size starts with a decimal number".into());
+    }
+    if integer.len() + fraction.len() > 30 {
+        return Err("the number has too many digits".into());
+    }
+    let unit_name = unit.strip_suffix('B').unwrap_or(unit);
+    let (_, multiplier) = UNITS
+        .iter()
+        .find(|(name, _)| *name == unit_name)
+        .ok_or_else(|| {
+            format!("the unit '{unit}' is unknown, a unit such as k, M, Ki or Mi is expected")
+        })?;
+
+    let mantissa: u128 = format!("{integer}{fraction}").parse().unwrap();
+    let scale = 10_u128.pow(fraction.len() as u32);
+    let bytes = mantissa
+        .checked_mul(*multiplier)
+        .ok_or("the size is too large")?;
+    if bytes % scale != 0 {
+        return Err("the size is not a whole number of bytes".into());
+    }
+    i64::try_from(bytes / scale).map_err(|_| "the size is too large".into())
+}
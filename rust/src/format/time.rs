@@ -0,0 +1,167 @@
+use chrono::NaiveDate;
+
+// A run of ASCII digits at the start of the text, and the rest of the text
+fn digits(text: &str) -> (&str, &str) {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    text.split_at(end)
+}
+
+// A number of exactly `width` digits from `min` to `max`, then the rest
+fn field<'t>(
+    text: &'t str,
+    width: usize,
+    min: u32,
+    max: u32,
+    name: &str,
+) -> Result<(u32, &'t str), String> {
+    let (number, rest) = digits(text);
+    if number.len() != width {
+        return Err(format!("the {name} must have {width} digits"));
+    }
+    let value: u32 = number.parse().unwrap();
+    if value < min || value > max {
+        return Err(format!("the {name} must be from {min} to {max}"));
+    }
+    Ok((value, rest))
+}
+
+fn separator<'t>(text: &'t str, separator: char, before: &str) -> Result<&'t str, String> {
+    text.strip_prefix(separator)
+        .ok_or_else(|| format!("a '{separator}' is expected after the {before}"))
+}
+
+// The full-date of RFC 3339: YYYY-MM-DD
+pub fn check_date(value: &str) -> Result<(), String> {
+    let (year, rest) = field(value, 4, 0, 9999, "year")?;
+    let (month, rest) = field(separator(rest, '-', "year")?, 2, 1, 12, "month")?;
+    let (day, rest) = field(separator(rest, '-', "month")?, 2, 1, 31, "day")?;
+    if !rest.is_empty() {
+        return Err("unexpected characters after the day".into());
+    }
+    if NaiveDate::from_ymd_opt(year as i32, month, day).is_none() {
+        return Err(format!("{year:04}-{month:02} has no day {day}"));
+    }
+    Ok(())
+}
+
+// The partial-time of RFC 3339, HH:MM:SS with optional fractional seconds,
+// and an optional time offset: Z or ±HH:MM
+pub fn check_time(value: &str) -> Result<(), String> {
+    let (_, rest) = field(value, 2, 0, 23, "hour")?;
+    let (_, rest) = field(separator(rest, ':', "hour")?, 2, 0, 59, "minute")?;
+    // 60 is a leap second
+    let (_, mut rest) = field(separator(rest, ':', "minute")?, 2, 0, 60, "second")?;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let (fraction, after) = digits(fraction);
+        if fraction.is_empty() {
+            return Err("digits are expected after the decimal point".into());
+        }
+        rest = after;
+    }
+    match rest.chars().next() {
+        None => Ok(()),
+        Some('Z' | 'z') if rest.len() == 1 => Ok(()),
+        Some('+' | '-') => {
+            let (_, rest) = field(&rest[1..], 2, 0, 23, "offset hour")?;
+            let (_, rest) = field(
+                separator(rest, ':', "offset hour")?,
+                2,
+                0,
+                59,
+                "offset minute",
+            )?;
+            if rest.is_empty() {
+                Ok(())
+            } else {
+                Err("unexpected characters after the time offset".into())
+            }
+        }
+        _ => Err("the time offset must be Z or ±HH:MM".into()),
+    }
+}
+
+// An ISO 8601 duration, such as P1DT12H, or a Go duration, such as 1h30m
+pub fn check_duration(value: &str) -> Result<(), String> {
+    match value.strip_prefix('P') {
+        Some(rest) => check_iso_duration(rest),
+        None => check_go_duration(value),
+    }
+}
+
+// A decimal number, with an optional fractional part after one of the
+// decimal marks, at the start of the text. Whether it has a fractional part,
+// and the rest of the text.
+fn number<'t>(text: &'t str, decimal_marks: &[char]) -> Option<(bool, &'t str)> {
+    let (integer, rest) = digits(text);
+    match rest.strip_prefix(decimal_marks) {
+        Some(fraction) => {
+            let (fraction, rest) = digits(fraction);
+            (!integer.is_empty() || !fraction.is_empty()).then_some((true, rest))
+        }
+        None => (!integer.is_empty()).then_some((false, rest)),
+    }
+}
+
+fn check_iso_duration(text: &str) -> Result<(), String> {
+    let (date, time) = match text.split_once('T') {
+        Some((date, "")) => return Err(format!("P{date}T has no time component after the T")),
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+    let mut components = 0;
+    let mut has_fraction = false;
+    for (part, units) in [(date, "YMWD"), (time.unwrap_or(""), "HMS")] {
+        let mut rest = part;
+        let mut allowed = units;
+        while !rest.is_empty() {
+            let (is_fraction, after) =
+                number(rest, &['.', ',']).ok_or("a number is expected before each unit")?;
+            let unit = after
+                .chars()
+                .next()
+                .ok_or("a unit is expected after each number")?;
+            let position = allowed
+                .find(unit)
+                .ok_or_else(|| format!("the unit '{unit}' is unknown or out of order"))?;
+            if has_fraction {
+                return Err("only the last component may have a fraction".into());
+            }
+            has_fraction = is_fraction;
+            allowed = &allowed[position + 1..];
+            rest = &after[1..];
+            components += 1;
+        }
+    }
+    if components == 0 {
+        return Err("a duration has at least one component".into());
+    }
+    Ok(())
+}
+
+fn check_go_duration(text: &str) -> Result<(), String> {
+    let text = text.strip_prefix(['+', '-']).unwrap_or(text);
+    if text == "0" {
+        return Ok(());
+    }
+    if text.is_empty() {
+        return Err("a duration has at least one component".into());
+    }
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (_, after) = number(rest, &['.']).ok_or("a number is expected before each unit")?;
+        let end = after
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(after.len());
+        let unit = &after[..end];
+        if !["ns", "us", "µs", "μs", "ms", "s", "m", "h"].contains(&unit) {
+            return Err(match unit {
+                "" => "a unit is expected after each number".into(),
+                unit => format!("the unit '{unit}' is unknown, ns, us, ms, s, m or h is expected"),
+            });
+        }
+        rest = &after[end..];
+    }
+    Ok(())
+}
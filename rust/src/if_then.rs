@@ -0,0 +1,53 @@
+use crate::{
+    error::{AnyBoxedError, JoinError, SimpleError},
+    expression::apply_expression,
+    parser::Parser,
+    LidyResult,
+};
+use lidy__yaml::{Yaml, YamlData};
+
+pub fn apply_if_then_matcher<TV>(
+    parser: &mut Parser<TV>,
+    node: &Yaml,
+    content: &Yaml,
+) -> Result<LidyResult<TV>, AnyBoxedError>
+where
+{
+    let pairs = match &node.data {
+        YamlData::List(list) => list,
+        _ => panic!("expected schema node to be a sequence for (_ifThen: <x>)"),
+    };
+
+    let mut join_error = JoinError::default();
+    join_error.add(
+        SimpleError::from_check(
+            "_ifThen",
+            &format!("none of the {} test expressions matched", pairs.len()),
+            content,
+        )
+        .into(),
+    );
+
+    for pair in pairs {
+        let (test, then) = match &pair.data {
+            YamlData::List(pair) if pair.len() == 2 => (&pair[0], &pair[1]),
+            _ => panic!("expected each entry of (_ifThen: <x>) to be a pair of expressions"),
+        };
+
+        // The result of the test is discarded, so its builders must not run,
+        // and the names it defines or references are forgotten
+        let skip_builders = parser.skip_builders;
+        let mark = parser.cross_references.mark();
+        parser.skip_builders = true;
+        let outcome = apply_expression(parser, test, content);
+        parser.skip_builders = skip_builders;
+        parser.cross_references.rollback(mark);
+
+        match outcome {
+            Ok(_) => return apply_expression(parser, then, content),
+            Err(error) => join_error.add(error),
+        }
+    }
+
+    Err(join_error.into())
+}
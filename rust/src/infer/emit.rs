@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+use lidy__yaml::{LineCol, Yaml, YamlData};
+
+use crate::format::is_format_rule;
+use crate::syaml::{extract_kv_entry, to_flow_yaml};
+
+use super::shape::{Bounds, ListShape, MapShape, Shape};
+
+// The longest tuple written as a `_list` rather than a `_listOf`
+const MAX_TUPLE_SIZE: usize = 8;
+
+const PREDEFINED_RULES: [&str; 11] = [
+    "main",
+    "string",
+    "int",
+    "float",
+    "binary",
+    "boolean",
+    "nullType",
+    "timestamp",
+    "any",
+    "anyData",
+    "never",
+];
+
+fn node(data: YamlData) -> Yaml {
+    Yaml {
+        data,
+        line_col: LineCol::default(),
+    }
+}
+
+pub fn name(name: &str) -> Yaml {
+    node(YamlData::String(name.into()))
+}
+
+fn checker(entries: Vec<(&str, Yaml)>) -> Yaml {
+    let entries = entries.into_iter().map(|(k, v)| (name(k), v)).collect();
+    node(YamlData::Mapping(entries))
+}
+
+#[derive(Default)]
+pub struct Emitter {
+    // A name for each map checker, after the first key it appears under
+    pub hints: HashMap<String, String>,
+}
+
+impl Emitter {
+    pub fn expression(&mut self, shape: &Shape, hint: &str) -> Yaml {
+        if shape.others > 0 {
+            return name("any");
+        }
+        let mut alternatives = Vec::new();
+        if shape.nulls > 0 {
+            alternatives.push(name("nullType"));
+        }
+        if shape.booleans > 0 {
+            alternatives.push(name("boolean"));
+        }
+        if shape.timestamps > 0 {
+            alternatives.push(name("timestamp"));
+        }
+        if shape.binaries > 0 {
+            alternatives.push(name("binary"));
+        }
+        match (&shape.integers, &shape.reals) {
+            // A range would lose the precision of the wide integers
+            _ if shape.wide_integers > 0 => {
+                alternatives.push(name(if shape.reals.is_some() {
+                    "float"
+                } else {
+                    "int"
+                }));
+            }
+            (None, None) => {}
+            (Some(integers), None) => {
+                let bounds = Bounds {
+                    min: integers.min as f64,
+                    max: integers.max as f64,
+                };
+                alternatives.push(range(&bounds, "int"));
+            }
+            (integers, reals) => {
+                let mut bounds: Vec<(f64, f64)> = reals.iter().map(|b| (b.min, b.max)).collect();
+                bounds.extend(integers.iter().map(|b| (b.min as f64, b.max as f64)));
+                let bounds = Bounds {
+                    min: bounds.iter().map(|b| b.0).fold(f64::INFINITY, f64::min),
+                    max: bounds.iter().map(|b| b.1).fold(f64::NEG_INFINITY, f64::max),
+                };
+                alternatives.push(range(&bounds, "float"));
+            }
+        }
+        if let Some(strings) = &shape.strings {
+            // A value which repeats across the documents suggests a closed set
+            let is_closed = !strings.overflow && strings.count > strings.distinct.len();
+            alternatives.push(if is_closed {
+                let values = strings.distinct.iter().map(|s| name(s)).collect();
+                checker(vec![("_in", node(YamlData::List(values)))])
+            } else {
+                name("string")
+            });
+        }
+        if let Some(maps) = &shape.maps {
+            alternatives.push(self.map(maps, hint));
+        }
+        if let Some(lists) = &shape.lists {
+            alternatives.push(self.list(lists, hint));
+        }
+
+        match alternatives.len() {
+            // Only the items of empty lists have no kind
+            0 => name("any"),
+            1 => alternatives.pop().unwrap(),
+            _ => checker(vec![("_oneOf", node(YamlData::List(alternatives)))]),
+        }
+    }
+
+    fn map(&mut self, maps: &MapShape, hint: &str) -> Yaml {
+        let mut map = Vec::new();
+        let mut map_facultative = Vec::new();
+        for (key, present, shape) in &maps.keys {
+            let expression = self.expression(shape, key);
+            if *present == maps.count {
+                map.push((name(key), expression));
+            } else {
+                map_facultative.push((name(key), expression));
+            }
+        }
+
+        let mut entries = Vec::new();
+        if !map.is_empty() || (map_facultative.is_empty() && maps.other_keys.is_none()) {
+            entries.push(("_map", node(YamlData::Mapping(map))));
+        }
+        if !map_facultative.is_empty() {
+            entries.push(("_mapFacultative", node(YamlData::Mapping(map_facultative))));
+        }
+        if let Some((key_shape, value_shape)) = &maps.other_keys {
+            let association = (
+                self.expression(key_shape, hint),
+                self.expression(value_shape, hint),
+            );
+            entries.push(("_mapOf", node(YamlData::Mapping(vec![association]))));
+        }
+        let expression = checker(entries);
+        self.hints
+            .entry(to_flow_yaml(&expression))
+            .or_insert_with(|| hint.into());
+        expression
+    }
+
+    fn list(&mut self, lists: &ListShape, hint: &str) -> Yaml {
+        let item_hint = singular(hint);
+        match &lists.positions {
+            // Lists of a fixed length whose positions hold different kinds
+            // of values are tuples
+            Some(positions)
+                if lists.items.kinds() > 1
+                    && !positions.is_empty()
+                    && positions.len() <= MAX_TUPLE_SIZE
+                    && positions.iter().all(|p| p.kinds() == 1) =>
+            {
+                let items = positions
+                    .iter()
+                    .map(|p| self.expression(p, &item_hint))
+                    .collect();
+                checker(vec![("_list", node(YamlData::List(items)))])
+            }
+            _ => checker(vec![("_listOf", self.expression(&lists.items, &item_hint))]),
+        }
+    }
+}
+
+// Negative bounds cannot be written in a _range; they are left open
+fn range(bounds: &Bounds<f64>, number_type: &str) -> Yaml {
+    let minimum = (bounds.min >= 0.0).then(|| format!("{} <= ", bounds.min));
+    let maximum = (bounds.max >= 0.0).then(|| format!(" <= {}", bounds.max));
+    if minimum.is_none() && maximum.is_none() {
+        return name(number_type);
+    }
+    let pattern = format!(
+        "{}{number_type}{}",
+        minimum.unwrap_or_default(),
+        maximum.unwrap_or_default()
+    );
+    checker(vec![("_range", name(&pattern))])
+}
+
+// The name of the items of a list, after the key of the list
+fn singular(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if word.len() > 3
+        && word.ends_with('s')
+        && !["ss", "us", "is"].iter().any(|s| word.ends_with(s))
+    {
+        word[..word.len() - 1].into()
+    } else {
+        format!("{word}Item")
+    }
+}
+
+// A rule identifier, in camel case, made from a key of the documents
+fn identifier(hint: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in hint.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            if out.is_empty() && !c.is_ascii_alphabetic() {
+                continue;
+            }
+            if upper && !out.is_empty() {
+                out.push(c.to_ascii_uppercase());
+            } else {
+                out.push(c);
+            }
+            upper = false;
+        } else {
+            upper = true;
+        }
+    }
+    if out.is_empty() {
+        "structure".into()
+    } else {
+        out
+    }
+}
+
+// Give a name to each map checker which appears several times, largest
+// first, and refer to it by this name. The rules come in order of creation.
+pub fn extract_rules(main: Yaml, hints: &HashMap<String, String>) -> Vec<(String, Yaml)> {
+    let mut rules = vec![("main".to_string(), main)];
+    loop {
+        let mut counts: HashMap<String, (usize, &Yaml)> = HashMap::new();
+        for (_, body) in &rules {
+            count_map_checkers(body, &mut counts);
+        }
+        let candidate = counts
+            .iter()
+            .filter(|(_, (count, _))| *count >= 2)
+            .max_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+            .map(|(flow, (_, node))| (flow.clone(), (*node).clone()));
+        let Some((flow, checker)) = candidate else {
+            break;
+        };
+
+        let existing = rules
+            .iter()
+            .find(|(_, body)| to_flow_yaml(body) == flow)
+            .map(|(name, _)| name.clone());
+        let rule_name = match existing {
+            Some(rule_name) => rule_name,
+            None => {
+                let base = identifier(hints.get(&flow).map_or("structure", |h| h.as_str()));
+                let is_taken = |candidate: &str| {
+                    PREDEFINED_RULES.contains(&candidate)
+                        || is_format_rule(candidate)
+                        || rules.iter().any(|(name, _)| name == candidate)
+                };
+                let rule_name = std::iter::once(base.clone())
+                    .chain((2..).map(|k| format!("{base}{k}")))
+                    .find(|candidate| !is_taken(candidate))
+                    .unwrap();
+                rules.push((rule_name.clone(), checker));
+                rule_name
+            }
+        };
+        for (body_name, body) in &mut rules {
+            if *body_name != rule_name {
+                replace(body, &flow, &rule_name);
+            } else if let YamlData::Mapping(entries) = &mut body.data {
+                for (_, value) in entries {
+                    replace(value, &flow, &rule_name);
+                }
+            }
+        }
+    }
+    rules
+}
+
+fn is_map_checker(node: &Yaml) -> bool {
+    match &node.data {
+        YamlData::Mapping(entries) => {
+            extract_kv_entry(entries, "_map").is_some()
+                || extract_kv_entry(entries, "_mapFacultative").is_some()
+        }
+        _ => false,
+    }
+}
+
+fn count_map_checkers<'a>(node: &'a Yaml, counts: &mut HashMap<String, (usize, &'a Yaml)>) {
+    if is_map_checker(node) {
+        counts.entry(to_flow_yaml(node)).or_insert((0, node)).0 += 1;
+    }
+    match &node.data {
+        YamlData::Mapping(entries) => {
+            for (key, value) in entries {
+                count_map_checkers(key, counts);
+                count_map_checkers(value, counts);
+            }
+        }
+        YamlData::List(items) => items
+            .iter()
+            .for_each(|item| count_map_checkers(item, counts)),
+        _ => {}
+    }
+}
+
+fn replace(node: &mut Yaml, flow: &str, rule_name: &str) {
+    if is_map_checker(node) && to_flow_yaml(node) == flow {
+        *node = name(rule_name);
+        return;
+    }
+    match &mut node.data {
+        YamlData::Mapping(entries) => {
+            for (key, value) in entries {
+                replace(key, flow, rule_name);
+                replace(value, flow, rule_name);
+            }
+        }
+        YamlData::List(items) => items
+            .iter_mut()
+            .for_each(|item| replace(item, flow, rule_name)),
+        _ => {}
+    }
+}
@@ -0,0 +1,84 @@
+mod emit;
+mod shape;
+
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::error::AnyBoxedError;
+use crate::syaml::to_block_yaml;
+
+use emit::{extract_rules, name, Emitter};
+use shape::Shape;
+
+/// Proposes a schema accepting a corpus of sample documents
+///
+/// Keys present in every sample map go into `_map` and the others into
+/// `_mapFacultative`. Homogeneous lists become `_listOf`, and fixed-length
+/// lists of mixed values become `_list`. Strings which repeat among a few
+/// values become `_in` lists, numbers get the range of the observed values,
+/// and places which held several kinds of values become `_oneOf`. Map
+/// checkers which appear several times are extracted as named rules.
+pub struct SchemaInferrer {
+    max_in_size: usize,
+    documents: usize,
+    shape: Shape,
+}
+
+impl Default for SchemaInferrer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SchemaInferrer {
+    pub fn new() -> Self {
+        SchemaInferrer {
+            max_in_size: 5,
+            documents: 0,
+            shape: Shape::default(),
+        }
+    }
+
+    /// Set the largest number of distinct strings written as an `_in` list
+    /// rather than `string`. It applies to the documents added afterwards.
+    pub fn with_max_in_size(mut self, max_in_size: usize) -> Self {
+        self.max_in_size = max_in_size;
+        self
+    }
+
+    pub fn add_document(&mut self, document: &Yaml) {
+        self.documents += 1;
+        self.shape.observe(document, self.max_in_size);
+    }
+
+    /// Write the schema, as YAML text
+    pub fn infer(&self) -> Result<String, AnyBoxedError> {
+        if self.documents == 0 {
+            return Err("no sample document to infer a schema from".into());
+        }
+        let mut emitter = Emitter::default();
+        let main = emitter.expression(&self.shape, "main");
+        let rules = extract_rules(main, &emitter.hints)
+            .into_iter()
+            .map(|(rule_name, body)| (name(&rule_name), body))
+            .collect();
+        let schema = Yaml {
+            data: YamlData::Mapping(rules),
+            line_col: Default::default(),
+        };
+
+        let mut out = format!(
+            "# Inferred from {} sample document{}\n",
+            self.documents,
+            if self.documents == 1 { "" } else { "s" }
+        );
+        // Separate the rules with a blank line
+        for (k, line) in to_block_yaml(&schema).lines().enumerate() {
+            if k > 0 && !line.starts_with(' ') {
+                out.push('\n');
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
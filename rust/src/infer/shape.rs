@@ -0,0 +1,165 @@
+use lidy__yaml::{Yaml, YamlData};
+
+// What the sample documents showed at one place of their structure. A place
+// may hold values of several kinds across the documents.
+#[derive(Default)]
+pub struct Shape {
+    pub count: usize,
+    pub nulls: usize,
+    pub booleans: usize,
+    pub timestamps: usize,
+    pub binaries: usize,
+    // Values which no kind describes, such as aliases
+    pub others: usize,
+    pub integers: Option<Bounds<i64>>,
+    // Integers beyond the range of i64, which get no bounds
+    pub wide_integers: usize,
+    pub reals: Option<Bounds<f64>>,
+    pub strings: Option<Strings>,
+    pub maps: Option<MapShape>,
+    pub lists: Option<ListShape>,
+}
+
+pub struct Bounds<T> {
+    pub min: T,
+    pub max: T,
+}
+
+pub struct Strings {
+    pub count: usize,
+    // The distinct values in order of appearance, until there are too many
+    // for an _in list
+    pub distinct: Vec<String>,
+    pub overflow: bool,
+}
+
+#[derive(Default)]
+pub struct MapShape {
+    pub count: usize,
+    // The string keys in order of appearance, with the number of maps which
+    // have them
+    pub keys: Vec<(String, usize, Shape)>,
+    // The keys which are not strings, and their values
+    pub other_keys: Option<(Box<Shape>, Box<Shape>)>,
+}
+
+#[derive(Default)]
+pub struct ListShape {
+    pub count: usize,
+    pub items: Box<Shape>,
+    // The items by position, as long as all the lists have the same length
+    pub positions: Option<Vec<Shape>>,
+}
+
+impl<T: PartialOrd + Copy> Bounds<T> {
+    fn include(bounds: &mut Option<Bounds<T>>, value: T) {
+        match bounds {
+            Some(bounds) if value < bounds.min => bounds.min = value,
+            Some(bounds) if value > bounds.max => bounds.max = value,
+            Some(_) => {}
+            None => {
+                *bounds = Some(Bounds {
+                    min: value,
+                    max: value,
+                })
+            }
+        }
+    }
+}
+
+impl Shape {
+    pub fn observe(&mut self, node: &Yaml, max_in_size: usize) {
+        self.count += 1;
+        match &node.data {
+            YamlData::Null => self.nulls += 1,
+            YamlData::Boolean(_) => self.booleans += 1,
+            YamlData::Timestamp(_) => self.timestamps += 1,
+            YamlData::Binary(_) => self.binaries += 1,
+            YamlData::Integer(value) => Bounds::include(&mut self.integers, *value),
+            YamlData::BigInteger(_) => self.wide_integers += 1,
+            YamlData::Real(text) => match text.parse::<f64>() {
+                Ok(value) if value.is_finite() => Bounds::include(&mut self.reals, value),
+                _ => self.others += 1,
+            },
+            YamlData::String(value) => {
+                let strings = self.strings.get_or_insert_with(|| Strings {
+                    count: 0,
+                    distinct: Vec::new(),
+                    overflow: false,
+                });
+                strings.count += 1;
+                if !strings.overflow && !strings.distinct.contains(value) {
+                    if strings.distinct.len() < max_in_size {
+                        strings.distinct.push(value.clone());
+                    } else {
+                        strings.overflow = true;
+                    }
+                }
+            }
+            YamlData::Mapping(mapping) => {
+                let maps = self.maps.get_or_insert_with(MapShape::default);
+                maps.count += 1;
+                for (key, value) in mapping {
+                    let Some(name) = key.data.as_str() else {
+                        let (key_shape, value_shape) =
+                            maps.other_keys.get_or_insert_with(Default::default);
+                        key_shape.observe(key, max_in_size);
+                        value_shape.observe(value, max_in_size);
+                        continue;
+                    };
+                    let position = match maps.keys.iter().position(|(k, _, _)| k == name) {
+                        Some(position) => position,
+                        None => {
+                            maps.keys.push((name.into(), 0, Shape::default()));
+                            maps.keys.len() - 1
+                        }
+                    };
+                    let (_, present, shape) = &mut maps.keys[position];
+                    *present += 1;
+                    shape.observe(value, max_in_size);
+                }
+            }
+            YamlData::List(list) => {
+                let is_first = self.lists.is_none();
+                let lists = self.lists.get_or_insert_with(ListShape::default);
+                lists.count += 1;
+                if is_first {
+                    lists.positions = Some(list.iter().map(|_| Shape::default()).collect());
+                }
+                if lists
+                    .positions
+                    .as_ref()
+                    .is_some_and(|p| p.len() != list.len())
+                {
+                    lists.positions = None;
+                }
+                for (k, item) in list.iter().enumerate() {
+                    lists.items.observe(item, max_in_size);
+                    if let Some(positions) = &mut lists.positions {
+                        positions[k].observe(item, max_in_size);
+                    }
+                }
+            }
+            _ => self.others += 1,
+        }
+    }
+
+    // The number of kinds of values seen, each of which becomes an
+    // alternative of the inferred expression
+    pub fn kinds(&self) -> usize {
+        let numbers = self.integers.is_some() || self.wide_integers > 0 || self.reals.is_some();
+        [
+            self.nulls > 0,
+            self.booleans > 0,
+            self.timestamps > 0,
+            self.binaries > 0,
+            numbers,
+            self.strings.is_some(),
+            self.maps.is_some(),
+            self.lists.is_some(),
+        ]
+        .into_iter()
+        .filter(|&kind| kind)
+        .count()
+    }
+}
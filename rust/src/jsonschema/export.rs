@@ -0,0 +1,852 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use lidy__yaml::{JsonEmitter, LineCol, Yaml, YamlData, BAD_VALUE};
+
+use crate::discriminator::read_discriminator;
+use crate::key_constraint::collect_key_constraints;
+use crate::map::resolve_merge_reference;
+use crate::range::{RangeKind, RangePattern};
+use crate::regex::RegexChecker;
+use crate::rule::Rule;
+use crate::syaml::extract_kv_entry;
+
+const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A construct of the lidy schema which the JSON Schema cannot express
+/// exactly
+#[derive(Clone, Debug)]
+pub struct ExportWarning {
+    pub rule_name: Box<str>,
+    pub line_col: LineCol,
+    pub message: Box<str>,
+}
+
+impl fmt::Display for ExportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} {}", self.rule_name, self.message, self.line_col)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct JsonSchemaExport {
+    // The JSON Schema document, with one `$defs` entry per lidy rule
+    pub schema: Yaml,
+    pub warnings: Vec<ExportWarning>,
+}
+
+impl JsonSchemaExport {
+    pub fn to_json_string(&self) -> String {
+        JsonEmitter::dump(&self.schema)
+    }
+}
+
+/// Convert a lidy rule set to a JSON Schema 2020-12 document. The `main` rule
+/// becomes the root of the document, through a `$ref`.
+pub fn export_json_schema(rule_set: &HashMap<Box<str>, Rule>) -> JsonSchemaExport {
+    let mut exporter = Exporter {
+        rule_set,
+        rule_name: "".into(),
+        warnings: Vec::new(),
+    };
+
+    // Keep the order of the rules in the schema file
+    let mut rule_list: Vec<&Rule> = rule_set.values().collect();
+    rule_list.sort_by_key(|rule| rule.node.line_col);
+
+    let mut defs = Vec::new();
+    for rule in rule_list {
+        exporter.rule_name = rule.name.clone();
+        defs.push((string(&rule.name), exporter.expression(&rule.node)));
+    }
+
+    let mut document = vec![("$schema", string(DRAFT_2020_12))];
+    if rule_set.contains_key("main") {
+        document.push(("$ref", string("#/$defs/main")));
+    }
+    document.push(("$defs", node(YamlData::Mapping(defs))));
+
+    JsonSchemaExport {
+        schema: object(document),
+        warnings: exporter.warnings,
+    }
+}
+
+fn node(data: YamlData) -> Yaml {
+    Yaml {
+        data,
+        line_col: LineCol::default(),
+    }
+}
+
+fn string(value: &str) -> Yaml {
+    node(YamlData::String(value.into()))
+}
+
+fn integer(value: i64) -> Yaml {
+    node(YamlData::Integer(value))
+}
+
+// JSON Schema keywords are numbers; keep integers as integers
+fn number(value: f64) -> Yaml {
+    if value.trunc() == value && value.abs() < i64::MAX as f64 {
+        integer(value as i64)
+    } else {
+        node(YamlData::Real(format!("{value:?}")))
+    }
+}
+
+fn object(entries: Vec<(&str, Yaml)>) -> Yaml {
+    node(YamlData::Mapping(
+        entries
+            .into_iter()
+            .map(|(key, value)| (string(key), value))
+            .collect(),
+    ))
+}
+
+fn boolean_schema(value: bool) -> Yaml {
+    node(YamlData::Boolean(value))
+}
+
+// The names of _defines and _references are scalars
+fn scalar_schema() -> Yaml {
+    let types = ["string", "number", "boolean"].map(string);
+    object(vec![("type", node(YamlData::List(types.to_vec())))])
+}
+
+fn default_value<'a>(mapping: &'a Vec<(Yaml, Yaml)>, name: &str) -> Option<&'a Yaml> {
+    match extract_kv_entry(mapping, "_mapDefault") {
+        Some(Yaml {
+            data: YamlData::Mapping(defaults),
+            ..
+        }) => extract_kv_entry(defaults, name),
+        _ => None,
+    }
+}
+
+// Copy the annotation keywords of a checker form to its converted schema
+fn with_annotations(schema: Yaml, mapping: &Vec<(Yaml, Yaml)>) -> Yaml {
+    let mut annotations = Vec::new();
+    for (keyword, name) in [
+        ("_title", "title"),
+        ("_description", "description"),
+        ("_examples", "examples"),
+        ("_deprecated", "deprecated"),
+    ] {
+        if let Some(value) = extract_kv_entry(mapping, keyword) {
+            annotations.push((string(name), value.clone()));
+        }
+    }
+    if annotations.is_empty() {
+        return schema;
+    }
+    match schema.data {
+        YamlData::Mapping(mut entries) => {
+            entries.extend(annotations);
+            node(YamlData::Mapping(entries))
+        }
+        YamlData::Boolean(true) => node(YamlData::Mapping(annotations)),
+        _ => schema,
+    }
+}
+
+// Add a `default` annotation to a converted expression
+fn with_default(schema: Yaml, default: &Yaml) -> Yaml {
+    let default = (string("default"), default.clone());
+    match schema.data {
+        YamlData::Mapping(mut entries) => {
+            entries.push(default);
+            node(YamlData::Mapping(entries))
+        }
+        YamlData::Boolean(true) => node(YamlData::Mapping(vec![default])),
+        _ => schema,
+    }
+}
+
+// The JSON type keyword of a converted expression, if it has one
+fn json_type(schema: &Yaml) -> Option<&str> {
+    match &schema.data {
+        YamlData::Mapping(mapping) => {
+            extract_kv_entry(mapping, "type").and_then(|t| t.data.as_str())
+        }
+        _ => None,
+    }
+}
+
+struct Exporter<'a> {
+    rule_set: &'a HashMap<Box<str>, Rule>,
+    rule_name: Box<str>,
+    warnings: Vec<ExportWarning>,
+}
+
+impl<'a> Exporter<'a> {
+    fn warn(&mut self, node: &Yaml, message: &str) {
+        self.warnings.push(ExportWarning {
+            rule_name: self.rule_name.clone(),
+            line_col: node.line_col,
+            message: message.into(),
+        });
+    }
+
+    fn expression(&mut self, schema: &Yaml) -> Yaml {
+        match &schema.data {
+            YamlData::String(name) => self.rule_reference(name, schema),
+            YamlData::Mapping(mapping) => {
+                let converted = self.checker(mapping, schema);
+                with_annotations(converted, mapping)
+            }
+            _ => {
+                self.warn(
+                    schema,
+                    "invalid expression, exported as an unconstrained schema",
+                );
+                boolean_schema(true)
+            }
+        }
+    }
+
+    fn rule_reference(&mut self, name: &str, schema: &Yaml) -> Yaml {
+        if self.rule_set.contains_key(name) {
+            return object(vec![("$ref", string(&format!("#/$defs/{name}")))]);
+        }
+        match name {
+            "string" => object(vec![("type", string("string"))]),
+            "int" => object(vec![("type", string("integer"))]),
+            "float" => object(vec![("type", string("number"))]),
+            "boolean" => object(vec![("type", string("boolean"))]),
+            "nullType" => object(vec![("type", string("null"))]),
+            "timestamp" => object(vec![
+                ("type", string("string")),
+                ("format", string("date-time")),
+            ]),
+            "binary" => {
+                self.warn(
+                    schema,
+                    "JSON Schema does not validate contentEncoding; binary is exported as a plain string",
+                );
+                object(vec![
+                    ("type", string("string")),
+                    ("contentEncoding", string("base64")),
+                ])
+            }
+            "any" | "anyData" => boolean_schema(true),
+            "never" => boolean_schema(false),
+            "email" | "uri" | "uuid" | "ipv4" | "ipv6" | "hostname" | "date" | "time" => {
+                object(vec![("type", string("string")), ("format", string(name))])
+            }
+            "duration" => {
+                self.warn(
+                    schema,
+                    "the duration format of JSON Schema does not accept Go durations such as 1h30m",
+                );
+                object(vec![
+                    ("type", string("string")),
+                    ("format", string("duration")),
+                ])
+            }
+            "port" => object(vec![
+                ("type", string("integer")),
+                ("minimum", integer(0)),
+                ("maximum", integer(65535)),
+            ]),
+            "cidr" | "semver" | "byteSize" | "cron" => {
+                self.warn(
+                    schema,
+                    &format!(
+                        "JSON Schema has no {name} format; {name} is exported as a plain string"
+                    ),
+                );
+                object(vec![("type", string("string"))])
+            }
+            _ => {
+                self.warn(
+                    schema,
+                    &format!("unknown rule '{name}', exported as an unconstrained schema"),
+                );
+                boolean_schema(true)
+            }
+        }
+    }
+
+    fn checker(&mut self, mapping: &Vec<(Yaml, Yaml)>, schema: &Yaml) -> Yaml {
+        let keyword = |name: &str| extract_kv_entry(mapping, name);
+
+        if keyword("_regex").is_some() {
+            let pattern = self.regex(mapping);
+            let mut entries = vec![("type", string("string")), ("pattern", pattern)];
+            self.size_keywords(mapping, "minLength", "maxLength", &mut entries, 0);
+            return object(entries);
+        }
+        if let Some(expression) = keyword("_string") {
+            return self.string_checker(mapping, expression);
+        }
+        if let Some(values) = keyword("_in") {
+            return self.in_(values);
+        }
+        if let Some(value) = keyword("_const") {
+            return object(vec![("const", value.clone())]);
+        }
+        if let Some(range) = keyword("_range") {
+            return self.range(range);
+        }
+        if let Some(alternatives) = keyword("_oneOf") {
+            return self.one_of(alternatives);
+        }
+        if let Some(Yaml {
+            data: YamlData::List(expressions),
+            ..
+        }) = keyword("_allOf")
+        {
+            let converted = expressions.iter().map(|e| self.expression(e)).collect();
+            return object(vec![("allOf", node(YamlData::List(converted)))]);
+        }
+        if let Some(expression) = keyword("_not") {
+            let inner = self.expression(expression);
+            return object(vec![("not", inner)]);
+        }
+        if let Some(pairs) = keyword("_ifThen") {
+            return self.if_then(pairs);
+        }
+        if let Some((key, cases)) = read_discriminator(mapping) {
+            return self.discriminator(key, cases);
+        }
+        if keyword("_defines").is_some() {
+            return scalar_schema();
+        }
+        if let Some(space) = keyword("_references") {
+            self.warn(
+                space,
+                "JSON Schema cannot check that a value names a definition; _references is exported as a scalar",
+            );
+            return scalar_schema();
+        }
+
+        let is_mapping = ["_map", "_mapFacultative", "_mapOf", "_merge"]
+            .iter()
+            .any(|k| keyword(k).is_some());
+        let is_list = ["_list", "_listFacultative", "_listOf"]
+            .iter()
+            .any(|k| keyword(k).is_some());
+
+        if is_mapping {
+            self.map_checker(mapping)
+        } else if is_list {
+            self.list_checker(mapping)
+        } else {
+            self.warn(
+                schema,
+                "no keyword found in checker, exported as an unconstrained schema",
+            );
+            boolean_schema(true)
+        }
+    }
+
+    // The JSON Schema pattern of a _regex checker, with its full-match
+    // anchors
+    fn regex(&mut self, mapping: &Vec<(Yaml, Yaml)>) -> Yaml {
+        let Some(checker) = RegexChecker::from_mapping(mapping) else {
+            return string("");
+        };
+        let node = extract_kv_entry(mapping, "_regex").unwrap_or(&BAD_VALUE);
+        // RE2-only syntax which ECMA 262 regexes do not support
+        if [
+            "(?P<", "(?i", "(?m", "(?s", "(?U", "\\A", "\\z", "\\Q", "[[:",
+        ]
+        .iter()
+        .any(|construct| checker.pattern.contains(construct))
+        {
+            self.warn(
+                node,
+                "the regex uses RE2 syntax which JSON Schema (ECMA 262) regexes may not support",
+            );
+        }
+        if !checker.flags.is_empty() {
+            self.warn(
+                node,
+                &format!(
+                    "JSON Schema patterns have no flags, the flags '{}' are dropped",
+                    checker.flags
+                ),
+            );
+        }
+        if checker.full_match {
+            string(&format!("^(?:{})$", checker.pattern))
+        } else {
+            string(checker.pattern)
+        }
+    }
+
+    fn in_(&mut self, values: &Yaml) -> Yaml {
+        let list = match &values.data {
+            YamlData::List(list) => list.clone(),
+            _ => Vec::new(),
+        };
+        object(vec![("enum", node(YamlData::List(list)))])
+    }
+
+    fn range(&mut self, range: &Yaml) -> Yaml {
+        let pattern = match range.data.as_str().and_then(RangePattern::parse) {
+            Some(pattern) => pattern,
+            None => {
+                self.warn(range, "invalid range, exported as an unconstrained number");
+                return object(vec![("type", string("number"))]);
+            }
+        };
+        if pattern.kind == RangeKind::Timestamp {
+            self.warn(
+                range,
+                "JSON Schema cannot bound timestamps; the range is exported as a date-time",
+            );
+            return object(vec![
+                ("type", string("string")),
+                ("format", string("date-time")),
+            ]);
+        }
+        let mut entries = vec![(
+            "type",
+            string(if pattern.kind == RangeKind::Int {
+                "integer"
+            } else {
+                "number"
+            }),
+        )];
+        if let Some(minimum) = pattern.minimum {
+            let keyword = if minimum.inclusive {
+                "minimum"
+            } else {
+                "exclusiveMinimum"
+            };
+            entries.push((keyword, number(minimum.value)));
+        }
+        if let Some(maximum) = pattern.maximum {
+            let keyword = if maximum.inclusive {
+                "maximum"
+            } else {
+                "exclusiveMaximum"
+            };
+            entries.push((keyword, number(maximum.value)));
+        }
+        object(entries)
+    }
+
+    // `_oneOf` picks the first matching expression, which is what `anyOf`
+    // does. `oneOf` is used when the alternatives have distinct JSON types,
+    // which makes the intent clearer to JSON Schema tools.
+    fn one_of(&mut self, alternatives: &Yaml) -> Yaml {
+        let items = match &alternatives.data {
+            YamlData::List(list) => list,
+            _ => return boolean_schema(false),
+        };
+        let converted: Vec<Yaml> = items.iter().map(|item| self.expression(item)).collect();
+
+        let mut types: Vec<&str> = Vec::new();
+        let mut disjoint = true;
+        for schema in &converted {
+            match json_type(schema) {
+                Some(t) => {
+                    let overlaps = types.iter().any(|other| {
+                        *other == t
+                            || matches!((*other, t), ("integer", "number") | ("number", "integer"))
+                    });
+                    disjoint = disjoint && !overlaps;
+                    types.push(t);
+                }
+                None => disjoint = false,
+            }
+        }
+
+        let keyword = if disjoint && !converted.is_empty() {
+            "oneOf"
+        } else {
+            "anyOf"
+        };
+        object(vec![(keyword, node(YamlData::List(converted)))])
+    }
+
+    // `_ifThen` becomes a chain of if/then/else; when no test matches, the
+    // value is rejected
+    fn if_then(&mut self, pairs: &Yaml) -> Yaml {
+        let pair_list = match &pairs.data {
+            YamlData::List(list) => list,
+            _ => return boolean_schema(false),
+        };
+        let mut result = boolean_schema(false);
+        for pair in pair_list.iter().rev() {
+            if let YamlData::List(items) = &pair.data {
+                if items.len() == 2 {
+                    result = object(vec![
+                        ("if", self.expression(&items[0])),
+                        ("then", self.expression(&items[1])),
+                        ("else", result),
+                    ]);
+                }
+            }
+        }
+        result
+    }
+
+    // The discriminator key must hold one of the case values, and each case
+    // applies when the key holds its value
+    fn discriminator(&mut self, key: &str, cases: &[(Yaml, Yaml)]) -> Yaml {
+        let property = |schema: Yaml| node(YamlData::Mapping(vec![(string(key), schema)]));
+        let values = cases.iter().map(|(value, _)| value.clone()).collect();
+        let conditions = cases
+            .iter()
+            .map(|(value, expression)| {
+                let test = object(vec![("const", value.clone())]);
+                object(vec![
+                    ("if", object(vec![("properties", property(test))])),
+                    ("then", self.expression(expression)),
+                ])
+            })
+            .collect();
+        object(vec![
+            ("type", string("object")),
+            ("required", node(YamlData::List(vec![string(key)]))),
+            (
+                "properties",
+                property(object(vec![("enum", node(YamlData::List(values)))])),
+            ),
+            ("allOf", node(YamlData::List(conditions))),
+        ])
+    }
+
+    // Collect the keys of `_map` and `_mapFacultative`, flattening `_merge`
+    fn collect_keys(
+        &mut self,
+        mapping: &Vec<(Yaml, Yaml)>,
+        properties: &mut Vec<(Yaml, Yaml)>,
+        required: &mut Vec<Yaml>,
+    ) {
+        if let Some(merge) = extract_kv_entry(mapping, "_merge") {
+            if let YamlData::List(merge_list) = &merge.data {
+                for merged in merge_list {
+                    match resolve_merge_reference(self.rule_set, merged) {
+                        Ok(merged_mapping) => {
+                            if extract_kv_entry(merged_mapping, "_mapOf").is_some() {
+                                self.warn(merged, "the _mapOf of merged checkers is ignored");
+                            }
+                            self.collect_keys(merged_mapping, properties, required)
+                        }
+                        Err(e) => self.warn(merged, &e.to_string()),
+                    }
+                }
+            }
+        }
+
+        for (keyword, is_required) in [("_map", true), ("_mapFacultative", false)] {
+            if let Some(Yaml {
+                data: YamlData::Mapping(entries),
+                ..
+            }) = extract_kv_entry(mapping, keyword)
+            {
+                for (key, value) in entries {
+                    let name = match key.data.as_str() {
+                        Some(name) => name,
+                        None => continue,
+                    };
+                    let is_known = properties
+                        .iter()
+                        .any(|(k, _)| k.data.as_str() == Some(name));
+                    if is_known {
+                        // _map entries win over _mapFacultative ones
+                        if is_required {
+                            properties.retain(|(k, _)| k.data.as_str() != Some(name));
+                        } else {
+                            continue;
+                        }
+                    }
+                    let mut converted = self.expression(value);
+                    if !is_required {
+                        if let Some(default) = default_value(mapping, name) {
+                            converted = with_default(converted, default);
+                        }
+                    }
+                    properties.push((string(name), converted));
+                    if is_required && !required.iter().any(|k| k.data.as_str() == Some(name)) {
+                        required.push(string(name));
+                    }
+                }
+            }
+        }
+    }
+
+    // The expression of a _string checker, with the length bounds of the
+    // size keywords
+    fn string_checker(&mut self, mapping: &Vec<(Yaml, Yaml)>, expression: &Yaml) -> Yaml {
+        let mut entries = Vec::new();
+        self.size_keywords(mapping, "minLength", "maxLength", &mut entries, 0);
+        let inner = self.expression(expression);
+        match inner.data {
+            // Add the bounds to a schema of its own
+            YamlData::Mapping(mut inner_entries)
+                if !inner_entries.iter().any(|(key, _)| {
+                    matches!(key.data.as_str(), Some("minLength" | "maxLength" | "$ref"))
+                }) =>
+            {
+                if !inner_entries
+                    .iter()
+                    .any(|(key, _)| key.data.as_str() == Some("type"))
+                {
+                    entries.insert(0, ("type", string("string")));
+                }
+                inner_entries.extend(entries.into_iter().map(|(k, v)| (string(k), v)));
+                node(YamlData::Mapping(inner_entries))
+            }
+            _ => {
+                entries.insert(0, ("type", string("string")));
+                object(vec![(
+                    "allOf",
+                    node(YamlData::List(vec![inner, object(entries)])),
+                )])
+            }
+        }
+    }
+
+    fn size_keywords(
+        &mut self,
+        mapping: &Vec<(Yaml, Yaml)>,
+        min_keyword: &'static str,
+        max_keyword: &'static str,
+        entries: &mut Vec<(&'static str, Yaml)>,
+        min_count: usize,
+    ) {
+        let size = |keyword: &str| extract_kv_entry(mapping, keyword).and_then(|n| n.data.as_i64());
+        let nb = size("_nb");
+        let min = nb.or(size("_min")).unwrap_or(0).max(min_count as i64);
+        if min > 0 {
+            entries.push((min_keyword, integer(min)));
+        }
+        if let Some(max) = nb.or(size("_max")) {
+            entries.push((max_keyword, integer(max)));
+        }
+    }
+
+    fn map_checker(&mut self, mapping: &Vec<(Yaml, Yaml)>) -> Yaml {
+        let mut properties = Vec::new();
+        let mut required = Vec::new();
+        self.collect_keys(mapping, &mut properties, &mut required);
+
+        let mut entries = vec![("type", string("object"))];
+        let declared_names: Vec<Yaml> = properties.iter().map(|(k, _)| k.clone()).collect();
+        if !properties.is_empty() {
+            entries.push(("properties", node(YamlData::Mapping(properties))));
+        }
+        if !required.is_empty() {
+            entries.push(("required", node(YamlData::List(required))));
+        }
+
+        let mut pattern_properties = Vec::new();
+        let mut additional_properties = None;
+        let mut property_names = None;
+        if let Some(map_of) = extract_kv_entry(mapping, "_mapOf") {
+            if let YamlData::Mapping(associations) = &map_of.data {
+                for (key_schema, value_schema) in associations {
+                    let value = self.expression(value_schema);
+                    let regex = match &key_schema.data {
+                        YamlData::Mapping(m) if extract_kv_entry(m, "_regex").is_some() => {
+                            Some(self.regex(m))
+                        }
+                        _ => None,
+                    };
+                    let is_any_string =
+                        matches!(key_schema.data.as_str(), Some("string" | "any" | "anyData"));
+                    if let Some(pattern) = regex {
+                        pattern_properties.push((pattern, value));
+                    } else if is_any_string && additional_properties.is_none() {
+                        additional_properties = Some(value);
+                    } else if associations.len() == 1 {
+                        // The only association: constrain the names of the
+                        // extra keys, without constraining the declared ones
+                        if matches!(
+                            key_schema.data.as_str(),
+                            Some("int" | "float" | "boolean" | "nullType")
+                        ) {
+                            self.warn(key_schema, "JSON object keys are always strings");
+                        }
+                        let key = self.expression(key_schema);
+                        property_names = Some(if declared_names.is_empty() {
+                            key
+                        } else {
+                            object(vec![(
+                                "anyOf",
+                                node(YamlData::List(vec![
+                                    object(vec![(
+                                        "enum",
+                                        node(YamlData::List(declared_names.clone())),
+                                    )]),
+                                    key,
+                                ])),
+                            )])
+                        });
+                        additional_properties = Some(value);
+                    } else {
+                        self.warn(
+                            key_schema,
+                            "JSON Schema cannot pair this _mapOf key expression with its value; the key is not checked",
+                        );
+                        additional_properties = Some(match additional_properties.take() {
+                            None => value,
+                            Some(previous) => {
+                                object(vec![("anyOf", node(YamlData::List(vec![previous, value])))])
+                            }
+                        });
+                    }
+                }
+            }
+        }
+        if !pattern_properties.is_empty() {
+            entries.push((
+                "patternProperties",
+                node(YamlData::Mapping(pattern_properties)),
+            ));
+        }
+        if let Some(names) = property_names {
+            entries.push(("propertyNames", names));
+        }
+        entries.push((
+            "additionalProperties",
+            additional_properties.unwrap_or_else(|| boolean_schema(false)),
+        ));
+
+        self.size_keywords(mapping, "minProperties", "maxProperties", &mut entries, 0);
+        self.key_constraints(mapping, &mut entries);
+        object(entries)
+    }
+
+    // _dependentRequired maps to dependentRequired and _mutuallyExclusive to
+    // dependentSchemas; each group of _exactlyOneOf and _atMostOneOf becomes
+    // an allOf item
+    fn key_constraints(&mut self, mapping: &Vec<(Yaml, Yaml)>, entries: &mut Vec<(&str, Yaml)>) {
+        let mut constraints = Vec::new();
+        collect_key_constraints(self.rule_set, mapping, &mut constraints);
+        let required = |names: &[&Yaml]| {
+            let names = names.iter().map(|&name| name.clone()).collect();
+            object(vec![("required", node(YamlData::List(names)))])
+        };
+        let mut dependent_required = Vec::new();
+        let mut dependent_schemas = Vec::new();
+        let mut all_of = Vec::new();
+        for (keyword, value) in constraints {
+            match (keyword, &value.data) {
+                ("_dependentRequired", YamlData::Mapping(dependencies)) => {
+                    dependent_required.extend(dependencies.iter().cloned());
+                }
+                ("_exactlyOneOf", YamlData::List(groups)) => {
+                    for group in groups {
+                        let YamlData::List(names) = &group.data else {
+                            continue;
+                        };
+                        let alternatives = names.iter().map(|name| required(&[name])).collect();
+                        all_of.push(object(vec![("oneOf", node(YamlData::List(alternatives)))]));
+                    }
+                }
+                ("_atMostOneOf", YamlData::List(groups)) => {
+                    for group in groups {
+                        let YamlData::List(names) = &group.data else {
+                            continue;
+                        };
+                        let mut pairs = Vec::new();
+                        for (k, first) in names.iter().enumerate() {
+                            for second in &names[k + 1..] {
+                                pairs.push(required(&[first, second]));
+                            }
+                        }
+                        let any_pair = object(vec![("anyOf", node(YamlData::List(pairs)))]);
+                        all_of.push(object(vec![("not", any_pair)]));
+                    }
+                }
+                ("_mutuallyExclusive", YamlData::Mapping(exclusions)) => {
+                    for (key, conditions) in exclusions {
+                        let YamlData::List(conditions) = &conditions.data else {
+                            continue;
+                        };
+                        let excluded = conditions
+                            .iter()
+                            .filter_map(|condition| match &condition.data {
+                                YamlData::Mapping(pair) => {
+                                    let (name, value) = pair.first()?;
+                                    let property = object(vec![("const", value.clone())]);
+                                    Some(object(vec![
+                                        ("required", node(YamlData::List(vec![name.clone()]))),
+                                        (
+                                            "properties",
+                                            node(YamlData::Mapping(vec![(name.clone(), property)])),
+                                        ),
+                                    ]))
+                                }
+                                _ => Some(required(&[condition])),
+                            })
+                            .collect();
+                        let any_excluded = object(vec![("anyOf", node(YamlData::List(excluded)))]);
+                        dependent_schemas.push((key.clone(), object(vec![("not", any_excluded)])));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !dependent_required.is_empty() {
+            entries.push((
+                "dependentRequired",
+                node(YamlData::Mapping(dependent_required)),
+            ));
+        }
+        if !dependent_schemas.is_empty() {
+            entries.push((
+                "dependentSchemas",
+                node(YamlData::Mapping(dependent_schemas)),
+            ));
+        }
+        if !all_of.is_empty() {
+            entries.push(("allOf", node(YamlData::List(all_of))));
+        }
+    }
+
+    fn list_checker(&mut self, mapping: &Vec<(Yaml, Yaml)>) -> Yaml {
+        let mut prefix_items = Vec::new();
+        let mut required_count = 0;
+        for keyword in ["_list", "_listFacultative"] {
+            if let Some(Yaml {
+                data: YamlData::List(items),
+                ..
+            }) = extract_kv_entry(mapping, keyword)
+            {
+                if keyword == "_list" {
+                    required_count = items.len();
+                }
+                for item in items {
+                    prefix_items.push(self.expression(item));
+                }
+            }
+        }
+
+        let mut entries = vec![("type", string("array"))];
+        if !prefix_items.is_empty() {
+            entries.push(("prefixItems", node(YamlData::List(prefix_items))));
+        }
+        let items = match extract_kv_entry(mapping, "_listOf") {
+            Some(list_of) => self.expression(list_of),
+            None => boolean_schema(false),
+        };
+        entries.push(("items", items));
+        self.size_keywords(
+            mapping,
+            "minItems",
+            "maxItems",
+            &mut entries,
+            required_count,
+        );
+        if let Some(YamlData::Boolean(true)) = extract_kv_entry(mapping, "_unique").map(|u| &u.data)
+        {
+            entries.push(("uniqueItems", node(YamlData::Boolean(true))));
+        }
+        if let Some(key) = extract_kv_entry(mapping, "_uniqueBy") {
+            self.warn(
+                key,
+                "JSON Schema cannot require unique values of a key; _uniqueBy is not exported",
+            );
+        }
+        object(entries)
+    }
+}
@@ -0,0 +1,983 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use lidy__yaml::{LineCol, Yaml, YamlData};
+
+use crate::format::FORMAT_RULES;
+use crate::syaml::{extract_kv_entry, to_flow_yaml};
+
+const PREDEFINED_RULES: [&str; 10] = [
+    "string",
+    "int",
+    "float",
+    "binary",
+    "boolean",
+    "nullType",
+    "timestamp",
+    "any",
+    "anyData",
+    "never",
+];
+
+// The string formats of JSON Schema which have a lidy format rule
+const STRING_FORMATS: [&str; 9] = [
+    "email", "uri", "uuid", "ipv4", "ipv6", "hostname", "date", "time", "duration",
+];
+
+// Keywords which do not constrain the document
+const ANNOTATION_KEYWORDS: [&str; 14] = [
+    "$schema",
+    "$id",
+    "$anchor",
+    "$comment",
+    "$vocabulary",
+    "$defs",
+    "definitions",
+    "title",
+    "description",
+    "default",
+    "examples",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+];
+
+// Validation keywords which have no lidy counterpart
+const UNSUPPORTED_KEYWORDS: [&str; 15] = [
+    "if",
+    "then",
+    "else",
+    "dependentSchemas",
+    "dependencies",
+    "contains",
+    "minContains",
+    "maxContains",
+    "multipleOf",
+    "unevaluatedProperties",
+    "unevaluatedItems",
+    "contentSchema",
+    "$dynamicRef",
+    "$recursiveRef",
+    "$dynamicAnchor",
+];
+
+const OBJECT_KEYWORDS: [&str; 8] = [
+    "properties",
+    "required",
+    "dependentRequired",
+    "additionalProperties",
+    "patternProperties",
+    "propertyNames",
+    "minProperties",
+    "maxProperties",
+];
+
+const ARRAY_KEYWORDS: [&str; 6] = [
+    "items",
+    "prefixItems",
+    "additionalItems",
+    "minItems",
+    "maxItems",
+    "uniqueItems",
+];
+
+const NUMBER_KEYWORDS: [&str; 4] = ["minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum"];
+
+/// A construct of the JSON Schema which the lidy schema cannot express
+/// exactly
+#[derive(Clone, Debug)]
+pub struct ImportWarning {
+    pub rule_name: Box<str>,
+    pub line_col: LineCol,
+    pub message: Box<str>,
+}
+
+impl fmt::Display for ImportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} {}", self.rule_name, self.message, self.line_col)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct JsonSchemaImport {
+    // The text of the lidy schema, in YAML
+    pub schema: String,
+    pub warnings: Vec<ImportWarning>,
+}
+
+/// Convert a JSON Schema document (draft-07 or 2020-12) to the text of a
+/// lidy schema. The root schema becomes the `main` rule and each entry of
+/// `$defs` or `definitions` reachable from it becomes a rule of its own.
+/// Constructs which lidy cannot express become `any`, with the message of
+/// the warning as a comment.
+pub fn import_json_schema(document: &Yaml) -> JsonSchemaImport {
+    let mut importer = Importer {
+        definitions: Vec::new(),
+        pointers: HashMap::new(),
+        rules: HashMap::new(),
+        in_progress: HashSet::new(),
+        references: HashMap::new(),
+        rule_name: "main".into(),
+        warnings: Vec::new(),
+    };
+    importer.collect_definitions(document);
+
+    for index in 0..importer.definitions.len() {
+        let name = importer.definitions[index].0.clone();
+        importer.definition(&name);
+    }
+    importer.rule_name = "main".into();
+    let main = importer.schema(document);
+
+    // Rules which main does not reach would be rejected by the metaparser
+    let mut reachable = HashSet::from([Box::<str>::from("main")]);
+    let mut pending = vec![Box::<str>::from("main")];
+    while let Some(name) = pending.pop() {
+        for target in importer.references.get(&name).into_iter().flatten() {
+            if reachable.insert(target.clone()) {
+                pending.push(target.clone());
+            }
+        }
+    }
+
+    let mut writer = Writer::default();
+    writer.rule("main", &main);
+    let mut skipped = Vec::new();
+    for (name, schema) in &importer.definitions {
+        if reachable.contains(name) {
+            writer.out.push('\n');
+            writer.rule(name, &importer.rules[name]);
+        } else {
+            skipped.push(ImportWarning {
+                rule_name: name.clone(),
+                line_col: schema.line_col,
+                message: "definition is not used by the root schema and was left out".into(),
+            });
+        }
+    }
+    for warning in &skipped {
+        writer
+            .out
+            .push_str(&format!("\n# {}: {}\n", warning.rule_name, warning.message));
+    }
+    importer.warnings.extend(skipped);
+
+    JsonSchemaImport {
+        schema: writer.out,
+        warnings: importer.warnings,
+    }
+}
+
+// A lidy expression, ready to be written out
+#[derive(Clone, Debug)]
+enum Node {
+    // A rule name or a keyword
+    Plain(Box<str>),
+    // A value copied from the JSON Schema document
+    Scalar(Yaml),
+    Map(Vec<(Node, Node)>),
+    List(Vec<Node>),
+    Commented(Box<Node>, Box<str>),
+}
+
+fn plain(text: &str) -> Node {
+    Node::Plain(text.into())
+}
+
+fn string(text: &str) -> Node {
+    Node::Scalar(Yaml {
+        data: YamlData::String(text.into()),
+        line_col: LineCol::default(),
+    })
+}
+
+fn checker(keyword: &str, value: Node) -> Node {
+    Node::Map(vec![(plain(keyword), value)])
+}
+
+fn is_map_checker(node: &Node) -> bool {
+    match node {
+        Node::Map(entries) => entries.iter().all(|(key, _)| {
+            matches!(key, Node::Plain(k) if [
+                "_map",
+                "_mapFacultative",
+                "_mapOf",
+                "_merge",
+                "_min",
+                "_max",
+                "_title",
+                "_description",
+                "_deprecated",
+            ]
+            .contains(&&**k))
+        }),
+        _ => false,
+    }
+}
+
+// The annotations which lidy can carry on a checker form. Examples are left
+// out: lidy rejects examples which do not match, while JSON Schema does not
+// check them.
+fn annotations(mapping: &Vec<(Yaml, Yaml)>) -> Vec<(Node, Node)> {
+    let mut entries = Vec::new();
+    for (name, keyword) in [("title", "_title"), ("description", "_description")] {
+        if let Some(YamlData::String(text)) = extract_kv_entry(mapping, name).map(|t| &t.data) {
+            entries.push((plain(keyword), string(text)));
+        }
+    }
+    if let Some(YamlData::Boolean(deprecated)) =
+        extract_kv_entry(mapping, "deprecated").map(|d| &d.data)
+    {
+        entries.push((plain("_deprecated"), plain(&deprecated.to_string())));
+    }
+    entries
+}
+
+fn keys(mapping: &[(Yaml, Yaml)]) -> impl Iterator<Item = &str> {
+    mapping.iter().filter_map(|(key, _)| key.data.as_str())
+}
+
+// Turn a definition name into a lidy identifier
+fn rule_identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !identifier.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        identifier.insert(0, 'r');
+    }
+    identifier
+}
+
+// Decode the percent-encoding and the JSON pointer escapes of a reference
+// token
+fn decode_token(token: &str) -> String {
+    let bytes = token.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut k = 0;
+    while k < bytes.len() {
+        let hex = bytes
+            .get(k + 1..k + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[k], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                k += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                k += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded)
+        .replace("~1", "/")
+        .replace("~0", "~")
+}
+
+// Print a JSON number without a useless fractional part
+fn format_number(value: f64) -> String {
+    if value.trunc() == value && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{value}")
+    }
+}
+
+struct Importer<'a> {
+    // Rule name and schema of each definition, in document order
+    definitions: Vec<(Box<str>, &'a Yaml)>,
+    // `$ref` target to rule name
+    pointers: HashMap<String, Box<str>>,
+    rules: HashMap<Box<str>, Node>,
+    in_progress: HashSet<Box<str>>,
+    // Rules referenced by each rule
+    references: HashMap<Box<str>, HashSet<Box<str>>>,
+    rule_name: Box<str>,
+    warnings: Vec<ImportWarning>,
+}
+
+impl<'a> Importer<'a> {
+    fn collect_definitions(&mut self, document: &'a Yaml) {
+        let mut taken: HashSet<String> = PREDEFINED_RULES
+            .iter()
+            .chain(FORMAT_RULES.iter().map(|(name, _)| name))
+            .map(|&s| s.into())
+            .collect();
+        taken.insert("main".into());
+
+        let YamlData::Mapping(mapping) = &document.data else {
+            return;
+        };
+        for section in ["$defs", "definitions"] {
+            let Some(YamlData::Mapping(entries)) =
+                extract_kv_entry(mapping, section).map(|d| &d.data)
+            else {
+                continue;
+            };
+            for (key, schema) in entries {
+                let Some(name) = key.data.as_str() else {
+                    continue;
+                };
+                let base = rule_identifier(name);
+                let mut identifier = base.clone();
+                let mut n = 2;
+                while taken.contains(&identifier) {
+                    identifier = format!("{base}_{n}");
+                    n += 1;
+                }
+                taken.insert(identifier.clone());
+                let identifier: Box<str> = identifier.into();
+                self.pointers
+                    .insert(format!("/{section}/{name}"), identifier.clone());
+                self.definitions.push((identifier, schema));
+            }
+        }
+    }
+
+    // Convert the definition on first use, so that `allOf` can tell whether
+    // it is an object schema
+    fn definition(&mut self, name: &str) -> Option<&Node> {
+        if !self.rules.contains_key(name) {
+            if self.in_progress.contains(name) {
+                return None;
+            }
+            let schema = self.definitions.iter().find(|(n, _)| &**n == name)?.1;
+            self.in_progress.insert(name.into());
+            let outer = std::mem::replace(&mut self.rule_name, name.into());
+            let node = self.schema(schema);
+            self.rule_name = outer;
+            self.in_progress.remove(name);
+            self.rules.insert(name.into(), node);
+        }
+        self.rules.get(name)
+    }
+
+    fn warn(&mut self, schema: &Yaml, message: String) -> Node {
+        self.warnings.push(ImportWarning {
+            rule_name: self.rule_name.clone(),
+            line_col: schema.line_col,
+            message: message.clone().into(),
+        });
+        Node::Commented(Box::new(plain("any")), message.into())
+    }
+
+    fn schema(&mut self, schema: &Yaml) -> Node {
+        match &schema.data {
+            YamlData::Boolean(true) => plain("any"),
+            YamlData::Boolean(false) => plain("never"),
+            YamlData::Mapping(mapping) => self.schema_object(mapping, schema),
+            _ => self.warn(schema, "a schema must be an object or a boolean".into()),
+        }
+    }
+
+    fn schema_object(&mut self, mapping: &Vec<(Yaml, Yaml)>, schema: &Yaml) -> Node {
+        match self.validation(mapping, schema) {
+            Node::Map(mut entries) if !entries.is_empty() => {
+                entries.extend(annotations(mapping));
+                Node::Map(entries)
+            }
+            node => node,
+        }
+    }
+
+    fn validation(&mut self, mapping: &Vec<(Yaml, Yaml)>, schema: &Yaml) -> Node {
+        let keyword = |name: &str| extract_kv_entry(mapping, name);
+
+        let unsupported: Vec<&str> = keys(mapping)
+            .filter(|k| UNSUPPORTED_KEYWORDS.contains(k))
+            .collect();
+        if !unsupported.is_empty() {
+            return self.warn(
+                schema,
+                format!(
+                    "unsupported JSON Schema keywords: {}",
+                    unsupported.join(", ")
+                ),
+            );
+        }
+
+        // Keywords which combine with each other only through `type`
+        let validation: Vec<&str> = keys(mapping)
+            .filter(|k| !ANNOTATION_KEYWORDS.contains(k) && *k != "format")
+            .collect();
+
+        if let Some(target) = keyword("$ref") {
+            if validation.len() > 1 {
+                return self.warn(
+                    schema,
+                    "$ref combined with other keywords is not supported".into(),
+                );
+            }
+            return self.reference(target);
+        }
+
+        let combinators = ["enum", "const", "anyOf", "oneOf", "allOf", "not"];
+        let combined: Vec<&str> = validation
+            .iter()
+            .copied()
+            .filter(|k| combinators.contains(k))
+            .collect();
+        if combined.len() > 1 || (combined.len() == 1 && validation.len() > 1) {
+            if combined == ["allOf"] {
+                return self.all_of(mapping, schema);
+            }
+            if combined.len() == 1
+                && ["enum", "const"].contains(&combined[0])
+                && validation.iter().all(|k| *k == combined[0] || *k == "type")
+            {
+                // The values of an enum already have a type
+            } else {
+                return self.warn(
+                    schema,
+                    format!(
+                        "{} combined with other keywords is not supported",
+                        combined.join(", ")
+                    ),
+                );
+            }
+        }
+
+        if let Some(values) = keyword("enum") {
+            return self.enum_(values);
+        }
+        if let Some(value) = keyword("const") {
+            return self.enum_(&Yaml {
+                data: YamlData::List(vec![value.clone()]),
+                line_col: value.line_col,
+            });
+        }
+        if let Some(alternatives) = keyword("anyOf").or_else(|| keyword("oneOf")) {
+            return self.any_of(alternatives);
+        }
+        if keyword("allOf").is_some() {
+            return self.all_of(mapping, schema);
+        }
+        if let Some(negated) = keyword("not") {
+            return checker("_not", self.schema(negated));
+        }
+
+        match keyword("type").map(|t| &t.data) {
+            Some(YamlData::String(name)) => self.typed(name, mapping, schema),
+            Some(YamlData::List(names)) => {
+                let mut names: Vec<&str> = names.iter().filter_map(|n| n.data.as_str()).collect();
+                if names.contains(&"number") {
+                    names.retain(|&n| n != "integer");
+                }
+                let alternatives: Vec<Node> = names
+                    .into_iter()
+                    .map(|name| self.typed(name, mapping, schema))
+                    .collect();
+                match alternatives.len() {
+                    0 => plain("never"),
+                    1 => alternatives.into_iter().next().unwrap(),
+                    _ => checker("_oneOf", Node::List(alternatives)),
+                }
+            }
+            Some(_) => self.warn(schema, "invalid type keyword".into()),
+            None => {
+                // Without a type, infer it from the keywords
+                let has = |list: &[&str]| validation.iter().any(|k| list.contains(k));
+                let name = if has(&OBJECT_KEYWORDS) {
+                    "object"
+                } else if has(&ARRAY_KEYWORDS) {
+                    "array"
+                } else if has(&NUMBER_KEYWORDS) {
+                    "number"
+                } else if ["pattern", "minLength", "maxLength"]
+                    .iter()
+                    .any(|k| keyword(k).is_some())
+                {
+                    "string"
+                } else {
+                    return plain("any");
+                };
+                self.typed(name, mapping, schema)
+            }
+        }
+    }
+
+    fn reference(&mut self, target: &Yaml) -> Node {
+        let Some(text) = target.data.as_str() else {
+            return self.warn(target, "invalid $ref".into());
+        };
+        let pointer = match text.strip_prefix('#') {
+            Some(fragment) => fragment
+                .split('/')
+                .map(decode_token)
+                .collect::<Vec<_>>()
+                .join("/"),
+            None => {
+                return self.warn(
+                    target,
+                    format!("external reference '{text}' is not supported"),
+                )
+            }
+        };
+        let name = if pointer.is_empty() {
+            Box::<str>::from("main")
+        } else if let Some(name) = self.pointers.get(&pointer) {
+            name.clone()
+        } else {
+            return self.warn(target, format!("reference '{text}' is not supported"));
+        };
+        self.references
+            .entry(self.rule_name.clone())
+            .or_default()
+            .insert(name.clone());
+        Node::Plain(name)
+    }
+
+    fn enum_(&mut self, values: &Yaml) -> Node {
+        let YamlData::List(list) = &values.data else {
+            return self.warn(values, "enum must be an array".into());
+        };
+        if list
+            .iter()
+            .any(|v| matches!(v.data, YamlData::List(_) | YamlData::Mapping(_)))
+        {
+            // Arrays and objects are compared by value with _const
+            let mut constants: Vec<Node> = list
+                .iter()
+                .map(|value| checker("_const", Node::Scalar(value.clone())))
+                .collect();
+            return match constants.len() {
+                1 => constants.pop().unwrap(),
+                _ => checker("_oneOf", Node::List(constants)),
+            };
+        }
+        checker(
+            "_in",
+            Node::List(list.iter().cloned().map(Node::Scalar).collect()),
+        )
+    }
+
+    fn any_of(&mut self, alternatives: &Yaml) -> Node {
+        let YamlData::List(list) = &alternatives.data else {
+            return self.warn(alternatives, "anyOf and oneOf must be arrays".into());
+        };
+        checker(
+            "_oneOf",
+            Node::List(list.iter().map(|s| self.schema(s)).collect()),
+        )
+    }
+
+    // An allOf of object schemas becomes a map checker merging them, and any
+    // other allOf an _allOf checker
+    fn all_of(&mut self, mapping: &Vec<(Yaml, Yaml)>, schema: &Yaml) -> Node {
+        let Some(YamlData::List(list)) = extract_kv_entry(mapping, "allOf").map(|a| &a.data) else {
+            return self.warn(schema, "allOf must be an array".into());
+        };
+        let mut merged = Vec::new();
+        let mut mergeable = true;
+        for part in list {
+            let node = self.schema(part);
+            mergeable &= match &node {
+                Node::Plain(name) => {
+                    let name = name.clone();
+                    self.definition(&name).is_some_and(is_map_checker)
+                }
+                node => is_map_checker(node),
+            };
+            merged.push(node);
+        }
+
+        if !mergeable {
+            let rest: Vec<(Yaml, Yaml)> = mapping
+                .iter()
+                .filter(|(key, _)| key.data.as_str() != Some("allOf"))
+                .cloned()
+                .collect();
+            if keys(&rest).any(|k| !ANNOTATION_KEYWORDS.contains(&k)) {
+                let own = self.validation(&rest, schema);
+                merged.insert(0, own);
+            }
+            return checker("_allOf", Node::List(merged));
+        }
+
+        let own = self.object(mapping, schema);
+        match own {
+            Node::Map(mut entries) => {
+                // The merged schemas bring their own additional properties
+                entries.retain(|(key, _)| !matches!(key, Node::Plain(k) if &**k == "_mapOf"));
+                entries.push((plain("_merge"), Node::List(merged)));
+                Node::Map(entries)
+            }
+            own => own,
+        }
+    }
+
+    fn typed(&mut self, name: &str, mapping: &Vec<(Yaml, Yaml)>, schema: &Yaml) -> Node {
+        let keyword = |name: &str| extract_kv_entry(mapping, name);
+        match name {
+            "string" => {
+                let sizes = self.sizes(mapping, schema, "minLength", "maxLength");
+                if let Some(pattern) = keyword("pattern") {
+                    // The size keywords bound the length of a _regex string
+                    let mut entries = vec![(plain("_regex"), Node::Scalar(pattern.clone()))];
+                    entries.extend(sizes);
+                    return Node::Map(entries);
+                }
+                let expression = if keyword("format").and_then(|f| f.data.as_str())
+                    == Some("date-time")
+                {
+                    plain("timestamp")
+                } else if let Some(format) = keyword("format")
+                    .and_then(|f| f.data.as_str())
+                    .filter(|f| STRING_FORMATS.contains(f))
+                {
+                    plain(format)
+                } else if keyword("contentEncoding").and_then(|f| f.data.as_str()) == Some("base64")
+                {
+                    plain("binary")
+                } else {
+                    plain("string")
+                };
+                if sizes.is_empty() {
+                    expression
+                } else {
+                    let mut entries = vec![(plain("_string"), expression)];
+                    entries.extend(sizes);
+                    Node::Map(entries)
+                }
+            }
+            "integer" => self.number(mapping, schema, "int"),
+            "number" => self.number(mapping, schema, "float"),
+            "boolean" => plain("boolean"),
+            "null" => plain("nullType"),
+            "object" => self.object(mapping, schema),
+            "array" => self.array(mapping, schema),
+            _ => self.warn(schema, format!("unknown type '{name}'")),
+        }
+    }
+
+    fn number(&mut self, mapping: &Vec<(Yaml, Yaml)>, schema: &Yaml, rule: &str) -> Node {
+        let bound = |name: &str| extract_kv_entry(mapping, name);
+        let value = |name: &str| {
+            bound(name).and_then(|b| match b.data {
+                YamlData::Integer(i) => Some(i as f64),
+                _ => b.data.as_f64(),
+            })
+        };
+
+        // Draft-04 style boolean exclusive bounds modify minimum and maximum
+        let (minimum, minimum_operator) = match (value("exclusiveMinimum"), value("minimum")) {
+            (Some(v), _) => (Some(v), "<"),
+            (None, v) if bound("exclusiveMinimum").and_then(|b| b.data.as_bool()) == Some(true) => {
+                (v, "<")
+            }
+            (None, v) => (v, "<="),
+        };
+        let (maximum, maximum_operator) = match (value("exclusiveMaximum"), value("maximum")) {
+            (Some(v), _) => (Some(v), "<"),
+            (None, v) if bound("exclusiveMaximum").and_then(|b| b.data.as_bool()) == Some(true) => {
+                (v, "<")
+            }
+            (None, v) => (v, "<="),
+        };
+
+        if minimum.is_none() && maximum.is_none() {
+            return plain(rule);
+        }
+        if minimum.into_iter().chain(maximum).any(|v| v < 0.0) {
+            return self.warn(schema, "negative bounds are not supported by _range".into());
+        }
+        let mut range = String::new();
+        if let Some(minimum) = minimum {
+            range.push_str(&format!("{} {minimum_operator} ", format_number(minimum)));
+        }
+        range.push_str(rule);
+        if let Some(maximum) = maximum {
+            range.push_str(&format!(" {maximum_operator} {}", format_number(maximum)));
+        }
+        checker("_range", plain(&range))
+    }
+
+    fn object(&mut self, mapping: &Vec<(Yaml, Yaml)>, schema: &Yaml) -> Node {
+        let keyword = |name: &str| extract_kv_entry(mapping, name);
+
+        let required: Vec<&str> = match keyword("required").map(|r| &r.data) {
+            Some(YamlData::List(list)) => list.iter().filter_map(|r| r.data.as_str()).collect(),
+            _ => Vec::new(),
+        };
+
+        let mut map = Vec::new();
+        let mut map_facultative = Vec::new();
+        if let Some(YamlData::Mapping(properties)) = keyword("properties").map(|p| &p.data) {
+            for (key, property) in properties {
+                let Some(name) = key.data.as_str() else {
+                    continue;
+                };
+                let node = self.schema(property);
+                if required.contains(&name) {
+                    map.push((string(name), node));
+                } else {
+                    map_facultative.push((string(name), node));
+                }
+            }
+        }
+        for name in &required {
+            if !map
+                .iter()
+                .any(|(key, _)| matches!(key, Node::Scalar(k) if k.data.as_str() == Some(name)))
+            {
+                map.push((string(name), plain("any")));
+            }
+        }
+
+        let mut map_of = Vec::new();
+        if let Some(YamlData::Mapping(patterns)) = keyword("patternProperties").map(|p| &p.data) {
+            for (pattern, property) in patterns {
+                map_of.push((
+                    checker("_regex", Node::Scalar(pattern.clone())),
+                    self.schema(property),
+                ));
+            }
+        }
+        let additional = match keyword("additionalProperties") {
+            None => Some(plain("any")),
+            Some(property) => match self.schema(property) {
+                Node::Plain(name) if &*name == "never" => None,
+                node => Some(node),
+            },
+        };
+        if let Some(additional) = additional {
+            let key = match keyword("propertyNames") {
+                Some(names) => self.schema(names),
+                None => plain("string"),
+            };
+            map_of.push((key, additional));
+        }
+
+        let dependent_required = keyword("dependentRequired").and_then(|dependencies| {
+            self.dependent_required(dependencies, &map, &map_facultative, &map_of)
+        });
+
+        let mut entries = Vec::new();
+        if !map.is_empty() || (map_facultative.is_empty() && map_of.is_empty()) {
+            entries.push((plain("_map"), Node::Map(map)));
+        }
+        if !map_facultative.is_empty() {
+            entries.push((plain("_mapFacultative"), Node::Map(map_facultative)));
+        }
+        if !map_of.is_empty() {
+            entries.push((plain("_mapOf"), Node::Map(map_of)));
+        }
+        entries.extend(self.sizes(mapping, schema, "minProperties", "maxProperties"));
+        entries.extend(dependent_required);
+        Node::Map(entries)
+    }
+
+    // A closed map checker may only name its own keys in _dependentRequired
+    fn dependent_required(
+        &mut self,
+        dependent_required: &Yaml,
+        map: &[(Node, Node)],
+        map_facultative: &[(Node, Node)],
+        map_of: &[(Node, Node)],
+    ) -> Option<(Node, Node)> {
+        let YamlData::Mapping(dependencies) = &dependent_required.data else {
+            return None;
+        };
+        let is_declared = |name: &str| {
+            !map_of.is_empty()
+                || map
+                    .iter()
+                    .chain(map_facultative)
+                    .any(|(key, _)| matches!(key, Node::Scalar(k) if k.data.as_str() == Some(name)))
+        };
+        let mut entries = Vec::new();
+        for (key, required) in dependencies {
+            let (Some(name), YamlData::List(required)) = (key.data.as_str(), &required.data) else {
+                continue;
+            };
+            let names: Vec<&str> = required.iter().filter_map(|r| r.data.as_str()).collect();
+            if names.is_empty() {
+                continue;
+            }
+            if !is_declared(name) || !names.iter().all(|n| is_declared(n)) {
+                self.warn(
+                    key,
+                    format!(
+                        "dependentRequired of '{name}' names an undeclared property, it is ignored"
+                    ),
+                );
+                continue;
+            }
+            entries.push((
+                string(name),
+                Node::List(names.into_iter().map(string).collect()),
+            ));
+        }
+        (!entries.is_empty()).then(|| (plain("_dependentRequired"), Node::Map(entries)))
+    }
+
+    fn array(&mut self, mapping: &Vec<(Yaml, Yaml)>, schema: &Yaml) -> Node {
+        let keyword = |name: &str| extract_kv_entry(mapping, name);
+
+        // Draft-07 uses an array of items for tuples, 2020-12 uses prefixItems
+        let (tuple, rest) = match (keyword("prefixItems"), keyword("items")) {
+            (Some(prefix), items) => (Some(prefix), items),
+            (None, Some(items)) if matches!(items.data, YamlData::List(_)) => {
+                (Some(items), keyword("additionalItems"))
+            }
+            (None, items) => (None, items),
+        };
+
+        let mut entries = Vec::new();
+        if let Some(YamlData::List(list)) = tuple.map(|t| &t.data) {
+            let min_items = keyword("minItems")
+                .and_then(|m| m.data.as_i64())
+                .unwrap_or(0);
+            let nodes: Vec<Node> = list.iter().map(|s| self.schema(s)).collect();
+            let split = (min_items.max(0) as usize).min(nodes.len());
+            let (list, list_facultative) = nodes.split_at(split);
+            if !list.is_empty() {
+                entries.push((plain("_list"), Node::List(list.to_vec())));
+            }
+            if !list_facultative.is_empty() {
+                entries.push((
+                    plain("_listFacultative"),
+                    Node::List(list_facultative.to_vec()),
+                ));
+            }
+        }
+        match rest.map(|r| self.schema(r)) {
+            Some(Node::Plain(name)) if &*name == "never" => {}
+            Some(node) => entries.push((plain("_listOf"), node)),
+            None => entries.push((plain("_listOf"), plain("any"))),
+        }
+        if entries.is_empty() {
+            entries.push((plain("_list"), Node::List(Vec::new())));
+        }
+        entries.extend(self.sizes(mapping, schema, "minItems", "maxItems"));
+        if keyword("uniqueItems").and_then(|u| u.data.as_bool()) == Some(true) {
+            entries.push((plain("_unique"), plain("true")));
+        }
+        Node::Map(entries)
+    }
+
+    fn sizes(
+        &mut self,
+        mapping: &Vec<(Yaml, Yaml)>,
+        schema: &Yaml,
+        min_keyword: &str,
+        max_keyword: &str,
+    ) -> Vec<(Node, Node)> {
+        let size = |name: &str| extract_kv_entry(mapping, name).and_then(|s| s.data.as_i64());
+        let mut entries = Vec::new();
+        // lidy sizes are positive; a minimum of zero is no constraint
+        if let Some(min) = size(min_keyword).filter(|&m| m > 0) {
+            entries.push((plain("_min"), plain(&min.to_string())));
+        }
+        if let Some(max) = size(max_keyword) {
+            if max > 0 {
+                entries.push((plain("_max"), plain(&max.to_string())));
+            } else {
+                self.warnings.push(ImportWarning {
+                    rule_name: self.rule_name.clone(),
+                    line_col: schema.line_col,
+                    message: format!("{max_keyword} of zero is not supported").into(),
+                });
+            }
+        }
+        entries
+    }
+}
+
+// Writes the lidy schema in block style
+#[derive(Default)]
+struct Writer {
+    out: String,
+}
+
+impl Writer {
+    fn rule(&mut self, name: &str, node: &Node) {
+        self.out.push_str(name);
+        self.out.push(':');
+        self.value(node, 0);
+    }
+
+    // Write a node after a `key:` or a `-`, at the given indentation
+    fn value(&mut self, node: &Node, indent: usize) {
+        match node {
+            Node::Map(entries) if entries.is_empty() => self.out.push_str(" {}\n"),
+            Node::List(items) if items.is_empty() => self.out.push_str(" []\n"),
+            Node::Map(entries) => {
+                self.out.push('\n');
+                self.entries(entries, indent + 2, false);
+            }
+            Node::List(items) => {
+                self.out.push('\n');
+                for item in items {
+                    self.out.push_str(&" ".repeat(indent + 2));
+                    self.out.push('-');
+                    match item {
+                        Node::Map(entries) if !entries.is_empty() => {
+                            self.out.push(' ');
+                            self.entries(entries, indent + 4, true);
+                        }
+                        item => self.value(item, indent + 2),
+                    }
+                }
+            }
+            Node::Commented(node, comment) => {
+                self.out.push(' ');
+                self.out.push_str(&flow(node));
+                self.out.push_str(" # ");
+                self.out.push_str(comment);
+                self.out.push('\n');
+            }
+            leaf => {
+                self.out.push(' ');
+                self.out.push_str(&flow(leaf));
+                self.out.push('\n');
+            }
+        }
+    }
+
+    fn entries(&mut self, entries: &[(Node, Node)], indent: usize, inline_first: bool) {
+        for (k, (key, value)) in entries.iter().enumerate() {
+            if !(inline_first && k == 0) {
+                self.out.push_str(&" ".repeat(indent));
+            }
+            if let Node::Map(_) | Node::List(_) = key {
+                // Complex keys, e.g. `_regex` checkers, use the explicit form
+                self.out.push_str("? ");
+                self.out.push_str(&flow(key));
+                self.out.push('\n');
+                self.out.push_str(&" ".repeat(indent));
+            } else {
+                self.out.push_str(&flow(key));
+            }
+            self.out.push(':');
+            self.value(value, indent);
+        }
+    }
+}
+
+fn flow(node: &Node) -> String {
+    match node {
+        Node::Plain(text) => text.to_string(),
+        Node::Scalar(value) => to_flow_yaml(value),
+        Node::Map(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", flow(key), flow(value)))
+                .collect();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        Node::List(items) => {
+            let items: Vec<String> = items.iter().map(flow).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Node::Commented(node, _) => flow(node),
+    }
+}
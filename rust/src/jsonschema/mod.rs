@@ -0,0 +1,5 @@
+mod export;
+mod import;
+
+pub use export::{export_json_schema, ExportWarning, JsonSchemaExport};
+pub use import::{import_json_schema, ImportWarning, JsonSchemaImport};
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::error::{AnyBoxedError, JoinError};
+use crate::map::resolve_merge_reference;
+use crate::rule::Rule;
+use crate::syaml::{extract_kv_entry, same_value, to_flow_yaml};
+use crate::SimpleError;
+
+pub const KEY_CONSTRAINT_KEYWORDS: [&str; 4] = [
+    "_dependentRequired",
+    "_exactlyOneOf",
+    "_atMostOneOf",
+    "_mutuallyExclusive",
+];
+
+// The key constraints of a map checker and of the checkers it merges, as
+// pairs of keyword and value
+pub fn collect_key_constraints<'a>(
+    rule_set: &'a HashMap<Box<str>, Rule>,
+    mapping: &'a Vec<(Yaml, Yaml)>,
+    constraints: &mut Vec<(&'a str, &'a Yaml)>,
+) {
+    for (key, value) in mapping {
+        match key.data.as_str() {
+            Some(keyword) if KEY_CONSTRAINT_KEYWORDS.contains(&keyword) => {
+                constraints.push((keyword, value))
+            }
+            Some("_merge") => {
+                if let YamlData::List(sources) = &value.data {
+                    for source in sources {
+                        if let Ok(merged) = resolve_merge_reference(rule_set, source) {
+                            collect_key_constraints(rule_set, merged, constraints);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// A condition of _mutuallyExclusive: a key name, or a map of one key to the
+// value the key must have
+pub fn key_condition(condition: &Yaml) -> Option<(&str, Option<&Yaml>)> {
+    match &condition.data {
+        YamlData::String(key) => Some((key, None)),
+        YamlData::Mapping(mapping) => match &mapping[..] {
+            [(key, value)] => Some((key.data.as_str()?, Some(value))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn describe_condition(condition: &Yaml) -> String {
+    match key_condition(condition) {
+        Some((key, None)) => format!("key '{key}'"),
+        Some((key, Some(value))) => format!("{key}: {}", to_flow_yaml(value)),
+        None => to_flow_yaml(condition),
+    }
+}
+
+// Each key constraint of a map checker as a sentence, one for each required
+// key, each group and each excluded condition
+pub fn describe_key_constraints(
+    rule_set: &HashMap<Box<str>, Rule>,
+    mapping: &Vec<(Yaml, Yaml)>,
+) -> Vec<String> {
+    let mut constraints = Vec::new();
+    collect_key_constraints(rule_set, mapping, &mut constraints);
+    let names = |list: &Yaml| -> Vec<String> {
+        match &list.data {
+            YamlData::List(items) => items.iter().map(to_flow_yaml).collect(),
+            _ => Vec::new(),
+        }
+    };
+    let mut descriptions = Vec::new();
+    for (keyword, value) in constraints {
+        match (keyword, &value.data) {
+            ("_dependentRequired", YamlData::Mapping(dependencies)) => {
+                for (key, required) in dependencies {
+                    for name in names(required) {
+                        descriptions
+                            .push(format!("key '{}' requires key '{name}'", to_flow_yaml(key)));
+                    }
+                }
+            }
+            ("_exactlyOneOf" | "_atMostOneOf", YamlData::List(groups)) => {
+                let expected = match keyword {
+                    "_exactlyOneOf" => "exactly one",
+                    _ => "at most one",
+                };
+                for group in groups {
+                    descriptions.push(format!(
+                        "{expected} of the keys {}",
+                        names(group).join(", ")
+                    ));
+                }
+            }
+            ("_mutuallyExclusive", YamlData::Mapping(exclusions)) => {
+                for (key, conditions) in exclusions {
+                    if let YamlData::List(conditions) = &conditions.data {
+                        for condition in conditions {
+                            descriptions.push(format!(
+                                "key '{}' excludes {}",
+                                to_flow_yaml(key),
+                                describe_condition(condition)
+                            ));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    descriptions
+}
+
+// Check the key constraints of a map checker against the keys of the
+// content. The errors are positioned at the content mapping
+pub fn apply_key_constraints(
+    rule_set: &HashMap<Box<str>, Rule>,
+    mapping: &Vec<(Yaml, Yaml)>,
+    content: &Yaml,
+) -> Option<AnyBoxedError> {
+    let YamlData::Mapping(entries) = &content.data else {
+        return None;
+    };
+    let mut constraints = Vec::new();
+    collect_key_constraints(rule_set, mapping, &mut constraints);
+    if constraints.is_empty() {
+        return None;
+    }
+
+    let present = |key: &str| extract_kv_entry(entries, key).is_some();
+    let holds = |condition: &Yaml| match key_condition(condition) {
+        Some((key, None)) => present(key),
+        Some((key, Some(value))) => {
+            extract_kv_entry(entries, key).is_some_and(|v| same_value(v, value))
+        }
+        None => false,
+    };
+    let mut join_error = JoinError::default();
+    let mut report = |keyword: &str, description: String| {
+        join_error.add(SimpleError::from_check(keyword, &description, content).into())
+    };
+
+    for (keyword, value) in constraints {
+        match (keyword, &value.data) {
+            ("_dependentRequired", YamlData::Mapping(dependencies)) => {
+                for (key, required) in dependencies {
+                    let (Some(key), YamlData::List(required)) = (key.data.as_str(), &required.data)
+                    else {
+                        continue;
+                    };
+                    if !present(key) {
+                        continue;
+                    }
+                    for name in required.iter().filter_map(|r| r.data.as_str()) {
+                        if !present(name) {
+                            report(
+                                keyword,
+                                format!("key '{key}' requires key '{name}', which is missing"),
+                            );
+                        }
+                    }
+                }
+            }
+            ("_exactlyOneOf" | "_atMostOneOf", YamlData::List(groups)) => {
+                for group in groups {
+                    let YamlData::List(names) = &group.data else {
+                        continue;
+                    };
+                    let names: Vec<&str> = names.iter().filter_map(|n| n.data.as_str()).collect();
+                    let found: Vec<&str> = names.iter().copied().filter(|n| present(n)).collect();
+                    let is_valid = match keyword {
+                        "_exactlyOneOf" => found.len() == 1,
+                        _ => found.len() <= 1,
+                    };
+                    if !is_valid {
+                        let expected = match keyword {
+                            "_exactlyOneOf" => "exactly one",
+                            _ => "at most one",
+                        };
+                        let found = match found.len() {
+                            0 => "none".to_string(),
+                            _ => found.join(", "),
+                        };
+                        report(
+                            keyword,
+                            format!(
+                                "{expected} of the keys {} is expected, found {found}",
+                                names.join(", ")
+                            ),
+                        );
+                    }
+                }
+            }
+            ("_mutuallyExclusive", YamlData::Mapping(exclusions)) => {
+                for (key, conditions) in exclusions {
+                    let (Some(key), YamlData::List(conditions)) =
+                        (key.data.as_str(), &conditions.data)
+                    else {
+                        continue;
+                    };
+                    if !present(key) {
+                        continue;
+                    }
+                    for condition in conditions.iter().filter(|c| holds(c)) {
+                        report(
+                            keyword,
+                            format!("key '{key}' excludes {}", describe_condition(condition)),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    join_error.into_result().err()
+}
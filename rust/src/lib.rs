@@ -1,24 +1,53 @@
+mod all_of;
+mod annotation;
 mod any;
+mod const_;
+mod diff;
+mod discriminator;
+mod doc;
 mod error;
 mod expression;
 mod file;
+mod format;
+mod if_then;
 mod in_;
+mod infer;
+mod jsonschema;
+mod key_constraint;
+mod lint;
 mod list;
 mod map;
 mod metaparser;
+mod not;
 mod one_of;
 mod parser;
 mod range;
+mod reference;
 mod regex;
 mod result;
 mod rule;
+mod sample;
 mod size;
+mod string;
 mod syaml;
+mod unique;
 mod yamlfile;
 
+pub use annotation::Annotations;
+pub use diff::{diff_rule_sets, SchemaChange, SchemaDiff};
+pub use doc::{generate_documentation, DocFormat};
 pub use error::SimpleError;
-pub use file::File;
+pub use file::{File, FileFormat};
+pub use infer::SchemaInferrer;
+pub use jsonschema::{
+    export_json_schema, import_json_schema, ExportWarning, ImportWarning, JsonSchemaExport,
+    JsonSchemaImport,
+};
+pub use lint::{lint_rule_set, LintFinding, Severity};
 pub use parser::Parser;
-pub use result::{KeyValueData, LidyResult, ListData, MapData, Position};
+pub use result::{Data, KeyValueData, LidyResult, ListData, MapData, Position};
 pub use rule::Rule;
+#[cfg(feature = "proptest")]
+pub use sample::sample_strategy;
+pub use sample::{mutate_document, Mutation, SampleGenerator};
 pub use yamlfile::YamlFile;
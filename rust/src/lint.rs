@@ -0,0 +1,455 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use lidy__yaml::{LineCol, Yaml, YamlData};
+use regex_syntax::hir::Look;
+
+use crate::format::is_string_format;
+use crate::range::{RangeKind, RangePattern};
+use crate::regex::RegexChecker;
+use crate::rule::Rule;
+use crate::syaml::{extract_kv_entry, same_data, to_flow_yaml};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The construct works, but may not do what the author expects
+    Warning,
+    /// The construct accepts no value
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A suspicious construct of a valid schema
+#[derive(Clone, Debug)]
+pub struct LintFinding {
+    pub severity: Severity,
+    pub rule_name: Box<str>,
+    pub line_col: LineCol,
+    pub message: Box<str>,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {} {}",
+            self.severity, self.rule_name, self.message, self.line_col
+        )
+    }
+}
+
+/// Look for constructs which the metaparser accepts but which are likely
+/// mistakes: unreachable `_oneOf` alternatives and `_ifThen` pairs, keys
+/// declared twice, unanchored regexes, `_in` lists or `_range` bounds which
+/// admit no value, `_references` to key spaces which nothing defines, and
+/// `_cases` which do not declare their discriminator key. The findings are
+/// sorted by position.
+pub fn lint_rule_set(rule_set: &HashMap<Box<str>, Rule>) -> Vec<LintFinding> {
+    let mut linter = Linter {
+        rule_set,
+        rule_name: "".into(),
+        findings: Vec::new(),
+        defined_spaces: Vec::new(),
+        references: Vec::new(),
+    };
+    for (name, rule) in rule_set {
+        linter.rule_name = name.clone();
+        linter.expression(&rule.node);
+    }
+    linter.references();
+    let mut findings = linter.findings;
+    findings.sort_by(|a, b| (a.line_col, &a.message).cmp(&(b.line_col, &b.message)));
+    findings
+}
+
+struct Linter<'a> {
+    rule_set: &'a HashMap<Box<str>, Rule>,
+    rule_name: Box<str>,
+    findings: Vec<LintFinding>,
+    // The key spaces of the _defines checkers, and the _references checkers
+    // with the rule they are found in
+    defined_spaces: Vec<&'a str>,
+    references: Vec<(Box<str>, &'a Yaml)>,
+}
+
+impl<'a> Linter<'a> {
+    fn report(&mut self, severity: Severity, node: &Yaml, message: String) {
+        self.findings.push(LintFinding {
+            severity,
+            rule_name: self.rule_name.clone(),
+            line_col: node.line_col,
+            message: message.into(),
+        });
+    }
+
+    fn expression(&mut self, expression: &'a Yaml) {
+        let YamlData::Mapping(mapping) = &expression.data else {
+            return;
+        };
+        for (key, value) in mapping {
+            match (key.data.as_str(), &value.data) {
+                // A full match needs no anchors
+                (Some("_regex"), YamlData::String(pattern))
+                    if RegexChecker::from_mapping(mapping).is_some_and(|c| !c.full_match) =>
+                {
+                    self.regex(value, pattern)
+                }
+                (Some("_in"), YamlData::List(values)) if values.is_empty() => self.report(
+                    Severity::Error,
+                    value,
+                    "_in: the list is empty, no value is accepted".into(),
+                ),
+                (Some("_range"), YamlData::String(pattern)) => self.range(value, pattern),
+                (Some("_defines"), YamlData::String(space)) => self.defined_spaces.push(space),
+                (Some("_references"), YamlData::String(_)) => {
+                    self.references.push((self.rule_name.clone(), value))
+                }
+                (Some("_allOf"), YamlData::List(expressions)) => {
+                    expressions.iter().for_each(|e| self.expression(e));
+                }
+                (Some("_oneOf"), YamlData::List(alternatives)) => {
+                    self.one_of(alternatives);
+                    alternatives.iter().for_each(|a| self.expression(a));
+                }
+                (Some("_ifThen"), YamlData::List(pairs)) => {
+                    self.if_then(pairs);
+                    for pair in pairs {
+                        if let YamlData::List(pair) = &pair.data {
+                            pair.iter().for_each(|e| self.expression(e));
+                        }
+                    }
+                }
+                (Some("_map" | "_mapFacultative"), YamlData::Mapping(entries)) => {
+                    entries.iter().for_each(|(_, e)| self.expression(e));
+                }
+                (Some("_mapOf"), YamlData::Mapping(entries)) => {
+                    for (key_expression, value_expression) in entries {
+                        self.expression(key_expression);
+                        self.expression(value_expression);
+                    }
+                }
+                (Some("_merge"), YamlData::List(sources)) => {
+                    self.merge(sources);
+                    sources.iter().for_each(|s| self.expression(s));
+                }
+                (Some("_list" | "_listFacultative"), YamlData::List(items)) => {
+                    items.iter().for_each(|e| self.expression(e));
+                }
+                (Some("_listOf"), _) => self.expression(value),
+                (Some("_cases"), YamlData::Mapping(cases)) => {
+                    if let Some(key) = extract_kv_entry(mapping, "_discriminator") {
+                        self.cases(key.data.as_str().unwrap_or_default(), cases);
+                    }
+                    cases.iter().for_each(|(_, e)| self.expression(e));
+                }
+                (Some("_not"), _) => {
+                    if matches!(value.data.as_str(), Some("any" | "anyData")) {
+                        self.report(
+                            Severity::Error,
+                            value,
+                            "_not: the expression accepts any value, no value is accepted".into(),
+                        );
+                    }
+                    self.expression(value);
+                }
+                _ => {}
+            }
+        }
+        self.map_and_map_facultative(mapping);
+    }
+
+    fn regex(&mut self, node: &Yaml, pattern: &str) {
+        let Ok(hir) = regex_syntax::Parser::new().parse(pattern) else {
+            return;
+        };
+        let properties = hir.properties();
+        let start = properties.look_set_prefix().contains(Look::Start);
+        let end = properties.look_set_suffix().contains(Look::End);
+        let missing = match (start, end) {
+            (true, true) => return,
+            (false, true) => "it matches after any prefix; anchor it with ^",
+            (true, false) => "it matches before any suffix; anchor it with $",
+            (false, false) => "it matches anywhere in the string; anchor it with ^ and $",
+        };
+        self.report(
+            Severity::Warning,
+            node,
+            format!("_regex: /{pattern}/ is not anchored, {missing}"),
+        );
+    }
+
+    fn range(&mut self, node: &Yaml, pattern: &str) {
+        let Some(range) = RangePattern::parse(pattern) else {
+            return;
+        };
+        let (Some(minimum), Some(maximum)) = (range.minimum, range.maximum) else {
+            return;
+        };
+        let is_empty = if range.kind == RangeKind::Int {
+            let low = if minimum.inclusive {
+                minimum.value.ceil()
+            } else {
+                minimum.value.floor() + 1.0
+            };
+            let high = if maximum.inclusive {
+                maximum.value.floor()
+            } else {
+                maximum.value.ceil() - 1.0
+            };
+            low > high
+        } else {
+            minimum.value > maximum.value
+                || (minimum.value == maximum.value && !(minimum.inclusive && maximum.inclusive))
+        };
+        if is_empty {
+            self.report(
+                Severity::Error,
+                node,
+                format!("_range: no number is inside {pattern}"),
+            );
+        }
+    }
+
+    // A reference to a key space which no _defines checker fills can never
+    // be resolved
+    fn references(&mut self) {
+        for (rule_name, node) in std::mem::take(&mut self.references) {
+            let space = node.data.as_str().unwrap_or_default();
+            if !self.defined_spaces.contains(&space) {
+                self.rule_name = rule_name;
+                self.report(
+                    Severity::Error,
+                    node,
+                    format!(
+                        "_references: no _defines checker fills the key space '{space}', no value is accepted"
+                    ),
+                );
+            }
+        }
+    }
+
+    fn one_of(&mut self, alternatives: &'a [Yaml]) {
+        for (j, later) in alternatives.iter().enumerate() {
+            let earlier = alternatives[..j]
+                .iter()
+                .position(|a| self.subsumes(a, later));
+            if let Some(i) = earlier {
+                self.report(
+                    Severity::Warning,
+                    later,
+                    format!(
+                        "_oneOf: alternative {j} can never match, alternative {i} accepts all of its values"
+                    ),
+                );
+            }
+        }
+    }
+
+    fn if_then(&mut self, pairs: &'a [Yaml]) {
+        let tests: Vec<Option<&Yaml>> = pairs
+            .iter()
+            .map(|pair| match &pair.data {
+                YamlData::List(pair) => pair.first(),
+                _ => None,
+            })
+            .collect();
+        let Some(i) = tests
+            .iter()
+            .position(|test| test.is_some_and(|test| self.accepts_anything(test)))
+        else {
+            return;
+        };
+        for (j, pair) in pairs.iter().enumerate().skip(i + 1) {
+            self.report(
+                Severity::Warning,
+                pair,
+                format!("_ifThen: pair {j} is never used, the test of pair {i} accepts any value"),
+            );
+        }
+    }
+
+    // Keys of _map win over _mapFacultative in apply_map_matcher
+    fn map_and_map_facultative(&mut self, mapping: &Vec<(Yaml, Yaml)>) {
+        let (Some(YamlData::Mapping(map)), Some(YamlData::Mapping(map_facultative))) = (
+            extract_kv_entry(mapping, "_map").map(|n| &n.data),
+            extract_kv_entry(mapping, "_mapFacultative").map(|n| &n.data),
+        ) else {
+            return;
+        };
+        for (key, _) in map_facultative {
+            let Some(name) = key.data.as_str() else {
+                continue;
+            };
+            if extract_kv_entry(map, name).is_some() {
+                self.report(
+                    Severity::Warning,
+                    key,
+                    format!(
+                        "_mapFacultative: key '{name}' is also declared in _map, so it is mandatory"
+                    ),
+                );
+            }
+        }
+    }
+
+    // A case whose map checker does not declare the discriminator key, and
+    // accepts no other key, rejects the maps which select it
+    fn cases(&mut self, key: &str, cases: &'a [(Yaml, Yaml)]) {
+        for (value, expression) in cases {
+            let YamlData::Mapping(mapping) = &self.resolve(expression).data else {
+                continue;
+            };
+            let is_closed_map = ["_map", "_mapFacultative", "_merge"]
+                .iter()
+                .any(|k| extract_kv_entry(mapping, k).is_some())
+                && extract_kv_entry(mapping, "_mapOf").is_none();
+            if !is_closed_map {
+                continue;
+            }
+            let mut keys = Vec::new();
+            self.declared_keys(expression, &mut keys, 0);
+            if !keys.contains(&key) {
+                self.report(
+                    Severity::Error,
+                    value,
+                    format!(
+                        "_cases: the case {} does not declare the key '{key}', no map is accepted",
+                        to_flow_yaml(value)
+                    ),
+                );
+            }
+        }
+    }
+
+    fn merge(&mut self, sources: &'a [Yaml]) {
+        let mut seen: HashMap<&str, String> = HashMap::new();
+        for (k, source) in sources.iter().enumerate() {
+            let label = match source.data.as_str() {
+                Some(name) => format!("'{name}'"),
+                None => format!("_merge[{k}]"),
+            };
+            let mut keys = Vec::new();
+            self.declared_keys(source, &mut keys, 0);
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                match seen.get(key) {
+                    Some(first) => {
+                        let message =
+                            format!("_merge: key '{key}' is declared by both {first} and {label}");
+                        self.report(Severity::Warning, source, message);
+                    }
+                    None => {
+                        seen.insert(key, label.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    // The keys declared by a map checker, including the keys of the checkers
+    // it merges. The depth bounds reference cycles.
+    fn declared_keys(&self, checker: &'a Yaml, keys: &mut Vec<&'a str>, depth: usize) {
+        let YamlData::Mapping(mapping) = &self.resolve(checker).data else {
+            return;
+        };
+        if depth > self.rule_set.len() {
+            return;
+        }
+        for keyword in ["_map", "_mapFacultative"] {
+            if let Some(YamlData::Mapping(entries)) =
+                extract_kv_entry(mapping, keyword).map(|n| &n.data)
+            {
+                keys.extend(entries.iter().filter_map(|(key, _)| key.data.as_str()));
+            }
+        }
+        if let Some(YamlData::List(sources)) = extract_kv_entry(mapping, "_merge").map(|n| &n.data)
+        {
+            for source in sources {
+                self.declared_keys(source, keys, depth + 1);
+            }
+        }
+    }
+
+    // Follow rule references until a checker form or a predefined rule
+    fn resolve(&self, mut node: &'a Yaml) -> &'a Yaml {
+        for _ in 0..=self.rule_set.len() {
+            match node.data.as_str().and_then(|name| self.rule_set.get(name)) {
+                Some(rule) => node = &rule.node,
+                None => break,
+            }
+        }
+        node
+    }
+
+    fn accepts_anything(&self, expression: &'a Yaml) -> bool {
+        matches!(
+            self.resolve(expression).data.as_str(),
+            Some("any" | "anyData")
+        )
+    }
+
+    // Whether every value accepted by `later` is accepted by `earlier`. This
+    // only recognizes simple cases.
+    fn subsumes(&self, earlier: &'a Yaml, later: &'a Yaml) -> bool {
+        let (earlier, later) = (self.resolve(earlier), self.resolve(later));
+        if same_data(earlier, later) || self.accepts_anything(earlier) {
+            return true;
+        }
+        let checker = |keyword: &str| match &later.data {
+            YamlData::Mapping(mapping) => extract_kv_entry(mapping, keyword),
+            _ => None,
+        };
+        let in_values = |is_accepted: fn(&YamlData) -> bool| matches!(checker("_in").map(|n| &n.data), Some(YamlData::List(values)) if values.iter().all(|v| is_accepted(&v.data)));
+        match earlier.data.as_str() {
+            Some("string") => {
+                matches!(later.data.as_str(), Some(name) if is_string_format(name))
+                    || checker("_regex").is_some()
+                    || in_values(|v| matches!(v, YamlData::String(_)))
+            }
+            Some("float") => {
+                matches!(later.data.as_str(), Some("int"))
+                    || checker("_range").is_some()
+                    || in_values(|v| {
+                        matches!(
+                            v,
+                            YamlData::Integer(_) | YamlData::BigInteger(_) | YamlData::Real(_)
+                        )
+                    })
+            }
+            Some("int") => {
+                matches!(later.data.as_str(), Some("port"))
+                    || in_values(|v| matches!(v, YamlData::Integer(_) | YamlData::BigInteger(_)))
+            }
+            Some("boolean") => in_values(|v| matches!(v, YamlData::Boolean(_))),
+            Some("nullType") => in_values(|v| matches!(v, YamlData::Null)),
+            _ => {
+                // An _in list which contains all the values of a later one
+                let values = |node: &'a Yaml| match &node.data {
+                    YamlData::Mapping(mapping) => match extract_kv_entry(mapping, "_in") {
+                        Some(Yaml {
+                            data: YamlData::List(values),
+                            ..
+                        }) => Some(values),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match (values(earlier), values(later)) {
+                    (Some(earlier), Some(later)) => later
+                        .iter()
+                        .all(|v| earlier.iter().any(|e| same_data(e, v))),
+                    _ => false,
+                }
+            }
+        }
+    }
+}
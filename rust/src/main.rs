@@ -0,0 +1,134 @@
+use std::path::Path;
+use std::process::ExitCode;
+use std::rc::Rc;
+
+use lidy::{
+    diff_rule_sets, generate_documentation, lint_rule_set, DocFormat, File, Parser, SchemaInferrer,
+    Severity, YamlFile,
+};
+
+type AnyBoxedError = Box<dyn std::error::Error>;
+
+const USAGE: &str = "Usage:
+  lidy doc [--format markdown|html] <schema>  Print the reference documentation of a schema
+  lidy lint <schema>                          Report suspicious constructs of a schema
+  lidy diff <old schema> <new schema>         Report the changes between two versions of a schema
+  lidy infer <document>...                    Propose a schema accepting the sample documents";
+
+fn load_schema(path: &str) -> Result<Parser<'static, ()>, AnyBoxedError> {
+    let file = File::read_local_file(path)?;
+    Parser::<()>::make(
+        &Rc::new(file),
+        Box::new(|_, lidy_result| Ok(lidy_result.data.clone())),
+    )
+}
+
+fn doc(args: &[String]) -> Result<(), AnyBoxedError> {
+    let mut format = DocFormat::Markdown;
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().map(|a| a.as_str()) {
+                    Some("markdown") => DocFormat::Markdown,
+                    Some("html") => DocFormat::Html,
+                    _ => return Err("--format expects 'markdown' or 'html'".into()),
+                }
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg.as_str()),
+            _ => return Err(format!("unexpected argument '{arg}'").into()),
+        }
+    }
+    let path = path.ok_or("missing schema file")?;
+
+    let parser = load_schema(path)?;
+    let title = Path::new(path)
+        .file_name()
+        .map_or(path.into(), |name| name.to_string_lossy());
+    print!(
+        "{}",
+        generate_documentation(&parser.rule_set, &title, format)
+    );
+    Ok(())
+}
+
+// Fails when a finding is an error
+fn lint(args: &[String]) -> Result<(), AnyBoxedError> {
+    let [path] = args else {
+        return Err("lint expects one schema file".into());
+    };
+    let parser = load_schema(path)?;
+    let findings = lint_rule_set(&parser.rule_set);
+    for finding in &findings {
+        println!("{finding}");
+    }
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Error)
+        .count();
+    match errors {
+        0 => Ok(()),
+        1 => Err("1 error found".into()),
+        n => Err(format!("{n} errors found").into()),
+    }
+}
+
+// Fails when a change is breaking
+fn diff(args: &[String]) -> Result<(), AnyBoxedError> {
+    let [old_path, new_path] = args else {
+        return Err("diff expects an old and a new schema file".into());
+    };
+    let (old, new) = (load_schema(old_path)?, load_schema(new_path)?);
+    let diff = diff_rule_sets(&old.rule_set, &new.rule_set);
+    for change in &diff.breaking {
+        println!("breaking: {change}");
+    }
+    for change in &diff.compatible {
+        println!("compatible: {change}");
+    }
+    match diff.breaking.len() {
+        0 => Ok(()),
+        1 => Err("1 breaking change found".into()),
+        n => Err(format!("{n} breaking changes found").into()),
+    }
+}
+
+fn infer(args: &[String]) -> Result<(), AnyBoxedError> {
+    if args.is_empty() {
+        return Err("infer expects sample document files".into());
+    }
+    let mut inferrer = SchemaInferrer::new();
+    for path in args {
+        let mut document = YamlFile::new(Rc::new(File::read_local_file(path)?));
+        document.deserialize()?;
+        inferrer.add_document(&document.yaml);
+    }
+    print!("{}", inferrer.infer()?);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let outcome = match args.first().map(|a| a.as_str()) {
+        Some("doc") => doc(&args[1..]),
+        Some("lint") => lint(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        Some("infer") => infer(&args[1..]),
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match outcome {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("lidy: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -5,16 +5,18 @@ use crate::error::{AnyBoxedError, JoinError, SimpleError};
 use crate::expression::apply_expression;
 use crate::parser::Parser;
 use crate::result::{Data, LidyResult, MapData};
+use crate::rule::Rule;
 use crate::syaml::extract_kv_entry;
 use crate::KeyValueData;
 
 struct MapInfo {
     mandatory_keys: HashMap<Box<str>, bool>,
     map: HashMap<Box<str>, Yaml>,
+    defaults: HashMap<Box<str>, Yaml>,
 }
 
-fn resolve_merge_reference<'a, TV>(
-    parser: &'a Parser<TV>,
+pub fn resolve_merge_reference<'a>(
+    rule_set: &'a HashMap<Box<str>, Rule>,
     node: &'a Yaml,
 ) -> Result<&'a Vec<(Yaml, Yaml)>, AnyBoxedError>
 where
@@ -22,12 +24,12 @@ where
     match &node.data {
         YamlData::Mapping(yaml_mapping) => Ok(yaml_mapping),
         YamlData::String(ref rule_name) => {
-            let rule = parser.rule_set.get(&**rule_name).ok_or_else(|| {
+            let rule = rule_set.get(&**rule_name).ok_or_else(|| {
                 SimpleError::from_message(
                     "The merge value reference must exist in the schema".into(),
                 )
             })?;
-            resolve_merge_reference(parser, &rule.node)
+            resolve_merge_reference(rule_set, &rule.node)
         }
         _ => Err(SimpleError::from_message(
             "The merge values must be mappings or references to mappings".into(),
@@ -42,6 +44,7 @@ fn contribute_to_map_info<TV>(
     map: Option<&Yaml>,
     map_facultative: Option<&Yaml>,
     merge: Option<&Yaml>,
+    map_default: Option<&Yaml>,
 ) -> Result<(), AnyBoxedError>
 where
 {
@@ -49,16 +52,19 @@ where
     if let Some(merge_yaml) = merge {
         if let YamlData::List(merge_list) = &merge_yaml.data {
             for node in merge_list {
-                let resolved_vec: &Vec<(Yaml, Yaml)> = resolve_merge_reference(parser, node)?;
+                let resolved_vec: &Vec<(Yaml, Yaml)> =
+                    resolve_merge_reference(&parser.rule_set, node)?;
                 let map_node = extract_kv_entry(resolved_vec, "_map");
                 let map_facultative_node = extract_kv_entry(resolved_vec, "_mapFacultative");
                 let merge_node = extract_kv_entry(resolved_vec, "_merge");
+                let map_default_node = extract_kv_entry(resolved_vec, "_mapDefault");
                 contribute_to_map_info(
                     parser,
                     map_info,
                     map_node,
                     map_facultative_node,
                     merge_node,
+                    map_default_node,
                 )?
             }
         }
@@ -94,6 +100,19 @@ where
         }
     }
 
+    // Extracting from _mapDefault
+    if let Some(map_default_yaml) = map_default {
+        if let YamlData::Mapping(mapping) = &map_default_yaml.data {
+            for (key, value) in mapping {
+                if let YamlData::String(key_string) = &key.data {
+                    map_info
+                        .defaults
+                        .insert(key_string.clone().into(), value.clone());
+                }
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -103,6 +122,7 @@ pub fn apply_map_matcher<TV>(
     map_facultative: Option<&Yaml>,
     map_of: Option<&Yaml>,
     merge: Option<&Yaml>,
+    map_default: Option<&Yaml>,
     content: &Yaml,
 ) -> Result<LidyResult<TV>, AnyBoxedError>
 where
@@ -111,9 +131,17 @@ where
         let mut map_info = MapInfo {
             mandatory_keys: HashMap::new(),
             map: HashMap::new(),
+            defaults: HashMap::new(),
         };
 
-        contribute_to_map_info(parser, &mut map_info, map, map_facultative, merge)?;
+        contribute_to_map_info(
+            parser,
+            &mut map_info,
+            map,
+            map_facultative,
+            merge,
+            map_default,
+        )?;
 
         let mut map_data = MapData {
             map: HashMap::new(),
@@ -224,6 +252,34 @@ where
             }
         }
 
+        // Absent facultative keys take their default value, positioned in the
+        // schema file
+        for (key, default) in &map_info.defaults {
+            if map_content.contains_key(key) || map_info.mandatory_keys.contains_key(key) {
+                continue;
+            }
+            if let Some(schema) = map_info.map.get(key) {
+                let content_file_name = std::mem::replace(
+                    &mut parser.content_file_name,
+                    parser.schema_file_name.clone(),
+                );
+                let content_source = std::mem::replace(&mut parser.content_source, "".into());
+                let outcome = apply_expression(parser, schema, default);
+                parser.content_file_name = content_file_name;
+                parser.content_source = content_source;
+                match outcome {
+                    Ok(mut result) => {
+                        result.is_default = true;
+                        map_data.map.insert(key.clone(), result);
+                    }
+                    Err(e) => join_error.add(
+                        SimpleError::from_message(format!("default of key {key}: {e}").into())
+                            .into(),
+                    ),
+                }
+            }
+        }
+
         join_error.into_result()?;
 
         Ok(LidyResult::create(parser, content, Data::MapData(map_data)))
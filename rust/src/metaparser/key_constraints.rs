@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::error::{AnyBoxedError, JoinError, SimpleError};
+use crate::key_constraint::{key_condition, KEY_CONSTRAINT_KEYWORDS};
+use crate::map::resolve_merge_reference;
+use crate::rule::Rule;
+
+// Check that the keys named by the key constraints of each map checker are
+// keys which the checker declares, in its _map, its _mapFacultative or the
+// checkers it merges. A checker with a _mapOf accepts other keys, so its
+// constraints may name any key.
+pub fn check_key_constraints(rule_set: &HashMap<Box<str>, Rule>) -> Result<(), AnyBoxedError> {
+    let mut join_error = JoinError::default();
+    for rule in rule_set.values() {
+        check_node(rule_set, &rule.node, &mut join_error);
+    }
+    join_error.into_result()
+}
+
+fn check_node(rule_set: &HashMap<Box<str>, Rule>, node: &Yaml, join_error: &mut JoinError) {
+    match &node.data {
+        YamlData::Mapping(mapping) => {
+            check_checker(rule_set, mapping, join_error);
+            for (key, value) in mapping {
+                // Values, not expressions
+                if !matches!(
+                    key.data.as_str(),
+                    Some("_mapDefault" | "_examples" | "_in" | "_const")
+                ) {
+                    check_node(rule_set, key, join_error);
+                    check_node(rule_set, value, join_error);
+                }
+            }
+        }
+        YamlData::List(list) => {
+            for item in list {
+                check_node(rule_set, item, join_error);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_checker(
+    rule_set: &HashMap<Box<str>, Rule>,
+    mapping: &Vec<(Yaml, Yaml)>,
+    join_error: &mut JoinError,
+) {
+    let mut named_keys = Vec::new();
+    for (key, value) in mapping {
+        let Some(keyword) = key.data.as_str() else {
+            continue;
+        };
+        if !KEY_CONSTRAINT_KEYWORDS.contains(&keyword) {
+            continue;
+        }
+        match &value.data {
+            // _dependentRequired and _mutuallyExclusive
+            YamlData::Mapping(entries) => {
+                for (key, items) in entries {
+                    named_keys.push((keyword, key));
+                    if let YamlData::List(items) = &items.data {
+                        named_keys.extend(items.iter().map(|item| (keyword, item)));
+                    }
+                }
+            }
+            // _exactlyOneOf and _atMostOneOf
+            YamlData::List(groups) => {
+                for group in groups {
+                    if let YamlData::List(names) = &group.data {
+                        named_keys.extend(names.iter().map(|name| (keyword, name)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if named_keys.is_empty() {
+        return;
+    }
+
+    let mut declared = Vec::new();
+    if !declare_keys(rule_set, mapping, &mut declared, 0) {
+        return;
+    }
+    for (keyword, node) in named_keys {
+        // A condition of _mutuallyExclusive names the key of its pair
+        let (name, node) = match key_condition(node) {
+            Some((name, Some(_))) => match &node.data {
+                YamlData::Mapping(pair) => (name, &pair[0].0),
+                _ => continue,
+            },
+            Some((name, None)) => (name, node),
+            None => continue,
+        };
+        if !declared.contains(&name) {
+            join_error.add(
+                SimpleError::from_check(
+                    keyword,
+                    &format!("key '{name}' is not a key of the map checker"),
+                    node,
+                )
+                .into(),
+            );
+        }
+    }
+}
+
+// Collect the keys declared by a map checker and the checkers it merges.
+// Returns false when the checker accepts any other key, through a _mapOf
+fn declare_keys<'a>(
+    rule_set: &'a HashMap<Box<str>, Rule>,
+    mapping: &'a Vec<(Yaml, Yaml)>,
+    declared: &mut Vec<&'a str>,
+    depth: usize,
+) -> bool {
+    // Merge cycles are reported by the metaparser
+    if depth > rule_set.len() {
+        return true;
+    }
+    let mut is_closed = true;
+    for (key, value) in mapping {
+        match (key.data.as_str(), &value.data) {
+            (Some("_map" | "_mapFacultative"), YamlData::Mapping(entries)) => {
+                declared.extend(entries.iter().filter_map(|(key, _)| key.data.as_str()));
+            }
+            (Some("_mapOf"), _) => is_closed = false,
+            (Some("_merge"), YamlData::List(sources)) => {
+                for source in sources {
+                    if let Ok(merged) = resolve_merge_reference(rule_set, source) {
+                        is_closed &= declare_keys(rule_set, merged, declared, depth + 1);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    is_closed
+}
@@ -8,12 +8,16 @@ use crate::{
 
 impl<'a, TV> Parser<'a, TV> {
     // Check the given mapping produced by the current parser to make sure that
-    // all _merge references of the schema are valid
+    // all _merge references of the schema are valid, and that every
+    // _mapDefault key is a key of _mapFacultative
     pub fn run_map_checker_builder(
         &mut self,
         lidy_result: &LidyResult<()>,
     ) -> Result<Data<()>, AnyBoxedError> {
         if let Data::MapData(map_data) = &lidy_result.data {
+            if let Some(default) = map_data.map.get("_mapDefault") {
+                self.check_default_keys(default, map_data.map.get("_mapFacultative"))?;
+            }
             if let Some(merge) = map_data.map.get("_merge") {
                 let mut join_error = JoinError::default();
                 if let Data::ListData(list_data) = &merge.data {
@@ -37,6 +41,40 @@ impl<'a, TV> Parser<'a, TV> {
         Ok(lidy_result.data.clone())
     }
 
+    fn check_default_keys(
+        &self,
+        default: &LidyResult<()>,
+        facultative: Option<&LidyResult<()>>,
+    ) -> Result<(), AnyBoxedError> {
+        let facultative_keys: Vec<&str> = match facultative.map(|f| &f.data) {
+            Some(Data::MapData(facultative_data)) => facultative_data
+                .map_of
+                .iter()
+                .filter_map(|kv| match &kv.key.data {
+                    Data::String(s) => Some(&**s),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let mut join_error = JoinError::default();
+        if let Data::MapData(default_data) = &default.data {
+            for kv in &default_data.map_of {
+                if let Data::String(key) = &kv.key.data {
+                    if !facultative_keys.contains(&&**key) {
+                        join_error.add(Box::new(SimpleError::from_check_result(
+                            "_mapDefault",
+                            &format!("key '{key}' has a default value but is not a key of _mapFacultative"),
+                            (&kv.key).into(),
+                        )));
+                    }
+                }
+            }
+        }
+        join_error.into_result()
+    }
+
     fn check_merged_node(
         &self,
         name: &str,
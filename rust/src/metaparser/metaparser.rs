@@ -4,30 +4,49 @@ use std::rc::Rc;
 use lidy__yaml::{Yaml, YamlData};
 
 use crate::error::{AnyBoxedError, JoinError, SimpleError};
-use crate::file::File;
+use crate::file::{File, FileFormat};
 use crate::parser::{make_rule_set, Parser};
+use crate::reference::CrossReferences;
 use crate::result::Data;
 use crate::rule::Rule;
 use crate::yamlfile::YamlFile;
 use crate::LidyResult;
 
+// The metaschema shared by all the implementations of lidy, and the rules which
+// the Rust implementation adds to it or replaces. They are embedded so that the
+// parser does not depend on the working directory.
+const SHARED_META_SCHEMA: &str = include_str!("../../../lidy.schema.yaml");
+const RUST_META_SCHEMA: &str = include_str!("../../lidy.schema.yaml");
+
+fn make_meta_rule_set(content: &str) -> Result<HashMap<Box<str>, Rule>, AnyBoxedError> {
+    let meta_schema_file = File {
+        name: "lidy.schema.yaml".into(),
+        content: content.into(),
+        format: FileFormat::Yaml,
+    };
+    let mut meta_schema = YamlFile::new(Rc::new(meta_schema_file));
+    meta_schema.deserialize()?;
+    make_rule_set(&meta_schema)
+}
+
 pub fn make_meta_parser_for<'a, 'b, TV>(
     parser: &'b mut Parser<'a, TV>,
 ) -> Result<Parser<'b, ()>, AnyBoxedError>
 where
     'a: 'b,
 {
-    let meta_schema_file = File::read_local_file("../lidy.schema.yaml")?;
-    let mut meta_schema = YamlFile::new(Rc::new(meta_schema_file));
-    meta_schema.deserialize()?;
-
-    let meta_rule_set = make_rule_set(&meta_schema)?;
+    let mut meta_rule_set = make_meta_rule_set(SHARED_META_SCHEMA)?;
+    meta_rule_set.extend(make_meta_rule_set(RUST_META_SCHEMA)?);
 
     let meta_parser = Parser {
         content_file_name: "lidy.schema.yaml".into(),
+        content_source: "".into(),
+        schema_file_name: "lidy.schema.yaml".into(),
         rule_set: meta_rule_set,
         rule_trace: Vec::new(),
         rule_is_matching_node: HashMap::new(),
+        skip_builders: false,
+        cross_references: CrossReferences::default(),
         builder_callback: Box::new(
             |rule_name,
              lidy_result: &LidyResult<()>|
@@ -35,7 +54,10 @@ where
                 match rule_name {
                     "mapChecker" => parser.run_map_checker_builder(lidy_result),
                     "ruleReference" => parser.run_rule_reference_checker_builder(lidy_result),
+                    "predefinedRule" => parser.run_predefined_rule_builder(lidy_result),
                     "sizeCheckerKeywordSet" => parser.run_size_checker_builder(lidy_result),
+                    "rangeChecker" => parser.run_range_checker_builder(lidy_result),
+                    "regexChecker" => parser.run_regex_checker_builder(lidy_result),
                     _ => Ok(lidy_result.data.clone()),
                 }
             },
@@ -110,10 +132,10 @@ fn check_direct_rule_reference(
         YamlData::Mapping(map) => {
             let mut join_error = JoinError::default();
 
-            // Check for _oneOf or _merge nodes
+            // Check for _oneOf, _allOf, _merge, _not or _cases nodes
             for (key, value) in map {
                 if let YamlData::String(key_str) = &key.data {
-                    if key_str == "_oneOf" || key_str == "_merge" {
+                    if key_str == "_oneOf" || key_str == "_allOf" || key_str == "_merge" {
                         if let YamlData::List(seq) = &value.data {
                             for node in seq {
                                 if let Some(err) =
@@ -123,6 +145,22 @@ fn check_direct_rule_reference(
                                 }
                             }
                         }
+                    } else if key_str == "_not" {
+                        if let Some(err) =
+                            check_direct_rule_reference(rule_set, value, rule_name_array)
+                        {
+                            join_error.add(err);
+                        }
+                    } else if key_str == "_cases" {
+                        if let YamlData::Mapping(cases) = &value.data {
+                            for (_, node) in cases {
+                                if let Some(err) =
+                                    check_direct_rule_reference(rule_set, node, rule_name_array)
+                                {
+                                    join_error.add(err);
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -1,7 +1,13 @@
+mod key_constraints;
 mod map_checker;
 mod metaparser;
+mod range_checker;
+mod regex_checker;
 mod rule_reference;
+mod schema_values;
 mod size_checker_keyword_set;
 
+pub use key_constraints::check_key_constraints;
 pub use metaparser::check_rule_set;
 pub use metaparser::make_meta_parser_for;
+pub use schema_values::check_schema_values;
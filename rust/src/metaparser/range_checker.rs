@@ -0,0 +1,31 @@
+use lidy__yaml::LineCol;
+
+use crate::range::RangePattern;
+use crate::{error::AnyBoxedError, result::Data, LidyResult, Parser, SimpleError};
+
+impl<'a, TV> Parser<'a, TV> {
+    // The metaschema only recognizes the shape of timestamp ranges, the
+    // bounds must also be valid timestamps
+    pub fn run_range_checker_builder(
+        &mut self,
+        lidy_result: &LidyResult<()>,
+    ) -> Result<Data<()>, AnyBoxedError> {
+        if let Data::MapData(map_data) = &lidy_result.data {
+            if let Some(range) = map_data.map.get("_range") {
+                if let Data::String(pattern) = &range.data {
+                    if RangePattern::parse(pattern).is_none() {
+                        return Err(Box::new(SimpleError::from_check_result(
+                            "_range",
+                            &format!("'{pattern}' is not a valid range, the bounds of a timestamp range are YAML timestamps"),
+                            LineCol {
+                                line: range.position.line,
+                                column: range.position.column,
+                            },
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(lidy_result.data.clone())
+    }
+}
@@ -0,0 +1,73 @@
+use lidy__yaml::LineCol;
+
+use crate::regex::{RegexChecker, REGEX_FLAGS};
+use crate::{error::AnyBoxedError, result::Data, LidyResult, Parser, SimpleError};
+
+impl<'a, TV> Parser<'a, TV> {
+    // The metaschema only ensures that the pattern and the flags are
+    // strings. The flags must be known, and the pattern, written with its
+    // options, must compile.
+    pub fn run_regex_checker_builder(
+        &mut self,
+        lidy_result: &LidyResult<()>,
+    ) -> Result<Data<()>, AnyBoxedError> {
+        let Data::MapData(map_data) = &lidy_result.data else {
+            return Ok(lidy_result.data.clone());
+        };
+        let mut checker = RegexChecker {
+            pattern: "",
+            flags: "",
+            full_match: false,
+        };
+        if let Some(LidyResult {
+            data: Data::String(flags),
+            position,
+            ..
+        }) = map_data.map.get("_regexFlags")
+        {
+            checker.flags = flags;
+            for (k, flag) in flags.char_indices() {
+                let problem = if !REGEX_FLAGS.contains(flag) {
+                    "is not a known flag"
+                } else if flags[..k].contains(flag) {
+                    "is repeated"
+                } else {
+                    continue;
+                };
+                return Err(Box::new(SimpleError::from_check_result(
+                    "_regexFlags",
+                    &format!("'{flag}' {problem}, the flags are among '{REGEX_FLAGS}'"),
+                    LineCol::from(position),
+                )));
+            }
+        }
+        if let Some(LidyResult {
+            data: Data::Boolean(full_match),
+            ..
+        }) = map_data.map.get("_regexFullMatch")
+        {
+            checker.full_match = *full_match;
+        }
+        if let Some(LidyResult {
+            data: Data::String(pattern),
+            position,
+            ..
+        }) = map_data.map.get("_regex")
+        {
+            checker.pattern = pattern;
+            if let Err(error) = checker.compile() {
+                // The syntax errors draw the pattern before the reason, on
+                // their last line
+                let error = error.to_string();
+                let reason = error.lines().last().unwrap_or_default();
+                let reason = reason.trim_start_matches("error: ");
+                return Err(Box::new(SimpleError::from_check_result(
+                    "_regex",
+                    &format!("{} is not a valid regex, {reason}", checker.display()),
+                    LineCol::from(position),
+                )));
+            }
+        }
+        Ok(lidy_result.data.clone())
+    }
+}
@@ -3,11 +3,25 @@ use std::collections::HashMap;
 use lidy__yaml::{LineCol, Yaml};
 
 use crate::{
-    error::AnyBoxedError, result::Data, rule::apply_predefined_rule, LidyResult, Parser,
-    SimpleError,
+    error::AnyBoxedError, reference::CrossReferences, result::Data, rule::apply_predefined_rule,
+    LidyResult, Parser, SimpleError,
 };
 
 impl<'a, TV> Parser<'a, TV> {
+    // A rule of the schema may shadow a predefined rule, such as `port`. Its
+    // references then use it.
+    pub fn run_predefined_rule_builder(
+        &mut self,
+        lidy_result: &LidyResult<()>,
+    ) -> Result<Data<()>, AnyBoxedError> {
+        if let Data::String(identifier) = &lidy_result.data {
+            if let Some(rule) = self.rule_set.get_mut(&**identifier) {
+                rule.is_used = true;
+            }
+        }
+        Ok(lidy_result.data.clone())
+    }
+
     pub fn run_rule_reference_checker_builder(
         &mut self,
         lidy_result: &LidyResult<()>,
@@ -23,9 +37,13 @@ impl<'a, TV> Parser<'a, TV> {
             let rule_exists = match apply_predefined_rule(
                 &mut Parser{
                     content_file_name: "ruleCheck".into(),
+                    content_source: "".into(),
+                    schema_file_name: "ruleCheck".into(),
                     rule_set: HashMap::new(),
                     rule_trace: Vec::new(),
                     rule_is_matching_node: HashMap::new(),
+                    skip_builders: false,
+                    cross_references: CrossReferences::default(),
                     builder_callback: Box::new(
                         |_: &str,
                          _: &LidyResult<()>|
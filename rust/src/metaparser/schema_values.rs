@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::error::{AnyBoxedError, JoinError, SimpleError};
+use crate::expression::apply_expression;
+use crate::reference::CrossReferences;
+use crate::rule::Rule;
+use crate::syaml::extract_kv_entry;
+use crate::Parser;
+
+// Check the content values embedded in the schema: the values of the
+// _mapDefault keywords must match the expression of their key, and the
+// _examples must match the expression they annotate. This runs once the
+// schema is known to be valid, since the expressions may refer to any rule of
+// the schema.
+pub fn check_schema_values(
+    rule_set: &HashMap<Box<str>, Rule>,
+    schema_file_name: &Rc<str>,
+) -> Result<(), AnyBoxedError> {
+    let mut parser = Parser::<()> {
+        content_file_name: schema_file_name.clone(),
+        content_source: "".into(),
+        schema_file_name: schema_file_name.clone(),
+        rule_set: rule_set.clone(),
+        builder_callback: Box::new(|_, lidy_result| Ok(lidy_result.data.clone())),
+        rule_trace: Vec::new(),
+        rule_is_matching_node: HashMap::new(),
+        skip_builders: false,
+        cross_references: CrossReferences::default(),
+    };
+    let mut join_error = JoinError::default();
+
+    for (name, rule) in rule_set {
+        parser.rule_trace.push(name.clone());
+        check_node(&mut parser, &rule.node, &mut join_error);
+        parser.rule_trace.pop();
+    }
+
+    join_error.into_result()
+}
+
+fn check_node(parser: &mut Parser<()>, node: &Yaml, join_error: &mut JoinError) {
+    match &node.data {
+        YamlData::Mapping(mapping) => {
+            check_map_defaults(parser, mapping, join_error);
+            check_examples(parser, node, mapping, join_error);
+
+            for (key, value) in mapping {
+                // Default values and examples are content, not expressions
+                if !matches!(key.data.as_str(), Some("_mapDefault" | "_examples")) {
+                    check_node(parser, key, join_error);
+                    check_node(parser, value, join_error);
+                }
+            }
+        }
+        YamlData::List(list) => {
+            for item in list {
+                check_node(parser, item, join_error);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_map_defaults(
+    parser: &mut Parser<()>,
+    mapping: &Vec<(Yaml, Yaml)>,
+    join_error: &mut JoinError,
+) {
+    let (Some(default_node), Some(facultative_node)) = (
+        extract_kv_entry(mapping, "_mapDefault"),
+        extract_kv_entry(mapping, "_mapFacultative"),
+    ) else {
+        return;
+    };
+    let (YamlData::Mapping(defaults), YamlData::Mapping(facultative)) =
+        (&default_node.data, &facultative_node.data)
+    else {
+        return;
+    };
+
+    for (key, value) in defaults {
+        let Some(key_str) = key.data.as_str() else {
+            continue;
+        };
+        let Some(expression) = extract_kv_entry(facultative, key_str) else {
+            // Reported by the mapChecker builder
+            continue;
+        };
+        if let Err(e) = apply_expression(parser, expression, value) {
+            join_error.add(
+                SimpleError::from_check(
+                    "_mapDefault",
+                    &format!(
+                        "the default value of key '{key_str}' does not match its expression: {e}"
+                    ),
+                    value,
+                )
+                .into(),
+            );
+        }
+    }
+}
+
+fn check_examples(
+    parser: &mut Parser<()>,
+    node: &Yaml,
+    mapping: &Vec<(Yaml, Yaml)>,
+    join_error: &mut JoinError,
+) {
+    let Some(YamlData::List(examples)) = extract_kv_entry(mapping, "_examples").map(|e| &e.data)
+    else {
+        return;
+    };
+
+    for (k, example) in examples.iter().enumerate() {
+        if let Err(e) = apply_expression(parser, node, example) {
+            join_error.add(
+                SimpleError::from_check(
+                    "_examples",
+                    &format!("example {k} does not match the expression it annotates: {e}"),
+                    example,
+                )
+                .into(),
+            );
+        }
+    }
+}
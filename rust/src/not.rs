@@ -0,0 +1,41 @@
+use crate::{
+    any::map_any_yaml_data_to_lidy_data, error::AnyBoxedError, expression::apply_expression,
+    parser::Parser, syaml::to_flow_yaml, LidyResult, SimpleError,
+};
+use lidy__yaml::Yaml;
+
+pub fn apply_not_matcher<TV>(
+    parser: &mut Parser<TV>,
+    node: &Yaml,
+    content: &Yaml,
+) -> Result<LidyResult<TV>, AnyBoxedError>
+where
+{
+    // The result of the negated expression is discarded, so its builders must
+    // not run, and the names it defines or references are forgotten
+    let skip_builders = parser.skip_builders;
+    let mark = parser.cross_references.mark();
+    parser.skip_builders = true;
+    let outcome = apply_expression(parser, node, content);
+    parser.skip_builders = skip_builders;
+    parser.cross_references.rollback(mark);
+
+    if outcome.is_ok() {
+        return Err(SimpleError::from_check(
+            "_not",
+            &format!("must not match {}", to_flow_yaml(node)),
+            content,
+        )
+        .into());
+    }
+
+    Ok(LidyResult::create(
+        parser,
+        content,
+        map_any_yaml_data_to_lidy_data(
+            &parser.content_file_name,
+            parser.rule_trace.last().unwrap(),
+            content,
+        ),
+    ))
+}
@@ -29,9 +29,14 @@ where
     );
 
     for schema in items {
+        // An alternative which does not match leaves no names behind
+        let mark = parser.cross_references.mark();
         match apply_expression(parser, schema, content) {
             Ok(result) => return Ok(result),
-            Err(error) => join_error.add(error),
+            Err(error) => {
+                parser.cross_references.rollback(mark);
+                join_error.add(error)
+            }
         }
     }
 
@@ -5,7 +5,10 @@ use std::rc::Rc;
 
 use crate::error::{AnyBoxedError, SimpleError};
 use crate::file::File;
-use crate::metaparser::{check_rule_set, make_meta_parser_for};
+use crate::metaparser::{
+    check_key_constraints, check_rule_set, check_schema_values, make_meta_parser_for,
+};
+use crate::reference::CrossReferences;
 use crate::result::Data;
 use crate::rule::{apply_rule, Rule};
 use crate::yamlfile::YamlFile;
@@ -13,6 +16,11 @@ use crate::LidyResult;
 
 pub struct Parser<'a, TV> {
     pub content_file_name: Rc<str>,
+    // The text of the content file, which positions the named groups of a
+    // _regex within quoted and block scalars
+    pub content_source: Rc<str>,
+    // The name of the schema file, used for the position of default values
+    pub schema_file_name: Rc<str>,
     // The map of rule name to rule content
     pub rule_set: HashMap<Box<str>, Rule>,
     // The map of builder functions for each rule
@@ -23,14 +31,21 @@ pub struct Parser<'a, TV> {
     // Whether this rule is already being processed for a node. This is used
     // to detect infinite loops
     pub rule_is_matching_node: HashMap<RuleNodePair, ()>,
+    // Whether the builders are left out, while matching the expression of a
+    // _not, whose result is discarded
+    pub skip_builders: bool,
+    // The names defined and referenced by _defines and _references, resolved
+    // once the whole content has been matched
+    pub cross_references: CrossReferences,
 }
 
 impl<'a, TV> Debug for Parser<'a, TV> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Parser{{content_file_name: {:?}}}{{rule_set: {:?}}}{{builder_callback: (closure)}}{{rule_trace: {:?}}}{{rule_is_matching_node: {:?}}}",
+            "Parser{{content_file_name: {:?}}}{{schema_file_name: {:?}}}{{rule_set: {:?}}}{{builder_callback: (closure)}}{{rule_trace: {:?}}}{{rule_is_matching_node: {:?}}}",
             self.content_file_name,
+            self.schema_file_name,
             self.rule_set,
             self.rule_trace,
             self.rule_is_matching_node,
@@ -66,10 +81,14 @@ impl<'a, TV> Parser<'a, TV> {
         let rule_set = make_rule_set(&schema_file)?;
         let mut parser = Parser {
             content_file_name: file.name.clone().into(),
+            content_source: "".into(),
+            schema_file_name: file.name.clone().into(),
             rule_set,
             builder_callback,
             rule_trace: Vec::new(),
             rule_is_matching_node: HashMap::new(),
+            skip_builders: false,
+            cross_references: CrossReferences::default(),
         };
 
         // METAPARSING VALIDATION
@@ -79,6 +98,8 @@ impl<'a, TV> Parser<'a, TV> {
             meta_parser.parse_content_yaml_file(&schema_file)?;
         }
         check_rule_set(&mut parser.rule_set)?;
+        check_schema_values(&parser.rule_set, &parser.schema_file_name)?;
+        check_key_constraints(&parser.rule_set)?;
 
         Ok(parser)
     }
@@ -93,7 +114,29 @@ impl<'a, TV> Parser<'a, TV> {
         &mut self,
         yaml_file: &YamlFile,
     ) -> Result<LidyResult<TV>, AnyBoxedError> {
-        apply_rule(self, "main", &yaml_file.yaml)
+        self.content_file_name = yaml_file.file.name.clone().into();
+        self.content_source = Rc::from(&*yaml_file.file.content);
+        self.cross_references.clear();
+        let result = apply_rule(self, "main", &yaml_file.yaml)?;
+        self.cross_references.check()?;
+        Ok(result)
+    }
+
+    /// Parse each document of a multi-document YAML stream with the main
+    /// rule. The references of a document may name the definitions of any
+    /// document of the stream.
+    pub fn parse_stream(&mut self, file: &Rc<File>) -> Result<Vec<LidyResult<TV>>, AnyBoxedError> {
+        let yaml_file = YamlFile::new(file.clone());
+        let documents = yaml_file.deserialize_stream()?;
+        self.content_file_name = file.name.clone().into();
+        self.content_source = Rc::from(&*file.content);
+        self.cross_references.clear();
+        let results = documents
+            .iter()
+            .map(|document| apply_rule(self, "main", document))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.cross_references.check()?;
+        Ok(results)
     }
 }
 
@@ -1,9 +1,12 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, FixedOffset};
 use lazy_static::lazy_static;
 use lidy__yaml::{Yaml, YamlData};
 use regex::Regex;
 
 use crate::result::Data;
-use crate::syaml::must_parse_float;
+use crate::syaml::{must_parse_float, parse_timestamp, timestamp_seconds};
 use crate::{error::AnyBoxedError, parser::Parser, LidyResult, SimpleError};
 
 lazy_static! {
@@ -12,6 +15,166 @@ lazy_static! {
             .unwrap();
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangeBound {
+    // For timestamps, the number of seconds since the Unix epoch
+    pub value: f64,
+    pub inclusive: bool,
+    // For timestamps, the exact boundary, which the f64 value approximates
+    // to about a microsecond
+    pub timestamp: Option<DateTime<FixedOffset>>,
+}
+
+impl RangeBound {
+    fn new(boundary: Option<f64>, operator: &str) -> Option<RangeBound> {
+        boundary.map(|value| RangeBound {
+            value,
+            inclusive: operator == "<=",
+            timestamp: None,
+        })
+    }
+
+    fn new_timestamp(timestamp: DateTime<FixedOffset>, operator: &str) -> RangeBound {
+        RangeBound {
+            value: timestamp_seconds(&timestamp),
+            inclusive: operator == "<=",
+            timestamp: Some(timestamp),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeKind {
+    Int,
+    Float,
+    Timestamp,
+}
+
+/// A parsed `_range` pattern, e.g. `1 <= int < 10` or
+/// `2020-01-01 <= timestamp < 2021-01-01`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangePattern {
+    pub minimum: Option<RangeBound>,
+    pub maximum: Option<RangeBound>,
+    pub kind: RangeKind,
+}
+
+impl RangePattern {
+    pub fn parse(pattern: &str) -> Option<RangePattern> {
+        if pattern.contains("timestamp") {
+            return Self::parse_timestamp_pattern(pattern);
+        }
+        let captures = RANGE_REGEX.captures(pattern)?;
+
+        let left_boundary: Option<f64> =
+            captures.get(2).map(|m| m.as_str().parse::<f64>().unwrap());
+        let left_operator = captures.get(4).map_or("", |m| m.as_str());
+        let number_type = captures.get(5).unwrap().as_str();
+        let right_operator = captures.get(7).map_or("", |m| m.as_str());
+        let right_boundary: Option<f64> =
+            captures.get(8).map(|m| m.as_str().parse::<f64>().unwrap());
+
+        Some(RangePattern {
+            minimum: RangeBound::new(left_boundary, left_operator),
+            maximum: RangeBound::new(right_boundary, right_operator),
+            kind: if number_type == "int" {
+                RangeKind::Int
+            } else {
+                RangeKind::Float
+            },
+        })
+    }
+
+    // Timestamps may contain spaces, so the pattern is split at the
+    // comparison operators
+    fn parse_timestamp_pattern(pattern: &str) -> Option<RangePattern> {
+        let mut operands = Vec::new();
+        let mut operators = Vec::new();
+        for (k, part) in pattern.split('<').enumerate() {
+            let mut part = part;
+            if k > 0 {
+                let inclusive = part.starts_with('=');
+                operators.push(if inclusive { "<=" } else { "<" });
+                part = part.strip_prefix('=').unwrap_or(part);
+            }
+            operands.push(part.trim());
+        }
+        let position = operands.iter().position(|&o| o == "timestamp")?;
+        if position > 1 || operands.len() > position + 2 {
+            return None;
+        }
+        let bound = |operand: &str, operator: &str| {
+            let timestamp = parse_timestamp(operand)?;
+            Some(RangeBound::new_timestamp(timestamp, operator))
+        };
+        let minimum = match position {
+            1 => Some(bound(operands[0], operators[0])?),
+            _ => None,
+        };
+        let maximum = match operands.get(position + 1) {
+            Some(operand) => Some(bound(operand, operators[position])?),
+            None => None,
+        };
+        Some(RangePattern {
+            minimum,
+            maximum,
+            kind: RangeKind::Timestamp,
+        })
+    }
+
+    // Write a value of the range, timestamps in RFC 3339 in UTC
+    pub fn format_value(&self, value: f64) -> String {
+        match self.kind {
+            RangeKind::Timestamp => {
+                let seconds = value.floor();
+                let nanoseconds = ((value - seconds) * 1e9).round() as u32;
+                match chrono::DateTime::from_timestamp(seconds as i64, nanoseconds.min(999_999_999))
+                {
+                    Some(timestamp) => {
+                        timestamp.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+                    }
+                    None => format!("{value}"),
+                }
+            }
+            _ => format!("{value}"),
+        }
+    }
+
+    // Write a boundary of the range, timestamps exactly
+    pub fn format_bound(&self, bound: &RangeBound) -> String {
+        match bound.timestamp {
+            Some(timestamp) => timestamp
+                .to_utc()
+                .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+            None => self.format_value(bound.value),
+        }
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        self.is_within(|bound| value.partial_cmp(&bound.value))
+    }
+
+    pub fn contains_timestamp(&self, timestamp: &DateTime<FixedOffset>) -> bool {
+        self.is_within(|bound| bound.timestamp.map(|boundary| timestamp.cmp(&boundary)))
+    }
+
+    // Whether a value is within the boundaries, given how it compares to
+    // each of them; values which cannot be compared are outside
+    fn is_within(&self, compare: impl Fn(&RangeBound) -> Option<Ordering>) -> bool {
+        let above_minimum = self.minimum.is_none_or(|left| match compare(&left) {
+            Some(Ordering::Greater) => true,
+            Some(Ordering::Equal) => left.inclusive,
+            _ => false,
+        });
+        let below_maximum = self.maximum.is_none_or(|right| match compare(&right) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Equal) => right.inclusive,
+            _ => false,
+        });
+        above_minimum && below_maximum
+    }
+}
+
 pub fn apply_range_matcher<TV>(
     parser: &mut Parser<TV>,
     node: &Yaml,
@@ -19,15 +182,6 @@ pub fn apply_range_matcher<TV>(
 ) -> Result<LidyResult<TV>, AnyBoxedError>
 where
 {
-    // Check that content is a number
-    let value = match &content.data {
-        YamlData::Integer(i) => *i as f64,
-        YamlData::Real(r) => must_parse_float(r),
-        _ => {
-            return Err(SimpleError::from_check("_range", "must be a number", content).into());
-        }
-    };
-
     let pattern_error =
         "the range pattern must be a valid range matcher string (_range: <pattern>)";
 
@@ -38,40 +192,67 @@ where
     };
 
     // Parse the range pattern
-    let captures = RANGE_REGEX.captures(pattern).expect(pattern_error);
+    let range = RangePattern::parse(pattern).expect(pattern_error);
 
-    let left_boundary: Option<f64> = captures.get(2).map(|m| m.as_str().parse::<f64>().unwrap());
-    let left_operator = captures.get(4).map_or("", |m| m.as_str());
-    let number_type = captures.get(5).unwrap().as_str();
-    let right_operator = captures.get(7).map_or("", |m| m.as_str());
-    let right_boundary: Option<f64> = captures.get(8).map(|m| m.as_str().parse::<f64>().unwrap());
+    // Timestamps are compared exactly with the boundaries, to the nanosecond
+    if range.kind == RangeKind::Timestamp {
+        let timestamp = match &content.data {
+            YamlData::String(text) | YamlData::Timestamp(text) => parse_timestamp(text),
+            _ => None,
+        };
+        let Some(timestamp) = timestamp else {
+            return Err(SimpleError::from_check("_range", "must be a timestamp", content).into());
+        };
+        if !range.contains_timestamp(&timestamp) {
+            return Err(SimpleError::from_check(
+                "_range",
+                "must be inside the specified range",
+                content,
+            )
+            .into());
+        }
+        return Ok(LidyResult::create(
+            parser,
+            content,
+            Data::Timestamp(timestamp),
+        ));
+    }
+
+    // Check that content is a number. Integers keep their decimal text to be
+    // compared exactly with the boundaries.
+    let (value, data, integer) = match &content.data {
+        YamlData::Integer(int) => (*int as f64, Data::Integer(*int), Some(int.to_string())),
+        YamlData::BigInteger(text) => match Data::from_big_integer(text) {
+            Some(data) => (must_parse_float(text), data, Some(text.clone())),
+            None => {
+                return Err(SimpleError::from_check(
+                    "_range",
+                    "must fit in 64 bits (enable the bigint feature)",
+                    content,
+                )
+                .into());
+            }
+        },
+        YamlData::Real(r) => {
+            let value = must_parse_float(r);
+            (value, Data::Float(value), None)
+        }
+        _ => {
+            return Err(SimpleError::from_check("_range", "must be a number", content).into());
+        }
+    };
 
     // Validate number type
-    if number_type == "int" && !value.trunc().eq(&value) {
+    if range.kind == RangeKind::Int && !value.trunc().eq(&value) {
         return Err(SimpleError::from_check("_range", "must be an integer", content).into());
     }
 
     // Check boundaries
-    let mut ok = true;
-    if let Some(left) = left_boundary {
-        ok = ok
-            && match left_operator {
-                "<" => left < value,
-                "<=" => left <= value,
-                _ => true,
-            };
-    }
-
-    if let Some(right) = right_boundary {
-        ok = ok
-            && match right_operator {
-                "<" => value < right,
-                "<=" => value <= right,
-                _ => true,
-            };
-    }
-
-    if !ok {
+    let is_inside = match &integer {
+        Some(integer) => is_integer_in_range(pattern, integer),
+        None => range.contains(value),
+    };
+    if !is_inside {
         return Err(SimpleError::from_check(
             "_range",
             "must be inside the specified range",
@@ -80,11 +261,53 @@ where
         .into());
     }
 
-    let data = match &content.data {
-        YamlData::Integer(int) => Data::Integer(*int),
-        YamlData::Real(_) => Data::Float(value),
-        _ => panic!("never, content is no longer a number"),
+    Ok(LidyResult::create(parser, content, data))
+}
+
+// Check an integer against the boundaries of a number range without going
+// through f64, which cannot hold every integer beyond 2^53
+fn is_integer_in_range(pattern: &str, integer: &str) -> bool {
+    let Some(captures) = RANGE_REGEX.captures(pattern) else {
+        return false;
+    };
+    let is_inclusive = |k: usize| captures.get(k).map_or("", |m| m.as_str()) == "<=";
+    let above_minimum = match captures.get(2) {
+        Some(bound) => match compare_to_bound(integer, bound.as_str()) {
+            Ordering::Greater => true,
+            Ordering::Equal => is_inclusive(4),
+            Ordering::Less => false,
+        },
+        None => true,
+    };
+    let below_maximum = match captures.get(8) {
+        Some(bound) => match compare_to_bound(integer, bound.as_str()) {
+            Ordering::Less => true,
+            Ordering::Equal => is_inclusive(7),
+            Ordering::Greater => false,
+        },
+        None => true,
     };
+    above_minimum && below_maximum
+}
 
-    Ok(LidyResult::create(parser, content, data))
+// Compare the decimal text of an integer with a boundary, which is a
+// non-negative decimal number
+fn compare_to_bound(integer: &str, bound: &str) -> Ordering {
+    if integer.starts_with('-') {
+        return Ordering::Less;
+    }
+    let (whole, fraction) = bound.split_once('.').unwrap_or((bound, ""));
+    let whole = whole.trim_start_matches('0');
+    let integer = integer.trim_start_matches('0');
+    integer
+        .len()
+        .cmp(&whole.len())
+        .then_with(|| integer.cmp(whole))
+        .then_with(|| {
+            if fraction.trim_end_matches('0').is_empty() {
+                Ordering::Equal
+            } else {
+                Ordering::Less
+            }
+        })
 }
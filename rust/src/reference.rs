@@ -0,0 +1,143 @@
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::{
+    any::map_any_yaml_data_to_lidy_data,
+    error::{AnyBoxedError, JoinError},
+    parser::Parser,
+    syaml::{same_value, to_flow_yaml},
+    LidyResult, SimpleError,
+};
+
+// The names collected by _defines and the names used by _references, each
+// with the key space they belong to. A reference may name a definition found
+// further down the content, so the references are resolved once the whole
+// content has been matched
+#[derive(Clone, Debug, Default)]
+pub struct CrossReferences {
+    definitions: Vec<(Box<str>, Yaml)>,
+    references: Vec<(Box<str>, Yaml)>,
+}
+
+// The number of definitions and references recorded at some point of the
+// matching, to forget those of an expression which did not match
+#[derive(Clone, Copy, Debug)]
+pub struct CrossReferenceMark {
+    definition_count: usize,
+    reference_count: usize,
+}
+
+impl CrossReferences {
+    pub fn mark(&self) -> CrossReferenceMark {
+        CrossReferenceMark {
+            definition_count: self.definitions.len(),
+            reference_count: self.references.len(),
+        }
+    }
+
+    pub fn rollback(&mut self, mark: CrossReferenceMark) {
+        self.definitions.truncate(mark.definition_count);
+        self.references.truncate(mark.reference_count);
+    }
+
+    pub fn is_defined(&self, space: &str, name: &Yaml) -> bool {
+        self.definitions
+            .iter()
+            .any(|(s, n)| &**s == space && same_value(n, name))
+    }
+
+    pub fn clear(&mut self) {
+        self.definitions.clear();
+        self.references.clear();
+    }
+
+    // Report each reference which names no definition of its key space, with
+    // the positions of the names which the space does define
+    pub fn check(&self) -> Result<(), AnyBoxedError> {
+        let mut join_error = JoinError::default();
+        for (space, reference) in &self.references {
+            if self.is_defined(space, reference) {
+                continue;
+            }
+            let definitions: Vec<&Yaml> = self
+                .definitions
+                .iter()
+                .filter(|(s, _)| s == space)
+                .map(|(_, name)| name)
+                .collect();
+            let defined = match definitions.len() {
+                0 => format!("no {space} is defined"),
+                _ => {
+                    let names: Vec<String> = definitions
+                        .iter()
+                        .map(|name| format!("{} at {}", to_flow_yaml(name), name.line_col))
+                        .collect();
+                    format!("defined: {}", names.join(", "))
+                }
+            };
+            join_error.add(
+                SimpleError::from_message(
+                    format!(
+                        "_references: no {space} is named {} {} ({defined})",
+                        to_flow_yaml(reference),
+                        reference.line_col
+                    )
+                    .into(),
+                )
+                .into(),
+            );
+        }
+        join_error.into_result()
+    }
+}
+
+pub fn apply_defines_matcher<TV>(
+    parser: &mut Parser<TV>,
+    node: &Yaml,
+    content: &Yaml,
+) -> Result<LidyResult<TV>, AnyBoxedError>
+where
+{
+    let space = expect_name("_defines", node, content)?;
+    parser
+        .cross_references
+        .definitions
+        .push((space, content.clone()));
+    Ok(name_result(parser, content))
+}
+
+pub fn apply_references_matcher<TV>(
+    parser: &mut Parser<TV>,
+    node: &Yaml,
+    content: &Yaml,
+) -> Result<LidyResult<TV>, AnyBoxedError>
+where
+{
+    let space = expect_name("_references", node, content)?;
+    parser
+        .cross_references
+        .references
+        .push((space, content.clone()));
+    Ok(name_result(parser, content))
+}
+
+// Names are scalars; the key space is the value of the keyword
+fn expect_name(keyword: &str, node: &Yaml, content: &Yaml) -> Result<Box<str>, AnyBoxedError> {
+    match &content.data {
+        YamlData::Mapping(_) | YamlData::List(_) | YamlData::Null | YamlData::BadValue => {
+            Err(SimpleError::from_check(keyword, "expected a scalar name", content).into())
+        }
+        _ => Ok(node.data.as_str().unwrap_or_default().into()),
+    }
+}
+
+fn name_result<TV>(parser: &Parser<TV>, content: &Yaml) -> LidyResult<TV> {
+    LidyResult::create(
+        parser,
+        content,
+        map_any_yaml_data_to_lidy_data(
+            &parser.content_file_name,
+            parser.rule_trace.last().unwrap(),
+            content,
+        ),
+    )
+}
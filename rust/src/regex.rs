@@ -1,23 +1,76 @@
-use lidy__yaml::{Yaml, YamlData};
+use std::collections::HashMap;
+
+use lidy__yaml::{LineCol, Yaml, YamlData};
 use regex::Regex;
 
-use crate::result::Data;
+use crate::result::{Data, MapData, Position};
+use crate::syaml::extract_kv_entry;
 use crate::{error::AnyBoxedError, parser::Parser, LidyResult, SimpleError};
 
+/// The flags `_regexFlags` accepts, as in the RE2 syntax `(?flags)`:
+/// case-insensitive, multi-line, dot matches new line and ignore whitespace
+pub const REGEX_FLAGS: &str = "imsx";
+
+/// A `_regex` checker, with its `_regexFlags` and `_regexFullMatch` options
+#[derive(Clone, Copy, Debug)]
+pub struct RegexChecker<'a> {
+    pub pattern: &'a str,
+    pub flags: &'a str,
+    pub full_match: bool,
+}
+
+impl<'a> RegexChecker<'a> {
+    pub fn from_mapping(mapping: &'a Vec<(Yaml, Yaml)>) -> Option<RegexChecker<'a>> {
+        let pattern = extract_kv_entry(mapping, "_regex")?.data.as_str()?;
+        let flags = extract_kv_entry(mapping, "_regexFlags")
+            .and_then(|flags| flags.data.as_str())
+            .unwrap_or_default();
+        let full_match = extract_kv_entry(mapping, "_regexFullMatch")
+            .and_then(|full_match| full_match.data.as_bool())
+            .unwrap_or(false);
+        Some(RegexChecker {
+            pattern,
+            flags,
+            full_match,
+        })
+    }
+
+    /// The pattern with the flags and the full-match anchors written in it
+    pub fn effective_pattern(&self) -> String {
+        let mut pattern = String::new();
+        if !self.flags.is_empty() {
+            pattern.push_str(&format!("(?{})", self.flags));
+        }
+        if self.full_match {
+            pattern.push_str(&format!(r"\A(?:{})\z", self.pattern));
+        } else {
+            pattern.push_str(self.pattern);
+        }
+        pattern
+    }
+
+    pub fn compile(&self) -> Result<Regex, regex::Error> {
+        Regex::new(&self.effective_pattern())
+    }
+
+    /// The regex as written in messages, e.g. `/^[a-z]+$/i`
+    pub fn display(&self) -> String {
+        format!("/{}/{}", self.pattern, self.flags)
+    }
+}
+
 pub fn apply_regex_matcher<TV>(
     parser: &mut Parser<TV>,
-    node: &Yaml,
+    mapping: &Vec<(Yaml, Yaml)>,
     content: &Yaml,
 ) -> Result<LidyResult<TV>, AnyBoxedError>
 where
 {
-    // Obtain the regex pattern as string from the schema node
-    let pattern = match &node.data {
-        YamlData::String(s) => s,
-        _ => panic!("expected schema node to be a string for (_regex: <pattern>)"),
-    };
-    // Compile the regex pattern
-    let regex = Regex::new(pattern).expect("invalid regex pattern");
+    // Obtain the regex pattern and its options from the schema node
+    let checker = RegexChecker::from_mapping(mapping)
+        .expect("expected schema node to be a string for (_regex: <pattern>)");
+    // The metaparser made sure that the pattern compiles
+    let regex = checker.compile()?;
 
     // Check that the content node is a string
     let content_str = match &content.data {
@@ -28,18 +81,98 @@ where
     };
 
     // Test the regex against the content
-    if regex.is_match(content_str) {
-        Ok(LidyResult::create(
-            parser,
-            content,
-            Data::String(content_str.clone().into()),
-        ))
-    } else {
-        Err(SimpleError::from_check(
+    let Some(captures) = regex.captures(content_str) else {
+        return Err(SimpleError::from_check(
             "_regex",
-            &format!("must match regex /{}/", pattern),
+            &format!(
+                "must {}match regex {}",
+                if checker.full_match { "fully " } else { "" },
+                checker.display()
+            ),
             content,
         )
-        .into())
+        .into());
+    };
+
+    // Without named groups, the result is the string itself
+    if regex.capture_names().flatten().next().is_none() {
+        return Ok(LidyResult::create(
+            parser,
+            content,
+            Data::String(content_str.clone().into()),
+        ));
     }
+
+    // Each named group which took part in the match becomes an entry, placed
+    // where the group starts in the string
+    let rule_name = parser.rule_trace.last().unwrap().clone();
+    let mut map = HashMap::new();
+    for name in regex.capture_names().flatten() {
+        if let Some(group) = captures.name(name) {
+            let (line, column) = offset_line_col(
+                &parser.content_source,
+                content,
+                &content_str[..group.start()],
+            );
+            let position = Position {
+                filename: parser.content_file_name.clone(),
+                line,
+                column,
+                line_end: line,
+                column_end: column + group.as_str().chars().count(),
+            };
+            map.insert(
+                name.into(),
+                LidyResult::make(&rule_name, position, Data::String(group.as_str().into())),
+            );
+        }
+    }
+    Ok(LidyResult::create(
+        parser,
+        content,
+        Data::MapData(MapData {
+            map,
+            map_of: Vec::new(),
+        }),
+    ))
+}
+
+// The line and column in the content file of the character which follows
+// the prefix of a string. A quoted scalar starts after its opening quote, and
+// a literal block scalar on the line after its indicator, at the indentation
+// of its first line. The columns are exact when the string has no escape
+// sequences; the groups of a folded scalar, and those which follow a line
+// break of a plain or quoted scalar, take the position of the string.
+fn offset_line_col(source: &str, content: &Yaml, prefix: &str) -> (usize, usize) {
+    let LineCol { line, column } = content.line_col;
+    let mut lines = source.lines().skip(line.saturating_sub(1));
+    let start: String = lines
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .skip(column)
+        .collect();
+    let columns = |text: &str| text.chars().count();
+
+    if start.starts_with('|') {
+        let Some(indent) = lines
+            .find(|text| !text.trim().is_empty())
+            .map(|text| columns(text) - columns(text.trim_start_matches(' ')))
+        else {
+            return (line, column);
+        };
+        let last = prefix.rsplit('\n').next().unwrap_or_default();
+        return (
+            line + 1 + prefix.matches('\n').count(),
+            indent + columns(last),
+        );
+    }
+    if prefix.contains('\n') || start.starts_with('>') {
+        return (line, column);
+    }
+    let quote = match start.chars().next() {
+        Some('\'' | '"') if !start.starts_with("\"\"\"") && !start.starts_with("'''") => 1,
+        _ => 0,
+    };
+    (line, column + quote + columns(prefix))
 }
@@ -1,5 +1,7 @@
 use std::{collections::HashMap, rc::Rc};
 
+use chrono::{DateTime, FixedOffset};
+
 use lidy__yaml::{LineCol, Yaml};
 
 use crate::parser::Parser;
@@ -57,6 +59,9 @@ pub struct LidyResult<TV> {
     pub position: Position,
     pub rule_name: Box<str>,
     pub data: Data<TV>,
+    // Whether the result comes from a `_mapDefault` value of the schema
+    // rather than from the content. Its position is then in the schema file.
+    pub is_default: bool,
 }
 
 impl<TV> LidyResult<TV> {
@@ -65,6 +70,7 @@ impl<TV> LidyResult<TV> {
             position,
             rule_name: rule_name.into(),
             data,
+            is_default: false,
         }
     }
     pub fn create(parser: &Parser<TV>, content: &Yaml, data: Data<TV>) -> LidyResult<TV>
@@ -76,6 +82,7 @@ where {
             ),
             rule_name: parser.rule_trace.last().unwrap().clone(),
             data,
+            is_default: false,
         }
     }
 }
@@ -102,10 +109,38 @@ pub struct ListData<TV> {
 pub enum Data<TV> {
     Float(f64),
     Integer(i64),
+    // An integer above i64::MAX which fits in 64 bits
+    UnsignedInteger(u64),
+    // An integer beyond 64 bits, with the bigint feature
+    #[cfg(feature = "bigint")]
+    BigInteger(num_bigint::BigInt),
     String(Box<str>),
+    // The decoded bytes of the binary rule
+    Binary(Vec<u8>),
+    // The date and time of the timestamp rule. A timestamp without time zone
+    // is in UTC.
+    Timestamp(DateTime<FixedOffset>),
     Boolean(bool),
     Null,
     MapData(MapData<TV>),
     ListData(ListData<TV>),
     CustomData(TV),
 }
+
+impl<TV> Data<TV> {
+    /// The data of an integer beyond the range of i64, given as its decimal
+    /// text. Without the bigint feature, integers beyond 64 bits have no data.
+    pub fn from_big_integer(text: &str) -> Option<Data<TV>> {
+        if let Ok(value) = text.parse::<u64>() {
+            return Some(Data::UnsignedInteger(value));
+        }
+        #[cfg(feature = "bigint")]
+        {
+            num_bigint::BigInt::parse_bytes(text.as_bytes(), 10).map(Data::BigInteger)
+        }
+        #[cfg(not(feature = "bigint"))]
+        {
+            None
+        }
+    }
+}
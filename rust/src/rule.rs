@@ -1,16 +1,12 @@
 use crate::any::map_any_yaml_data_to_lidy_data;
 use crate::error::AnyBoxedError;
 use crate::expression::apply_expression;
+use crate::format::{apply_format_rule, is_format_rule};
 use crate::parser::{Parser, RuleNodePair};
 use crate::result::{Data, LidyResult, Position};
-use crate::syaml::must_parse_float;
+use crate::syaml::{must_parse_float, parse_timestamp};
 use crate::SimpleError;
-use lidy__yaml::{Yaml, YamlData};
-use regex::Regex;
-
-lazy_static::lazy_static! {
-    static ref REGEX_BASE64: Regex = Regex::new(r"^[a-zA-Z0-9_\- \n]*[= \n]*$").unwrap();
-}
+use lidy__yaml::{decode_base64, Yaml, YamlData};
 
 #[derive(Clone, Debug)]
 pub struct Rule {
@@ -62,13 +58,17 @@ fn try_apply_rule<TV>(
             parser
                 .rule_is_matching_node
                 .insert(rule_node_pair.clone(), ());
-            let mut lidy_result = apply_expression(parser, &rule.node, content).map_err(|err| {
+            let outcome = apply_expression(parser, &rule.node, content);
+            // Forget the pair on failure too, or the other alternatives of a
+            // _oneOf would see a loop when they apply this rule to the node
+            parser.rule_is_matching_node.remove(&rule_node_pair);
+            let mut lidy_result = outcome.map_err(|err| {
                 SimpleError::from_check_result(rule_name, &err.to_string(), content.line_col)
             })?;
 
-            parser.rule_is_matching_node.remove(&rule_node_pair);
-
-            lidy_result.data = (parser.builder_callback)(rule_name, &lidy_result)?;
+            if !parser.skip_builders {
+                lidy_result.data = (parser.builder_callback)(rule_name, &lidy_result)?;
+            }
 
             Ok(lidy_result)
         }
@@ -94,32 +94,32 @@ where
                 Err(("expected a string").into())
             }
         })),
-        "int" => Some(Box::new(|content: &Yaml| {
-            if let YamlData::Integer(value) = &content.data {
-                Ok(Data::Integer(*value))
-            } else {
-                Err("expected an integer".into())
-            }
+        "int" => Some(Box::new(|content: &Yaml| match &content.data {
+            YamlData::Integer(value) => Ok(Data::Integer(*value)),
+            YamlData::BigInteger(text) => Data::from_big_integer(text).ok_or_else(|| {
+                format!("the integer {text} does not fit in 64 bits (enable the bigint feature)")
+                    .into()
+            }),
+            _ => Err("expected an integer".into()),
         })),
         "float" => Some(Box::new(|content: &Yaml| {
             if let YamlData::Real(value) = &content.data {
                 Ok(Data::Float(must_parse_float(value)))
             } else if let YamlData::Integer(value) = &content.data {
                 Ok(Data::Float(*value as f64))
+            } else if let YamlData::BigInteger(text) = &content.data {
+                Ok(Data::Float(must_parse_float(text)))
             } else {
                 Err("expected a float".into())
             }
         })),
-        "binary" => Some(Box::new(|content: &Yaml| {
-            if let YamlData::String(value) = &content.data {
-                if REGEX_BASE64.is_match(value) {
-                    Ok(Data::String((value.clone()).into()))
-                } else {
-                    Err("expected a base64 value".into())
-                }
-            } else {
-                Err("expected a binary or string value".into())
-            }
+        "binary" => Some(Box::new(|content: &Yaml| match &content.data {
+            YamlData::Binary(bytes) => Ok(Data::Binary(bytes.clone())),
+            YamlData::String(value) => match decode_base64(value) {
+                Ok(bytes) => Ok(Data::Binary(bytes)),
+                Err(reason) => Err(format!("expected a base64 value ({reason})").into()),
+            },
+            _ => Err("expected a binary or string value".into()),
         })),
         "boolean" => Some(Box::new(|content: &Yaml| {
             if let YamlData::Boolean(b) = &content.data {
@@ -136,13 +136,16 @@ where
             }
         })),
         "timestamp" => Some(Box::new(|content: &Yaml| {
-            if let YamlData::String(value) = &content.data {
-                match chrono::DateTime::parse_from_rfc3339(value) {
-                    Ok(_) => Ok(Data::String((value.clone()).into())),
-                    Err(_) => Err("invalid timestamp format - must be RFC3339/ISO8601".into()),
+            // YamlData::Timestamp holds the native datetimes of the TOML front end
+            if let YamlData::String(value) | YamlData::Timestamp(value) = &content.data {
+                match parse_timestamp(value) {
+                    Some(timestamp) => Ok(Data::Timestamp(timestamp)),
+                    None => Err(
+                        "invalid timestamp format - must be a YAML timestamp, such as 2002-12-14 or 2001-12-14T21:59:43.10-05:00".into(),
+                    ),
                 }
             } else {
-                Err("expected a timestamp string (an ISO 8601 datetime)".into())
+                Err("expected a timestamp string (an ISO 8601 date or datetime)".into())
             }
         })),
         "any" => Some(Box::new(|_: &Yaml| Ok(Data::Null))),
@@ -159,6 +162,9 @@ where
         "never" => Some(Box::new(|_: &Yaml| {
             Err("encountered the never value".into())
         })),
+        name if is_format_rule(name) => Some(Box::new(move |content: &Yaml| {
+            apply_format_rule(name, content)
+        })),
         _ => None,
     };
 
@@ -167,6 +173,7 @@ where
             rule_name: "".into(),
             position: Position::default(),
             data: Data::Boolean(predefined_rule.is_some()),
+            is_default: false,
         });
     }
 
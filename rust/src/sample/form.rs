@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::discriminator::read_discriminator;
+use crate::error::{AnyBoxedError, SimpleError};
+use crate::map::resolve_merge_reference;
+use crate::regex::RegexChecker;
+use crate::rule::Rule;
+use crate::syaml::{extract_kv_entry, same_value};
+
+// The checker forms of lidy expressions, as read by apply_expression
+pub enum Form<'a> {
+    Rule(&'a str),
+    // A _regex or _string checker, with the size keywords which bound its
+    // number of characters
+    Regex(RegexChecker<'a>, Size),
+    String(&'a Yaml, Size),
+    In(&'a [Yaml]),
+    Const(&'a Yaml),
+    Range(&'a str),
+    OneOf(&'a [Yaml]),
+    AllOf(&'a [Yaml]),
+    Not(&'a Yaml),
+    // The key space of a _defines or a _references checker
+    Defines(&'a str),
+    References(&'a str),
+    // The discriminator key and the _cases of a _discriminator checker
+    Discriminator(&'a str, &'a [(Yaml, Yaml)]),
+    Map(MapForm<'a>),
+    List(ListForm<'a>),
+}
+
+pub struct MapForm<'a> {
+    pub map: Option<&'a Yaml>,
+    pub map_facultative: Option<&'a Yaml>,
+    pub map_of: &'a [(Yaml, Yaml)],
+    pub merge: Option<&'a Yaml>,
+    pub size: Size,
+}
+
+pub struct ListForm<'a> {
+    pub list: &'a [Yaml],
+    pub list_facultative: &'a [Yaml],
+    pub list_of: Option<&'a Yaml>,
+    pub size: Size,
+    // _unique, and the key of _uniqueBy
+    pub unique: bool,
+    pub unique_by: Option<&'a str>,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Size {
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+    pub nb: Option<usize>,
+}
+
+pub struct MapKey<'a> {
+    pub name: &'a str,
+    pub expression: &'a Yaml,
+    pub mandatory: bool,
+}
+
+pub fn read_form(expression: &Yaml) -> Result<Form<'_>, AnyBoxedError> {
+    let mapping = match &expression.data {
+        YamlData::String(name) => return Ok(Form::Rule(name)),
+        YamlData::Mapping(mapping) => mapping,
+        _ => {
+            return Err(SimpleError::from_check(
+                "expression",
+                "must be a rule name or a checker",
+                expression,
+            )
+            .into())
+        }
+    };
+
+    let mut map_form = MapForm {
+        map: None,
+        map_facultative: None,
+        map_of: &[],
+        merge: None,
+        size: Size::default(),
+    };
+    let mut list_form = ListForm {
+        list: &[],
+        list_facultative: &[],
+        list_of: None,
+        size: Size::default(),
+        unique: false,
+        unique_by: None,
+    };
+    let mut is_mapping = false;
+    let mut is_list = false;
+    let mut size = Size::default();
+    let mut regex = None;
+    let mut string = None;
+
+    for (key, value) in mapping {
+        let Some(keyword) = key.data.as_str() else {
+            continue;
+        };
+        let count = || value.data.as_i64().map(|n| n.max(0) as usize);
+        match (keyword, &value.data) {
+            ("_regex", YamlData::String(_)) => regex = RegexChecker::from_mapping(mapping),
+            ("_string", _) => string = Some(value),
+            ("_in", YamlData::List(values)) => return Ok(Form::In(values)),
+            ("_const", _) => return Ok(Form::Const(value)),
+            ("_range", YamlData::String(pattern)) => return Ok(Form::Range(pattern)),
+            ("_oneOf", YamlData::List(alternatives)) => return Ok(Form::OneOf(alternatives)),
+            ("_allOf", YamlData::List(expressions)) => return Ok(Form::AllOf(expressions)),
+            ("_not", _) => return Ok(Form::Not(value)),
+            ("_defines", YamlData::String(space)) => return Ok(Form::Defines(space)),
+            ("_references", YamlData::String(space)) => return Ok(Form::References(space)),
+            ("_discriminator", _) => {
+                if let Some((key, cases)) = read_discriminator(mapping) {
+                    return Ok(Form::Discriminator(key, cases));
+                }
+            }
+            ("_map", _) => map_form.map = Some(value),
+            ("_mapFacultative", _) => map_form.map_facultative = Some(value),
+            ("_mapOf", YamlData::Mapping(associations)) => map_form.map_of = associations,
+            ("_merge", _) => map_form.merge = Some(value),
+            ("_list", YamlData::List(items)) => list_form.list = items,
+            ("_listFacultative", YamlData::List(items)) => list_form.list_facultative = items,
+            ("_listOf", _) => list_form.list_of = Some(value),
+            ("_unique", YamlData::Boolean(unique)) => list_form.unique = *unique,
+            ("_uniqueBy", YamlData::String(key)) => list_form.unique_by = Some(key),
+            ("_min", _) => size.min = count(),
+            ("_max", _) => size.max = count(),
+            ("_nb", _) => size.nb = count(),
+            // Read along with _regex and _discriminator
+            ("_regexFlags" | "_regexFullMatch" | "_cases", _) => {}
+            // Read with collect_key_constraints, along with those of the
+            // merged checkers
+            ("_dependentRequired" | "_exactlyOneOf" | "_atMostOneOf" | "_mutuallyExclusive", _) => {
+            }
+            // Default values are applied by the parser, and annotations do
+            // not constrain the content
+            ("_mapDefault" | "_title" | "_description" | "_examples" | "_deprecated", _) => {}
+            _ => {
+                return Err(SimpleError::from_check(
+                    keyword,
+                    "unknown or malformed keyword in checker",
+                    key,
+                )
+                .into())
+            }
+        }
+        is_mapping |= matches!(keyword, "_map" | "_mapFacultative" | "_mapOf" | "_merge");
+        is_list |= matches!(
+            keyword,
+            "_list" | "_listFacultative" | "_listOf" | "_unique" | "_uniqueBy"
+        );
+    }
+
+    if let Some(checker) = regex {
+        Ok(Form::Regex(checker, size))
+    } else if let Some(expression) = string {
+        Ok(Form::String(expression, size))
+    } else if is_mapping && !is_list {
+        map_form.size = size;
+        Ok(Form::Map(map_form))
+    } else if is_list && !is_mapping {
+        list_form.size = size;
+        Ok(Form::List(list_form))
+    } else {
+        Err(SimpleError::from_check(
+            "_(map*|list*)",
+            "the checker must be either a map checker or a list checker",
+            expression,
+        )
+        .into())
+    }
+}
+
+// A few values of each kind, among which the values of a _not checker are
+// drawn
+pub fn simple_values() -> Vec<YamlData> {
+    vec![
+        YamlData::Null,
+        YamlData::Boolean(false),
+        YamlData::Integer(0),
+        YamlData::Real("0.5".into()),
+        YamlData::String("".into()),
+        YamlData::String("-".into()),
+        YamlData::List(Vec::new()),
+        YamlData::Mapping(Vec::new()),
+    ]
+}
+
+impl ListForm<'_> {
+    // Whether the uniqueness keywords forbid adding the candidate to the items
+    pub fn is_duplicate(&self, items: &[Yaml], candidate: &Yaml) -> bool {
+        let key_value = |item: &'_ Yaml| match (&item.data, self.unique_by) {
+            (YamlData::Mapping(mapping), Some(key)) => extract_kv_entry(mapping, key).cloned(),
+            _ => None,
+        };
+        items.iter().any(|item| {
+            (self.unique && same_value(item, candidate))
+                || matches!(
+                    (key_value(item), key_value(candidate)),
+                    (Some(a), Some(b)) if same_value(&a, &b)
+                )
+        })
+    }
+}
+
+impl Size {
+    // The smallest and largest number of entries of a container, or of
+    // characters of a string, which has
+    // `required` mandatory entries and room for `capacity` entries
+    pub fn bounds(&self, required: usize, capacity: Option<usize>) -> (usize, Option<usize>) {
+        let lower = self.nb.or(self.min).unwrap_or(0).max(required);
+        let upper = [self.nb, self.max, capacity].into_iter().flatten().min();
+        (lower, upper)
+    }
+
+    pub fn admits(&self, size: usize) -> bool {
+        let (lower, upper) = self.bounds(0, None);
+        size >= lower && upper.is_none_or(|upper| size <= upper)
+    }
+}
+
+// The keys declared by a map checker and the checkers it merges, with the
+// precedence of apply_map_matcher: merged checkers come first, then _map,
+// then the _mapFacultative keys which are not mandatory
+pub fn map_keys<'a>(
+    rule_set: &'a HashMap<Box<str>, Rule>,
+    form: &MapForm<'a>,
+) -> Result<Vec<MapKey<'a>>, AnyBoxedError> {
+    let mut keys = Vec::new();
+    contribute_map_keys(
+        rule_set,
+        &mut keys,
+        form.map,
+        form.map_facultative,
+        form.merge,
+    )?;
+    Ok(keys)
+}
+
+fn contribute_map_keys<'a>(
+    rule_set: &'a HashMap<Box<str>, Rule>,
+    keys: &mut Vec<MapKey<'a>>,
+    map: Option<&'a Yaml>,
+    map_facultative: Option<&'a Yaml>,
+    merge: Option<&'a Yaml>,
+) -> Result<(), AnyBoxedError> {
+    if let Some(YamlData::List(merge_list)) = merge.map(|node| &node.data) {
+        for node in merge_list {
+            let merged = resolve_merge_reference(rule_set, node)?;
+            contribute_map_keys(
+                rule_set,
+                keys,
+                extract_kv_entry(merged, "_map"),
+                extract_kv_entry(merged, "_mapFacultative"),
+                extract_kv_entry(merged, "_merge"),
+            )?;
+        }
+    }
+    for (node, mandatory) in [(map, true), (map_facultative, false)] {
+        let Some(YamlData::Mapping(mapping)) = node.map(|node| &node.data) else {
+            continue;
+        };
+        for (key, expression) in mapping {
+            let Some(name) = key.data.as_str() else {
+                continue;
+            };
+            match keys.iter_mut().find(|key| key.name == name) {
+                Some(key) if key.mandatory && !mandatory => {}
+                Some(key) => {
+                    key.expression = expression;
+                    key.mandatory = mandatory;
+                }
+                None => keys.push(MapKey {
+                    name,
+                    expression,
+                    mandatory,
+                }),
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,911 @@
+use std::collections::HashMap;
+
+use lidy__yaml::{Yaml, YamlData};
+use regex::Regex;
+
+use crate::error::{AnyBoxedError, SimpleError};
+use crate::expression::apply_expression;
+use crate::format::is_format_rule;
+use crate::key_constraint::{apply_key_constraints, collect_key_constraints, key_condition};
+use crate::parser::Parser;
+use crate::range::{RangeKind, RangePattern};
+use crate::reference::CrossReferences;
+use crate::regex::RegexChecker;
+use crate::rule::Rule;
+use crate::syaml::{to_block_yaml, to_flow_yaml};
+
+use super::form::{map_keys, read_form, simple_values, Form, ListForm, MapForm, MapKey, Size};
+use super::pattern::generate_matching_string;
+use super::rng::Rng;
+
+const DEFAULT_MAX_DEPTH: usize = 6;
+// How many entries a container may hold beyond the ones the schema requires
+const EXTRA_ENTRIES: usize = 3;
+// How many times a string or a map key is drawn again before giving up
+const ATTEMPTS: usize = 20;
+// The spread of numbers when a range has no bound on one side
+const SPREAD: f64 = 1000.0;
+
+/// Generates random YAML documents that a lidy schema accepts.
+///
+/// Generators created with the same seed produce the same documents. Beyond
+/// the maximum depth, the generator only adds the nested values the schema
+/// requires, choosing the alternatives that finish the soonest.
+pub struct SampleGenerator<'a> {
+    rule_set: &'a HashMap<Box<str>, Rule>,
+    rng: Rng,
+    max_depth: usize,
+    // The smallest nesting of the values of each rule. Rules which accept no
+    // finite value are absent
+    heights: HashMap<&'a str, usize>,
+    regexes: HashMap<String, Regex>,
+    // How many names each key space of _defines holds in the document being
+    // generated
+    name_counts: HashMap<&'a str, usize>,
+}
+
+impl<'a> SampleGenerator<'a> {
+    pub fn new(rule_set: &'a HashMap<Box<str>, Rule>, seed: u64) -> SampleGenerator<'a> {
+        let mut generator = SampleGenerator {
+            rule_set,
+            rng: Rng::new(seed),
+            max_depth: DEFAULT_MAX_DEPTH,
+            heights: HashMap::new(),
+            regexes: HashMap::new(),
+            name_counts: HashMap::new(),
+        };
+        generator.compute_heights();
+        generator
+    }
+
+    /// Set how many containers deep the generator adds entries the schema
+    /// does not require
+    pub fn with_max_depth(mut self, max_depth: usize) -> SampleGenerator<'a> {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Generate a document accepted by the `main` rule
+    pub fn generate(&mut self) -> Result<String, AnyBoxedError> {
+        self.generate_rule("main")
+    }
+
+    /// Generate a document accepted by the given rule
+    pub fn generate_rule(&mut self, rule_name: &str) -> Result<String, AnyBoxedError> {
+        self.name_counts.clear();
+        let value = self.rule(rule_name, self.max_depth)?;
+        Ok(to_block_yaml(&value))
+    }
+
+    // Heights are the least fixed point of the height equations: start with
+    // no rule known to have a finite value, and lower the heights until
+    // nothing changes
+    fn compute_heights(&mut self) {
+        loop {
+            let mut changed = false;
+            for (name, rule) in self.rule_set {
+                if let Some(height) = self.height(&rule.node) {
+                    if self.heights.get(&**name).is_none_or(|&h| height < h) {
+                        self.heights.insert(name, height);
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    // The smallest nesting of the values of an expression, counting one for
+    // each container and for each reference to a rule of the schema
+    fn height(&self, expression: &'a Yaml) -> Option<usize> {
+        match read_form(expression).ok()? {
+            Form::Rule(name) => match self.rule_set.get(name) {
+                Some(_) => self.heights.get(name).map(|h| h + 1),
+                None if is_predefined_rule(name) && name != "never" => Some(0),
+                None => None,
+            },
+            Form::Regex(..) | Form::Range(_) => Some(0),
+            Form::String(expression, _) => self.height(expression),
+            Form::In(values) => (!values.is_empty()).then_some(0),
+            Form::Const(_) | Form::Defines(_) | Form::References(_) => Some(0),
+            Form::OneOf(alternatives) => alternatives.iter().filter_map(|a| self.height(a)).min(),
+            Form::AllOf(expressions) => {
+                let heights = expressions.iter().map(|e| self.height(e));
+                heights
+                    .collect::<Option<Vec<usize>>>()?
+                    .into_iter()
+                    .max()
+                    .or(Some(0))
+            }
+            // The values of a _not checker are drawn among simple values
+            Form::Not(_) => Some(0),
+            Form::Discriminator(_, cases) => {
+                cases.iter().filter_map(|(_, case)| self.height(case)).min()
+            }
+            Form::Map(form) => {
+                let keys = map_keys(self.rule_set, &form).ok()?;
+                let mandatory = keys.iter().filter(|key| key.mandatory);
+                let mut height = 0;
+                for key in mandatory.clone() {
+                    height = height.max(self.height(key.expression)?);
+                }
+                let (lower, _) = form.size.bounds(mandatory.count(), None);
+                if lower > keys.iter().filter(|key| key.mandatory).count() {
+                    let optional = keys.iter().filter(|key| !key.mandatory);
+                    let associations = form.map_of.iter().filter_map(|(key, value)| {
+                        Some(self.height(key)?.max(self.height(value)?))
+                    });
+                    let filler = optional
+                        .filter_map(|key| self.height(key.expression))
+                        .chain(associations)
+                        .min()?;
+                    height = height.max(filler);
+                }
+                Some(height + 1)
+            }
+            Form::List(form) => {
+                let (lower, _) = form.size.bounds(form.list.len(), None);
+                let facultative = form.list_facultative.iter();
+                let mut height = 0;
+                for item in form.list.iter().chain(facultative).take(lower) {
+                    height = height.max(self.height(item)?);
+                }
+                if lower > form.list.len() + form.list_facultative.len() {
+                    height = height.max(self.height(form.list_of?)?);
+                }
+                Some(height + 1)
+            }
+        }
+    }
+
+    fn expression(&mut self, expression: &'a Yaml, depth: usize) -> Result<Yaml, AnyBoxedError> {
+        match read_form(expression)? {
+            Form::Rule(name) => self.rule(name, depth),
+            Form::Regex(checker, size) => self.regex(&checker, &size, expression),
+            Form::String(inner, size) => self.string(inner, &size, expression, depth),
+            Form::In([]) => {
+                Err(SimpleError::from_check("_in", "no value is accepted", expression).into())
+            }
+            Form::In(values) => Ok(self.rng.pick(values).clone()),
+            Form::Const(value) => Ok(value.clone()),
+            Form::Range(pattern) => self.range(pattern, expression),
+            Form::OneOf(alternatives) => {
+                let alternative = self.choose(alternatives, depth).ok_or_else(|| {
+                    SimpleError::from_check("_oneOf", "no alternative accepts a value", expression)
+                })?;
+                self.expression(alternative, depth)
+            }
+            Form::AllOf(expressions) => self.all_of(expressions, expression, depth),
+            Form::Not(inner) => self.not(inner, expression),
+            Form::Defines(space) => Ok(self.defines(space)),
+            Form::References(space) => Ok(self.references(space)),
+            Form::Discriminator(key, cases) => self.discriminator(key, cases, expression, depth),
+            Form::Map(form) => self.map(&form, expression, depth),
+            Form::List(form) => self.list(&form, expression, depth),
+        }
+    }
+
+    // Pick one of the expressions which accept a value. Once the depth budget
+    // is spent, pick the one with the smallest values
+    fn choose(&mut self, expressions: &'a [Yaml], depth: usize) -> Option<&'a Yaml> {
+        let candidates: Vec<(&Yaml, usize)> = expressions
+            .iter()
+            .filter_map(|e| Some((e, self.height(e)?)))
+            .collect();
+        if depth == 0 {
+            candidates.iter().min_by_key(|(_, h)| *h).map(|(e, _)| *e)
+        } else if candidates.is_empty() {
+            None
+        } else {
+            Some(self.rng.pick(&candidates).0)
+        }
+    }
+
+    fn rule(&mut self, rule_name: &str, depth: usize) -> Result<Yaml, AnyBoxedError> {
+        let rule_set = self.rule_set;
+        match rule_set.get(rule_name) {
+            Some(rule) => self
+                .expression(&rule.node, depth)
+                .map_err(|e| SimpleError::from_message(format!("{rule_name}: {e}").into()).into()),
+            None => self.predefined_rule(rule_name),
+        }
+    }
+
+    fn predefined_rule(&mut self, rule_name: &str) -> Result<Yaml, AnyBoxedError> {
+        let data = match rule_name {
+            "string" => YamlData::String(self.word()),
+            "int" => YamlData::Integer(self.rng.between(-SPREAD as i64, SPREAD as i64)),
+            "float" => YamlData::Real(format_float(self.float(-SPREAD, SPREAD))),
+            "binary" => YamlData::Binary(self.bytes()),
+            "boolean" => YamlData::Boolean(self.rng.chance(0.5)),
+            "nullType" => YamlData::Null,
+            "timestamp" => {
+                // Between 1970 and 2100
+                let seconds = self.rng.between(0, 4_102_444_800);
+                let timestamp = chrono::DateTime::from_timestamp(seconds, 0).unwrap();
+                YamlData::String(timestamp.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            }
+            "any" | "anyData" => {
+                let scalar = *self
+                    .rng
+                    .pick(&["string", "int", "float", "boolean", "nullType"]);
+                return self.predefined_rule(scalar);
+            }
+            "never" => return Err("never: no value is accepted".into()),
+            "port" => YamlData::Integer(self.rng.between(1, 65535)),
+            name if is_format_rule(name) => YamlData::String(self.format(name)),
+            _ => return Err(format!("rule '{rule_name}' not found in the schema").into()),
+        };
+        Ok(leaf(data))
+    }
+
+    // A string of one of the format rules
+    fn format(&mut self, name: &str) -> String {
+        match name {
+            "email" => format!("{}@example.com", self.label()),
+            "uri" => format!("https://{}.example.com/{}", self.label(), self.label()),
+            "uuid" => [8, 4, 4, 4, 12]
+                .map(|length| {
+                    (0..length)
+                        .map(|_| *self.rng.pick(b"0123456789abcdef") as char)
+                        .collect::<String>()
+                })
+                .join("-"),
+            "ipv4" => self.ipv4(),
+            "ipv6" => (0..8)
+                .map(|_| format!("{:x}", self.rng.between(0, 0xffff)))
+                .collect::<Vec<_>>()
+                .join(":"),
+            "cidr" => format!("{}/{}", self.ipv4(), self.rng.between(8, 32)),
+            "hostname" => format!("{}.example.com", self.label()),
+            "semver" => format!(
+                "{}.{}.{}",
+                self.rng.between(0, 9),
+                self.rng.between(0, 20),
+                self.rng.between(0, 20)
+            ),
+            "date" => {
+                // Between 1970 and 2100
+                let days = self.rng.between(0, 47_481) as u64;
+                let date =
+                    chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap() + chrono::Days::new(days);
+                date.format("%Y-%m-%d").to_string()
+            }
+            "time" => format!(
+                "{:02}:{:02}:{:02}",
+                self.rng.between(0, 23),
+                self.rng.between(0, 59),
+                self.rng.between(0, 59)
+            ),
+            "duration" => {
+                let (hours, minutes) = (self.rng.between(0, 23), self.rng.between(0, 59));
+                if self.rng.chance(0.5) {
+                    format!("{hours}h{minutes}m")
+                } else {
+                    format!("PT{hours}H{minutes}M")
+                }
+            }
+            "byteSize" => {
+                let number = self.rng.between(0, 1023);
+                format!("{number}{}", self.rng.pick(&["", "k", "Mi", "Gi"]))
+            }
+            "cron" => format!(
+                "{} {} * * *",
+                self.rng.between(0, 59),
+                self.rng.between(0, 23)
+            ),
+            _ => panic!("never, '{name}' is not a string format"),
+        }
+    }
+
+    fn label(&mut self) -> String {
+        let length = self.rng.between(1, 8);
+        (0..length)
+            .map(|_| *self.rng.pick(b"abcdefghijklmnopqrstuvwxyz") as char)
+            .collect()
+    }
+
+    fn ipv4(&mut self) -> String {
+        (0..4)
+            .map(|_| self.rng.between(0, 255).to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    fn word(&mut self) -> String {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789 -_";
+        let length = self.rng.between(0, 8);
+        (0..length)
+            .map(|_| *self.rng.pick(ALPHABET) as char)
+            .collect()
+    }
+
+    // Written as a !!binary scalar
+    fn bytes(&mut self) -> Vec<u8> {
+        let length = self.rng.between(0, 12);
+        (0..length)
+            .map(|_| self.rng.between(0, 255) as u8)
+            .collect()
+    }
+
+    fn float(&mut self, low: f64, high: f64) -> f64 {
+        low + self.rng.unit() * (high - low)
+    }
+
+    fn regex(
+        &mut self,
+        checker: &RegexChecker,
+        size: &Size,
+        node: &Yaml,
+    ) -> Result<Yaml, AnyBoxedError> {
+        let pattern = checker.effective_pattern();
+        if !self.regexes.contains_key(&pattern) {
+            let regex = checker
+                .compile()
+                .map_err(|e| SimpleError::from_check("_regex", &e.to_string(), node))?;
+            self.regexes.insert(pattern.clone(), regex);
+        }
+        for _ in 0..ATTEMPTS {
+            let candidate = generate_matching_string(&pattern, &mut self.rng)
+                .map_err(|e| SimpleError::from_check("_regex", &e, node))?;
+            if self.regexes[&pattern].is_match(&candidate) && size.admits(candidate.chars().count())
+            {
+                return Ok(leaf(YamlData::String(candidate)));
+            }
+        }
+        Err(SimpleError::from_check(
+            "_regex",
+            &format!("could not generate a string matching {}", checker.display()),
+            node,
+        )
+        .into())
+    }
+
+    // A string of the expression, whose number of characters the size
+    // keywords bound
+    fn string(
+        &mut self,
+        expression: &'a Yaml,
+        size: &Size,
+        node: &Yaml,
+        depth: usize,
+    ) -> Result<Yaml, AnyBoxedError> {
+        if expression.data.as_str() == Some("string") && !self.rule_set.contains_key("string") {
+            let (lower, upper) = size.bounds(0, None);
+            let upper = upper.unwrap_or(usize::MAX).min(lower + 8);
+            if lower <= upper {
+                let length = self.rng.between(lower as i64, upper as i64);
+                let word = (0..length).map(|_| (b'a' + self.rng.between(0, 25) as u8) as char);
+                return Ok(leaf(YamlData::String(word.collect())));
+            }
+        }
+        for _ in 0..ATTEMPTS {
+            let candidate = self.expression(expression, depth)?;
+            if let YamlData::String(text) = &candidate.data {
+                if size.admits(text.chars().count()) {
+                    return Ok(candidate);
+                }
+            }
+        }
+        Err(SimpleError::from_check(
+            "_string",
+            "could not generate a string of the expected length",
+            node,
+        )
+        .into())
+    }
+
+    // A parser to check the values drawn for the checkers which the
+    // generator cannot follow
+    fn parser(&self) -> Parser<'static, ()> {
+        Parser {
+            content_file_name: "".into(),
+            content_source: "".into(),
+            schema_file_name: "".into(),
+            rule_set: self.rule_set.clone(),
+            builder_callback: Box::new(|_, lidy_result| Ok(lidy_result.data.clone())),
+            // The checkers name their results after the current rule, which
+            // does not matter here
+            rule_trace: vec!["".into()],
+            rule_is_matching_node: HashMap::new(),
+            skip_builders: true,
+            cross_references: CrossReferences::default(),
+        }
+    }
+
+    // Draw values for each expression in turn until all the expressions
+    // accept one
+    fn all_of(
+        &mut self,
+        expressions: &'a [Yaml],
+        node: &Yaml,
+        depth: usize,
+    ) -> Result<Yaml, AnyBoxedError> {
+        if expressions.is_empty() {
+            return Ok(leaf(YamlData::Null));
+        }
+        let mut parser = self.parser();
+        for attempt in 0..ATTEMPTS {
+            let source = &expressions[attempt % expressions.len()];
+            let candidate = self.expression(source, depth)?;
+            if expressions
+                .iter()
+                .all(|e| apply_expression(&mut parser, e, &candidate).is_ok())
+            {
+                return Ok(candidate);
+            }
+        }
+        Err(SimpleError::from_check(
+            "_allOf",
+            "could not generate a value which all the expressions accept",
+            node,
+        )
+        .into())
+    }
+
+    fn not(&mut self, inner: &Yaml, node: &Yaml) -> Result<Yaml, AnyBoxedError> {
+        let mut parser = self.parser();
+        let candidates: Vec<YamlData> = simple_values()
+            .into_iter()
+            .filter(|data| apply_expression(&mut parser, inner, &leaf(data.clone())).is_err())
+            .collect();
+        if candidates.is_empty() {
+            return Err(SimpleError::from_check(
+                "_not",
+                "the negated expression accepts every simple value",
+                node,
+            )
+            .into());
+        }
+        Ok(leaf(self.rng.pick(&candidates).clone()))
+    }
+
+    // Draw a map of one of the cases, and give its discriminator key the value
+    // of the case, which the case expression may accept among others
+    fn discriminator(
+        &mut self,
+        key: &str,
+        cases: &'a [(Yaml, Yaml)],
+        node: &Yaml,
+        depth: usize,
+    ) -> Result<Yaml, AnyBoxedError> {
+        let candidates: Vec<(&(Yaml, Yaml), usize)> = cases
+            .iter()
+            .filter_map(|case| Some((case, self.height(&case.1)?)))
+            .collect();
+        let chosen = match depth {
+            0 => candidates.iter().min_by_key(|(_, h)| *h).map(|(c, _)| *c),
+            _ if candidates.is_empty() => None,
+            _ => Some(self.rng.pick(&candidates).0),
+        };
+        let Some((value, expression)) = chosen else {
+            return Err(
+                SimpleError::from_check("_discriminator", "no case accepts a value", node).into(),
+            );
+        };
+        let mut document = self.expression(expression, depth)?;
+        let YamlData::Mapping(entries) = &mut document.data else {
+            return Err(SimpleError::from_check(
+                "_discriminator",
+                &format!("the case {} does not produce a map", to_flow_yaml(value)),
+                node,
+            )
+            .into());
+        };
+        let value = leaf(value.data.clone());
+        match entries
+            .iter_mut()
+            .find(|(k, _)| k.data.as_str() == Some(key))
+        {
+            Some((_, v)) => *v = value,
+            None => entries.insert(0, (leaf(YamlData::String(key.into())), value)),
+        }
+        Ok(document)
+    }
+
+    // Each definition is a new name of its key space
+    fn defines(&mut self, space: &'a str) -> Yaml {
+        let count = self.name_counts.entry(space).or_default();
+        *count += 1;
+        leaf(YamlData::String(format!("{space}{}", *count - 1)))
+    }
+
+    // A reference names one of the names defined so far, or the first name
+    // of its key space when it comes before the definitions
+    fn references(&mut self, space: &str) -> Yaml {
+        let index = match self.name_counts.get(space) {
+            Some(&count) if count > 0 => self.rng.below(count as u64),
+            _ => 0,
+        };
+        leaf(YamlData::String(format!("{space}{index}")))
+    }
+
+    fn range(&mut self, pattern: &str, node: &Yaml) -> Result<Yaml, AnyBoxedError> {
+        let range = RangePattern::parse(pattern)
+            .ok_or_else(|| SimpleError::from_check("_range", "invalid range pattern", node))?;
+        let empty = || SimpleError::from_check("_range", "the range contains no value", node);
+
+        // Timestamps are drawn as whole seconds
+        if range.kind != RangeKind::Float {
+            let low = range.minimum.map(|bound| {
+                if bound.inclusive {
+                    bound.value.ceil()
+                } else {
+                    bound.value.floor() + 1.0
+                }
+            });
+            let high = range.maximum.map(|bound| {
+                if bound.inclusive {
+                    bound.value.floor()
+                } else {
+                    bound.value.ceil() - 1.0
+                }
+            });
+            let (low, high) = spread(low, high);
+            if low > high {
+                return Err(empty().into());
+            }
+            let value = self.rng.between(low as i64, high as i64);
+            return Ok(leaf(match range.kind {
+                RangeKind::Timestamp => YamlData::String(range.format_value(value as f64)),
+                _ => YamlData::Integer(value),
+            }));
+        }
+
+        let (low, high) = spread(
+            range.minimum.map(|bound| bound.value),
+            range.maximum.map(|bound| bound.value),
+        );
+        for _ in 0..ATTEMPTS {
+            let value = self.float(low, high);
+            // Prefer short numbers, rounded to the thousandth
+            for candidate in [(value * 1000.0).round() / 1000.0, value] {
+                if range.contains(candidate) {
+                    return Ok(leaf(YamlData::Real(format_float(candidate))));
+                }
+            }
+        }
+        Err(empty().into())
+    }
+
+    // Maps are drawn again until they satisfy the key constraints which the
+    // choice of the optional keys does not settle, such as a value excluded
+    // by _mutuallyExclusive
+    fn map(
+        &mut self,
+        form: &MapForm<'a>,
+        node: &'a Yaml,
+        depth: usize,
+    ) -> Result<Yaml, AnyBoxedError> {
+        let YamlData::Mapping(checker) = &node.data else {
+            return self.map_entries(form, &[], node, depth).map(leaf);
+        };
+        let mut constraints = Vec::new();
+        collect_key_constraints(self.rule_set, checker, &mut constraints);
+        if constraints.is_empty() {
+            return self.map_entries(form, &[], node, depth).map(leaf);
+        }
+        for _ in 0..ATTEMPTS {
+            let mapping = leaf(self.map_entries(form, &constraints, node, depth)?);
+            if apply_key_constraints(self.rule_set, checker, &mapping).is_none() {
+                return Ok(mapping);
+            }
+        }
+        Err(SimpleError::from_check(
+            "_map",
+            "could not generate a map which satisfies its key constraints",
+            node,
+        )
+        .into())
+    }
+
+    fn map_entries(
+        &mut self,
+        form: &MapForm<'a>,
+        constraints: &[(&str, &Yaml)],
+        node: &Yaml,
+        depth: usize,
+    ) -> Result<YamlData, AnyBoxedError> {
+        let keys = map_keys(self.rule_set, form)?;
+        let (mandatory, mut optional): (Vec<&MapKey>, Vec<&MapKey>) =
+            keys.iter().partition(|key| key.mandatory);
+        optional.retain(|key| self.height(key.expression).is_some());
+        let associations: Vec<&(Yaml, Yaml)> = form
+            .map_of
+            .iter()
+            .filter(|(key, value)| self.height(key).is_some() && self.height(value).is_some())
+            .collect();
+
+        let capacity = associations
+            .is_empty()
+            .then_some(mandatory.len() + optional.len());
+        let count = self.count(&form.size, mandatory.len(), capacity, node, depth)?;
+        let child_depth = depth.saturating_sub(1);
+
+        // Choose the optional keys, then fill the rest with _mapOf entries
+        let rest = count - mandatory.len();
+        let optional_count = if associations.is_empty() {
+            rest.min(optional.len())
+        } else if depth == 0 {
+            // Only the optional keys which end sooner than any _mapOf entry
+            let filler = associations
+                .iter()
+                .filter_map(|(key, value)| self.height(key).max(self.height(value)))
+                .min();
+            let cheaper = optional
+                .iter()
+                .filter(|key| self.height(key.expression) < filler);
+            rest.min(cheaper.count())
+        } else {
+            self.rng.between(0, rest.min(optional.len()) as i64) as usize
+        };
+        if depth == 0 {
+            optional.sort_by_key(|key| self.height(key.expression));
+        } else {
+            self.rng.shuffle(&mut optional);
+        }
+        let available = optional.clone();
+        optional.truncate(optional_count);
+        self.choose_constrained_keys(constraints, &mandatory, &available, &mut optional);
+        let optional_count = optional.len();
+
+        let mut mapping = Vec::new();
+        for key in &keys {
+            let chosen = key.mandatory || optional.iter().any(|o| o.name == key.name);
+            if chosen {
+                let value = self.expression(key.expression, child_depth).map_err(|e| {
+                    SimpleError::from_message(format!("key {}: {e}", key.name).into())
+                })?;
+                mapping.push((leaf(YamlData::String(key.name.into())), value));
+            }
+        }
+
+        // The key constraints may have dropped optional keys, which only
+        // _mapOf entries can replace
+        let filler_count = match associations.is_empty() {
+            true => 0,
+            false => rest.saturating_sub(optional_count),
+        };
+        for _ in 0..filler_count {
+            let entry = self.map_of_entry(&associations, &keys, &mapping, node, child_depth)?;
+            mapping.push(entry);
+        }
+
+        Ok(YamlData::Mapping(mapping))
+    }
+
+    // Add or remove optional keys so that the chosen keys satisfy the key
+    // constraints on the presence of keys
+    fn choose_constrained_keys<'k>(
+        &mut self,
+        constraints: &[(&str, &Yaml)],
+        mandatory: &[&'k MapKey<'a>],
+        available: &[&'k MapKey<'a>],
+        chosen: &mut Vec<&'k MapKey<'a>>,
+    ) {
+        let names = |list: &Yaml| -> Vec<String> {
+            match &list.data {
+                YamlData::List(items) => items
+                    .iter()
+                    .filter_map(|item| Some(key_condition(item)?.0.to_string()))
+                    .collect(),
+                _ => Vec::new(),
+            }
+        };
+        let is_mandatory = |name: &str| mandatory.iter().any(|key| key.name == name);
+        for (keyword, value) in constraints {
+            match (*keyword, &value.data) {
+                ("_exactlyOneOf" | "_atMostOneOf", YamlData::List(groups)) => {
+                    for group in groups {
+                        let group = names(group);
+                        let in_group = |name: &str| group.iter().any(|n| n == name);
+                        let mandatory_count = group.iter().filter(|n| is_mandatory(n)).count();
+                        let chosen_count = chosen.iter().filter(|k| in_group(k.name)).count();
+                        if mandatory_count + chosen_count == 0 && *keyword == "_exactlyOneOf" {
+                            let candidates: Vec<&MapKey> = available
+                                .iter()
+                                .copied()
+                                .filter(|k| in_group(k.name))
+                                .collect();
+                            if !candidates.is_empty() {
+                                chosen.push(*self.rng.pick(&candidates));
+                            }
+                        } else if mandatory_count + chosen_count > 1 {
+                            // Keep the mandatory keys, or else the first chosen key
+                            let mut kept = mandatory_count;
+                            chosen.retain(|k| {
+                                if !in_group(k.name) {
+                                    return true;
+                                }
+                                kept += 1;
+                                kept == 1
+                            });
+                        }
+                    }
+                }
+                ("_mutuallyExclusive", YamlData::Mapping(exclusions)) => {
+                    for (key, conditions) in exclusions {
+                        let Some(key) = key.data.as_str() else {
+                            continue;
+                        };
+                        if is_mandatory(key) || chosen.iter().any(|k| k.name == key) {
+                            let excluded = names(conditions);
+                            chosen.retain(|k| !excluded.iter().any(|n| n == k.name));
+                        }
+                    }
+                }
+                ("_dependentRequired", YamlData::Mapping(dependencies)) => {
+                    for (key, required) in dependencies {
+                        let Some(key) = key.data.as_str() else {
+                            continue;
+                        };
+                        if is_mandatory(key) || chosen.iter().any(|k| k.name == key) {
+                            for name in names(required) {
+                                let missing =
+                                    !is_mandatory(&name) && !chosen.iter().any(|k| k.name == name);
+                                let candidate = available.iter().find(|k| k.name == name);
+                                if let (true, Some(candidate)) = (missing, candidate) {
+                                    chosen.push(candidate);
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn map_of_entry(
+        &mut self,
+        associations: &[&'a (Yaml, Yaml)],
+        keys: &[MapKey<'a>],
+        mapping: &[(Yaml, Yaml)],
+        node: &Yaml,
+        depth: usize,
+    ) -> Result<(Yaml, Yaml), AnyBoxedError> {
+        for _ in 0..ATTEMPTS {
+            let (key_expression, value_expression) = match depth {
+                0 => *associations
+                    .iter()
+                    .min_by_key(|(k, v)| self.height(k).max(self.height(v)))
+                    .unwrap(),
+                _ => *self.rng.pick(associations),
+            };
+            let key = self.expression(key_expression, depth)?;
+            // The declared keys are checked against their own expression
+            let is_declared = key
+                .data
+                .as_str()
+                .is_some_and(|name| keys.iter().any(|key| key.name == name));
+            if is_declared || mapping.iter().any(|(k, _)| k.data == key.data) {
+                continue;
+            }
+            let value = self.expression(value_expression, depth)?;
+            return Ok((key, value));
+        }
+        Err(
+            SimpleError::from_check("_mapOf", "could not generate enough distinct keys", node)
+                .into(),
+        )
+    }
+
+    fn list(
+        &mut self,
+        form: &ListForm<'a>,
+        node: &Yaml,
+        depth: usize,
+    ) -> Result<Yaml, AnyBoxedError> {
+        let fixed = form.list.len() + form.list_facultative.len();
+        let capacity = form.list_of.is_none().then_some(fixed);
+        let count = self.count(&form.size, form.list.len(), capacity, node, depth)?;
+        let child_depth = depth.saturating_sub(1);
+
+        let mut list = Vec::new();
+        for (k, expression) in form.list.iter().chain(form.list_facultative).enumerate() {
+            if k < count {
+                let item = self.item(form, &list, expression, child_depth)?;
+                list.push(item);
+            }
+        }
+        if let Some(list_of) = form.list_of {
+            for _ in fixed..count {
+                let item = self.item(form, &list, list_of, child_depth)?;
+                list.push(item);
+            }
+        }
+        Ok(leaf(YamlData::List(list)))
+    }
+
+    // An item which the uniqueness keywords of the list accept
+    fn item(
+        &mut self,
+        form: &ListForm<'a>,
+        list: &[Yaml],
+        expression: &'a Yaml,
+        depth: usize,
+    ) -> Result<Yaml, AnyBoxedError> {
+        for _ in 0..ATTEMPTS {
+            let item = self.expression(expression, depth)?;
+            if !form.is_duplicate(list, &item) {
+                return Ok(item);
+            }
+        }
+        let keyword = if form.unique { "_unique" } else { "_uniqueBy" };
+        Err(
+            SimpleError::from_check(keyword, "could not generate distinct items", expression)
+                .into(),
+        )
+    }
+
+    // The number of entries of a container. Once the depth budget is spent,
+    // containers get the fewest entries the schema allows
+    fn count(
+        &mut self,
+        size: &Size,
+        required: usize,
+        capacity: Option<usize>,
+        node: &Yaml,
+        depth: usize,
+    ) -> Result<usize, AnyBoxedError> {
+        let (lower, upper) = size.bounds(required, capacity);
+        let upper = upper.unwrap_or(usize::MAX).min(lower + EXTRA_ENTRIES);
+        if lower > upper {
+            return Err(SimpleError::from_check(
+                "_(nb|min|max)",
+                &format!("requires at least {lower} entries but accepts at most {upper}"),
+                node,
+            )
+            .into());
+        }
+        Ok(match depth {
+            0 => lower,
+            _ => self.rng.between(lower as i64, upper as i64) as usize,
+        })
+    }
+}
+
+fn is_predefined_rule(name: &str) -> bool {
+    matches!(
+        name,
+        "string"
+            | "int"
+            | "float"
+            | "binary"
+            | "boolean"
+            | "nullType"
+            | "timestamp"
+            | "any"
+            | "anyData"
+            | "never"
+    ) || is_format_rule(name)
+}
+
+// Fill in the missing bounds of a range
+fn spread(low: Option<f64>, high: Option<f64>) -> (f64, f64) {
+    match (low, high) {
+        (Some(low), Some(high)) => (low, high),
+        (Some(low), None) => (low, low + SPREAD),
+        (None, Some(high)) => (high - SPREAD, high),
+        (None, None) => (-SPREAD, SPREAD),
+    }
+}
+
+// Floats keep a decimal point so that YAML reads them back as floats
+pub fn format_float(value: f64) -> String {
+    let text = value.to_string();
+    if text.contains('.') {
+        text
+    } else {
+        format!("{text}.0")
+    }
+}
+
+fn leaf(data: YamlData) -> Yaml {
+    Yaml {
+        data,
+        line_col: Default::default(),
+    }
+}
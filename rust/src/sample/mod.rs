@@ -0,0 +1,12 @@
+pub(crate) mod form;
+mod generate;
+mod mutate;
+mod pattern;
+mod rng;
+#[cfg(feature = "proptest")]
+mod strategy;
+
+pub use generate::SampleGenerator;
+pub use mutate::{mutate_document, Mutation};
+#[cfg(feature = "proptest")]
+pub use strategy::sample_strategy;
@@ -0,0 +1,754 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use lidy__yaml::{LineCol, Yaml, YamlData};
+
+use crate::error::AnyBoxedError;
+use crate::expression::apply_expression;
+use crate::key_constraint::{collect_key_constraints, key_condition};
+use crate::parser::Parser;
+use crate::range::{RangeKind, RangePattern};
+use crate::reference::CrossReferences;
+use crate::regex::RegexChecker;
+use crate::rule::{apply_rule, Rule};
+use crate::syaml::{extract_kv_entry, same_value, to_flow_yaml};
+use crate::yamlfile::YamlFile;
+
+use super::form::{map_keys, read_form, simple_values, Form, ListForm, MapForm, Size};
+use super::generate::format_float;
+
+/// A near-miss invalid document, derived from a valid document by a single
+/// change
+#[derive(Clone, Debug)]
+pub struct Mutation {
+    /// The keyword of the checker the change violates, as it appears in the
+    /// error message: `_map` for a missing key, `_map*` for an unknown key,
+    /// `_regex`, `_range`, `_in`, `_nb`, `_min` or `_max`
+    pub keyword: &'static str,
+    /// The position, in the original document, of the node the error should
+    /// point to
+    pub line_col: LineCol,
+    pub description: String,
+    /// The mutated document. Its nodes keep their position in the original
+    /// document, and the added nodes take the position of their container.
+    pub document: YamlFile,
+}
+
+/// Derive near-miss invalid documents from a document accepted by the `main`
+/// rule of the schema: drop a mandatory key, add an unknown key, break a
+/// `_regex`, leave a `_range` or an `_in` list, match the expression of a
+/// `_not`, and break the `_nb`, `_min` or `_max` of a container.
+///
+/// The mutations follow the checkers which accept the original document. A
+/// mutation below a `_oneOf` may still be accepted by another alternative.
+pub fn mutate_document(
+    rule_set: &HashMap<Box<str>, Rule>,
+    document: &YamlFile,
+) -> Result<Vec<Mutation>, AnyBoxedError> {
+    let mut mutator = Mutator {
+        rule_set,
+        parser: Parser {
+            content_file_name: document.file.name.clone().into(),
+            content_source: Rc::from(&*document.file.content),
+            schema_file_name: "".into(),
+            rule_set: rule_set.clone(),
+            builder_callback: Box::new(|_, lidy_result| Ok(lidy_result.data.clone())),
+            rule_trace: Vec::new(),
+            rule_is_matching_node: HashMap::new(),
+            skip_builders: false,
+            cross_references: CrossReferences::default(),
+        },
+        document,
+        mutations: Vec::new(),
+    };
+    apply_rule(&mut mutator.parser, "main", &document.yaml)?;
+    mutator.rule("main", &document.yaml, &mut Vec::new());
+    Ok(mutator.mutations)
+}
+
+struct Mutator<'a> {
+    rule_set: &'a HashMap<Box<str>, Rule>,
+    // Used to find which checker accepts a node
+    parser: Parser<'static, ()>,
+    document: &'a YamlFile,
+    mutations: Vec<Mutation>,
+}
+
+// The path of a node is the index of its entry in each of its ancestors
+fn node_at<'b>(node: &'b mut Yaml, path: &[usize]) -> &'b mut Yaml {
+    let Some((&k, rest)) = path.split_first() else {
+        return node;
+    };
+    match &mut node.data {
+        YamlData::List(list) => node_at(&mut list[k], rest),
+        YamlData::Mapping(mapping) => node_at(&mut mapping[k].1, rest),
+        _ => unreachable!("the path of a node only goes through containers"),
+    }
+}
+
+// The key names of a list of a key constraint
+fn names(list: &Yaml) -> Vec<&str> {
+    match &list.data {
+        YamlData::List(items) => items.iter().filter_map(|i| i.data.as_str()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn positioned(data: YamlData, line_col: LineCol) -> Yaml {
+    Yaml { data, line_col }
+}
+
+impl<'a> Mutator<'a> {
+    fn record(
+        &mut self,
+        keyword: &'static str,
+        line_col: LineCol,
+        description: String,
+        path: &[usize],
+        change: impl FnOnce(&mut YamlData),
+    ) {
+        let mut document = self.document.clone();
+        change(&mut node_at(&mut document.yaml, path).data);
+        self.mutations.push(Mutation {
+            keyword,
+            line_col,
+            description,
+            document,
+        });
+    }
+
+    fn accepts(&mut self, expression: &Yaml, node: &Yaml) -> bool {
+        apply_expression(&mut self.parser, expression, node).is_ok()
+    }
+
+    fn rule(&mut self, rule_name: &str, node: &Yaml, path: &mut Vec<usize>) {
+        let rule_set = self.rule_set;
+        if let Some(rule) = rule_set.get(rule_name) {
+            self.parser.rule_trace.push(rule_name.into());
+            self.expression(&rule.node, node, path);
+            self.parser.rule_trace.pop();
+        }
+    }
+
+    fn expression(&mut self, expression: &'a Yaml, node: &Yaml, path: &mut Vec<usize>) {
+        let Ok(form) = read_form(expression) else {
+            return;
+        };
+        match form {
+            Form::Rule(name) => self.rule(name, node, path),
+            Form::Regex(checker, size) => {
+                self.regex(&checker, node, path);
+                if let Ok(regex) = checker.compile() {
+                    self.length(&size, node, path, |_, text| regex.is_match(text));
+                }
+            }
+            Form::String(expression, size) => {
+                self.expression(expression, node, path);
+                self.length(&size, node, path, |mutator, text| {
+                    let candidate = positioned(YamlData::String(text.into()), node.line_col);
+                    mutator.accepts(expression, &candidate)
+                });
+            }
+            Form::In(values) => self.in_("_in", values, node, path),
+            Form::Const(value) => self.in_("_const", std::slice::from_ref(value), node, path),
+            Form::Range(pattern) => self.range(pattern, node, path),
+            Form::OneOf(alternatives) => {
+                // Follow the first alternative which accepts the node, as the
+                // parser does
+                for alternative in alternatives {
+                    if self.accepts(alternative, node) {
+                        self.expression(alternative, node, path);
+                        break;
+                    }
+                }
+            }
+            Form::AllOf(expressions) => {
+                // Breaking any of the expressions breaks the checker
+                for expression in expressions {
+                    self.expression(expression, node, path);
+                }
+            }
+            Form::Not(inner) => self.not(inner, node, path),
+            // Removing a definition only breaks the references to it, which
+            // the references themselves cover
+            Form::Defines(_) => {}
+            Form::References(space) => self.references(space, node, path),
+            Form::Discriminator(key, cases) => self.discriminator(key, cases, node, path),
+            Form::Map(form) => self.map(&form, expression, node, path),
+            Form::List(form) => self.list(&form, node, path),
+        }
+    }
+
+    fn not(&mut self, inner: &Yaml, node: &Yaml, path: &[usize]) {
+        let candidate = simple_values()
+            .into_iter()
+            .map(|data| positioned(data, node.line_col))
+            .find(|candidate| self.accepts(inner, candidate));
+        if let Some(candidate) = candidate {
+            self.record(
+                "_not",
+                node.line_col,
+                format!(
+                    "replace the value by {}, which matches the negated expression",
+                    to_flow_yaml(&candidate)
+                ),
+                path,
+                |data| *data = candidate.data,
+            );
+        }
+    }
+
+    fn references(&mut self, space: &str, node: &Yaml, path: &[usize]) {
+        let cross_references = &self.parser.cross_references;
+        let candidate = (0..)
+            .map(|k| match k {
+                0 => "undefined".to_string(),
+                _ => format!("undefined{k}"),
+            })
+            .find(|c| {
+                !cross_references.is_defined(
+                    space,
+                    &positioned(YamlData::String(c.clone()), node.line_col),
+                )
+            })
+            .unwrap();
+        self.record(
+            "_references",
+            node.line_col,
+            format!("replace the value by '{candidate}', which names no {space}"),
+            path,
+            |data| *data = YamlData::String(candidate),
+        );
+    }
+
+    // Drop the discriminator key, or give it a value which no case has, then
+    // break the case which the node matches
+    fn discriminator(
+        &mut self,
+        key: &str,
+        cases: &'a [(Yaml, Yaml)],
+        node: &Yaml,
+        path: &mut Vec<usize>,
+    ) {
+        let YamlData::Mapping(entries) = &node.data else {
+            return;
+        };
+        let Some(k) = entries
+            .iter()
+            .position(|(name, _)| name.data.as_str() == Some(key))
+        else {
+            return;
+        };
+        let value = &entries[k].1;
+        self.record(
+            "_discriminator",
+            node.line_col,
+            format!("drop the discriminator key '{key}'"),
+            path,
+            |data| remove_entries(data, &[k]),
+        );
+        let candidate = (0..)
+            .map(|n| match n {
+                0 => "Unknown".to_string(),
+                _ => format!("Unknown{n}"),
+            })
+            .find(|c| {
+                !cases
+                    .iter()
+                    .any(|(case, _)| case.data.as_str() == Some(c.as_str()))
+            })
+            .unwrap();
+        self.record(
+            "_discriminator",
+            value.line_col,
+            format!("replace the {key} by '{candidate}', which is not one of the cases"),
+            path,
+            |data| {
+                if let YamlData::Mapping(entries) = data {
+                    entries[k].1.data = YamlData::String(candidate);
+                }
+            },
+        );
+        // The mutations of the case which touch the discriminator key break
+        // the discriminator instead, as the mutations above do
+        if let Some((_, expression)) = cases.iter().find(|(case, _)| same_value(case, value)) {
+            let count = self.mutations.len();
+            self.expression(expression, node, path);
+            let case_mutations = self.mutations.split_off(count);
+            self.mutations
+                .extend(case_mutations.into_iter().filter(|mutation| {
+                    let mut document = mutation.document.yaml.clone();
+                    match &node_at(&mut document, path).data {
+                        YamlData::Mapping(entries) => {
+                            extract_kv_entry(entries, key).is_some_and(|v| v.data == value.data)
+                        }
+                        _ => false,
+                    }
+                }));
+        }
+    }
+
+    fn regex(&mut self, checker: &RegexChecker, node: &Yaml, path: &[usize]) {
+        let Ok(regex) = checker.compile() else {
+            return;
+        };
+        let candidates = ["", "-", "0", "A", " ", "~~"];
+        if let Some(candidate) = candidates.iter().find(|c| !regex.is_match(c)) {
+            self.record(
+                "_regex",
+                node.line_col,
+                format!(
+                    "replace the value by '{candidate}', which does not match {}",
+                    checker.display()
+                ),
+                path,
+                |data| *data = YamlData::String(candidate.to_string()),
+            );
+        }
+    }
+
+    // Shorten or lengthen a string past the bounds of its size keywords,
+    // keeping only the strings which the checker accepts otherwise
+    fn length(
+        &mut self,
+        size: &Size,
+        node: &Yaml,
+        path: &[usize],
+        mut is_accepted: impl FnMut(&mut Self, &str) -> bool,
+    ) {
+        let YamlData::String(text) = &node.data else {
+            return;
+        };
+        let length = text.chars().count();
+        let last = text.chars().last().unwrap_or('a');
+
+        let shrink = [("_min", size.min), ("_nb", size.nb)];
+        for (keyword, size) in shrink {
+            let Some(size) = size.filter(|&size| size > 0 && size <= length) else {
+                continue;
+            };
+            let candidate: String = text.chars().take(size - 1).collect();
+            if is_accepted(self, &candidate) {
+                self.record(
+                    keyword,
+                    node.line_col,
+                    format!("cut the value to {} characters", size - 1),
+                    path,
+                    |data| *data = YamlData::String(candidate),
+                );
+            }
+        }
+
+        let grow = [("_max", size.max), ("_nb", size.nb)];
+        for (keyword, size) in grow {
+            let Some(size) = size.filter(|&size| size >= length) else {
+                continue;
+            };
+            let mut candidate = text.clone();
+            candidate.extend(std::iter::repeat_n(last, size + 1 - length));
+            if is_accepted(self, &candidate) {
+                self.record(
+                    keyword,
+                    node.line_col,
+                    format!("repeat the last character to have {} characters", size + 1),
+                    path,
+                    |data| *data = YamlData::String(candidate),
+                );
+            }
+        }
+    }
+
+    fn in_(&mut self, keyword: &'static str, values: &[Yaml], node: &Yaml, path: &[usize]) {
+        let candidate = (0..)
+            .map(|k| match k {
+                0 => "unexpected".to_string(),
+                _ => format!("unexpected{k}"),
+            })
+            .find(|c| !values.iter().any(|v| v.data.as_str() == Some(c)))
+            .unwrap();
+        self.record(
+            keyword,
+            node.line_col,
+            format!("replace the value by '{candidate}', which is not an accepted value"),
+            path,
+            |data| *data = YamlData::String(candidate),
+        );
+    }
+
+    fn range(&mut self, pattern: &str, node: &Yaml, path: &[usize]) {
+        let Some(range) = RangePattern::parse(pattern) else {
+            return;
+        };
+        let outside = match (range.maximum, range.minimum) {
+            (Some(bound), _) if bound.inclusive => Some(bound.value + 1.0),
+            (Some(bound), _) => Some(bound.value),
+            (None, Some(bound)) if bound.inclusive => Some(bound.value - 1.0),
+            (None, Some(bound)) => Some(bound.value),
+            (None, None) => None,
+        };
+        let (data, description) = match outside {
+            Some(value) if range.kind == RangeKind::Timestamp => {
+                let timestamp = range.format_value(value);
+                (
+                    YamlData::String(timestamp.clone()),
+                    format!("replace the value by {timestamp}, which is outside of {pattern}"),
+                )
+            }
+            Some(value) if range.kind == RangeKind::Int && value.fract() == 0.0 => (
+                YamlData::Integer(value as i64),
+                format!("replace the value by {value}, which is outside of {pattern}"),
+            ),
+            Some(value) => (
+                YamlData::Real(format_float(value)),
+                format!("replace the value by {value}, which is outside of {pattern}"),
+            ),
+            // Without bounds, only integer ranges can be broken
+            None if range.kind == RangeKind::Int => (
+                YamlData::Real("0.5".into()),
+                "replace the value by 0.5, which is not an integer".into(),
+            ),
+            None => return,
+        };
+        self.record("_range", node.line_col, description, path, |d| *d = data);
+    }
+
+    fn map(&mut self, form: &MapForm<'a>, checker: &'a Yaml, node: &Yaml, path: &mut Vec<usize>) {
+        let YamlData::Mapping(entries) = &node.data else {
+            return;
+        };
+        let Ok(keys) = map_keys(self.rule_set, form) else {
+            return;
+        };
+        let declared = |key: &Yaml| {
+            let name = key.data.as_str()?;
+            keys.iter().find(|declared| declared.name == name)
+        };
+
+        for (k, (key, value)) in entries.iter().enumerate() {
+            path.push(k);
+            match declared(key) {
+                Some(declared) => self.expression(declared.expression, value, path),
+                None => {
+                    for (key_expression, value_expression) in form.map_of {
+                        if self.accepts(key_expression, key)
+                            && self.accepts(value_expression, value)
+                        {
+                            self.expression(value_expression, value, path);
+                            break;
+                        }
+                    }
+                }
+            }
+            path.pop();
+        }
+
+        let is_mandatory = |key: &Yaml| declared(key).is_some_and(|declared| declared.mandatory);
+        for (k, (key, _)) in entries.iter().enumerate() {
+            if is_mandatory(key) {
+                self.record(
+                    "_map",
+                    node.line_col,
+                    format!("drop the mandatory key '{}'", key.data.as_str().unwrap()),
+                    path,
+                    |data| remove_entries(data, &[k]),
+                );
+            }
+        }
+
+        if form.map_of.is_empty() {
+            let name = (0..)
+                .map(|k| match k {
+                    0 => "unknownKey".to_string(),
+                    _ => format!("unknownKey{k}"),
+                })
+                .find(|name| !keys.iter().any(|key| key.name == name))
+                .unwrap();
+            let line_col = node.line_col;
+            self.record(
+                "_map*",
+                line_col,
+                format!("add the unknown key '{name}'"),
+                path,
+                |data| {
+                    if let YamlData::Mapping(mapping) = data {
+                        let key = positioned(YamlData::String(name), line_col);
+                        mapping.push((key, positioned(YamlData::Null, line_col)));
+                    }
+                },
+            );
+        }
+
+        // Only the entries of optional keys can be removed without missing a
+        // mandatory key
+        let removable: Vec<usize> = (0..entries.len())
+            .filter(|&k| !is_mandatory(&entries[k].0))
+            .collect();
+        let sizes = [("_min", form.size.min), ("_nb", form.size.nb)];
+        for (keyword, size) in sizes {
+            let Some(size) = size.filter(|&size| size > 0) else {
+                continue;
+            };
+            let count = entries.len() + 1 - size;
+            if count <= removable.len() {
+                let removed = &removable[removable.len() - count..];
+                self.record(
+                    keyword,
+                    node.line_col,
+                    format!("remove entries to leave {} of them", size - 1),
+                    path,
+                    |data| remove_entries(data, removed),
+                );
+            }
+        }
+
+        if let YamlData::Mapping(checker) = &checker.data {
+            let mut constraints = Vec::new();
+            collect_key_constraints(self.rule_set, checker, &mut constraints);
+            let removable: Vec<(&str, usize)> = entries
+                .iter()
+                .enumerate()
+                .filter(|(_, (key, _))| !is_mandatory(key))
+                .filter_map(|(k, (key, _))| Some((key.data.as_str()?, k)))
+                .collect();
+            for (keyword, value) in constraints {
+                self.key_constraint(keyword, value, node, path, &removable);
+            }
+        }
+    }
+
+    // Remove a key which a present key requires, or the only present key of
+    // an _exactlyOneOf group, and give a key the value which a present key
+    // excludes
+    fn key_constraint(
+        &mut self,
+        keyword: &str,
+        constraint: &Yaml,
+        node: &Yaml,
+        path: &[usize],
+        removable: &[(&str, usize)],
+    ) {
+        let removable = |name: &str| {
+            removable
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|&(_, k)| k)
+        };
+        let YamlData::Mapping(entries) = &node.data else {
+            return;
+        };
+        let is_present = |name: &str| extract_kv_entry(entries, name).is_some();
+        match (keyword, &constraint.data) {
+            ("_dependentRequired", YamlData::Mapping(dependencies)) => {
+                for (key, required) in dependencies {
+                    let Some(key) = key.data.as_str().filter(|key| is_present(key)) else {
+                        continue;
+                    };
+                    for name in names(required) {
+                        if let Some(k) = removable(name) {
+                            self.record(
+                                "_dependentRequired",
+                                node.line_col,
+                                format!("drop the key '{name}', which key '{key}' requires"),
+                                path,
+                                |data| remove_entries(data, &[k]),
+                            );
+                        }
+                    }
+                }
+            }
+            ("_exactlyOneOf", YamlData::List(groups)) => {
+                for group in groups {
+                    let group = names(group);
+                    let present: Vec<&str> =
+                        group.iter().copied().filter(|n| is_present(n)).collect();
+                    if let [name] = present[..] {
+                        if let Some(k) = removable(name) {
+                            self.record(
+                                "_exactlyOneOf",
+                                node.line_col,
+                                format!(
+                                    "drop the key '{name}', leaving none of {}",
+                                    group.join(", ")
+                                ),
+                                path,
+                                |data| remove_entries(data, &[k]),
+                            );
+                        }
+                    }
+                }
+            }
+            ("_mutuallyExclusive", YamlData::Mapping(exclusions)) => {
+                for (key, conditions) in exclusions {
+                    let (Some(key), YamlData::List(conditions)) =
+                        (key.data.as_str(), &conditions.data)
+                    else {
+                        continue;
+                    };
+                    if !is_present(key) {
+                        continue;
+                    }
+                    for condition in conditions {
+                        let Some((name, Some(value))) = key_condition(condition) else {
+                            continue;
+                        };
+                        if extract_kv_entry(entries, name).is_some_and(|v| same_value(v, value)) {
+                            continue;
+                        }
+                        let line_col = node.line_col;
+                        self.record(
+                            "_mutuallyExclusive",
+                            line_col,
+                            format!(
+                                "set {name} to {}, which key '{key}' excludes",
+                                to_flow_yaml(value)
+                            ),
+                            path,
+                            |data| {
+                                if let YamlData::Mapping(mapping) = data {
+                                    let value = positioned(value.data.clone(), line_col);
+                                    match mapping
+                                        .iter_mut()
+                                        .find(|(k, _)| k.data.as_str() == Some(name))
+                                    {
+                                        Some((_, v)) => *v = value,
+                                        None => mapping.push((
+                                            positioned(YamlData::String(name.into()), line_col),
+                                            value,
+                                        )),
+                                    }
+                                }
+                            },
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Make the second item repeat the first one, or its key of _uniqueBy
+    fn duplicate(&mut self, form: &ListForm, items: &[Yaml], path: &[usize]) {
+        let [first, second, ..] = items else {
+            return;
+        };
+        if form.unique {
+            self.record(
+                "_unique",
+                second.line_col,
+                "replace item 1 by a copy of item 0".into(),
+                path,
+                |data| {
+                    if let YamlData::List(list) = data {
+                        list[1].data = list[0].data.clone();
+                    }
+                },
+            );
+        }
+        let Some(key) = form.unique_by else {
+            return;
+        };
+        let (YamlData::Mapping(first_entries), YamlData::Mapping(second_entries)) =
+            (&first.data, &second.data)
+        else {
+            return;
+        };
+        let (Some(value), Some(second_value)) = (
+            extract_kv_entry(first_entries, key),
+            extract_kv_entry(second_entries, key),
+        ) else {
+            return;
+        };
+        self.record(
+            "_uniqueBy",
+            second_value.line_col,
+            format!("give item 1 the {key} of item 0"),
+            path,
+            |data| {
+                if let YamlData::List(list) = data {
+                    if let YamlData::Mapping(entries) = &mut list[1].data {
+                        for (entry_key, entry_value) in entries.iter_mut() {
+                            if entry_key.data.as_str() == Some(key) {
+                                entry_value.data = value.data.clone();
+                            }
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    fn list(&mut self, form: &ListForm<'a>, node: &Yaml, path: &mut Vec<usize>) {
+        let YamlData::List(items) = &node.data else {
+            return;
+        };
+        let fixed = form.list.len() + form.list_facultative.len();
+
+        for (k, item) in items.iter().enumerate() {
+            let expression = if k < form.list.len() {
+                Some(&form.list[k])
+            } else if k < fixed {
+                Some(&form.list_facultative[k - form.list.len()])
+            } else {
+                form.list_of
+            };
+            if let Some(expression) = expression {
+                path.push(k);
+                self.expression(expression, item, path);
+                path.pop();
+            }
+        }
+
+        self.duplicate(form, items, path);
+
+        let shrink = [("_min", form.size.min), ("_nb", form.size.nb)];
+        for (keyword, size) in shrink {
+            // Keep the items of _list, whose absence is reported instead
+            let Some(size) = size.filter(|&size| size > form.list.len()) else {
+                continue;
+            };
+            self.record(
+                keyword,
+                node.line_col,
+                format!("remove items to leave {} of them", size - 1),
+                path,
+                |data| {
+                    if let YamlData::List(list) = data {
+                        list.truncate(size - 1);
+                    }
+                },
+            );
+        }
+
+        // Growing the list repeats its last item, which must be a _listOf item
+        let Some(last) = items.last().filter(|_| items.len() > fixed) else {
+            return;
+        };
+        let grow = [("_max", form.size.max), ("_nb", form.size.nb)];
+        for (keyword, size) in grow {
+            let Some(size) = size else {
+                continue;
+            };
+            self.record(
+                keyword,
+                node.line_col,
+                format!("repeat the last item to have {} items", size + 1),
+                path,
+                |data| {
+                    if let YamlData::List(list) = data {
+                        list.resize(size + 1, last.clone());
+                    }
+                },
+            );
+        }
+    }
+}
+
+fn remove_entries(data: &mut YamlData, indexes: &[usize]) {
+    if let YamlData::Mapping(mapping) = data {
+        let mut k = 0;
+        mapping.retain(|_| {
+            k += 1;
+            !indexes.contains(&(k - 1))
+        });
+    }
+}
@@ -0,0 +1,83 @@
+use regex_syntax::hir::{Class, ClassUnicode, ClassUnicodeRange, Hir, HirKind};
+
+use super::rng::Rng;
+
+// How many repetitions an unbounded (or large) repetition may add to its
+// minimum
+const EXTRA_REPETITIONS: u32 = 4;
+
+// Write a random string matching the pattern. Look-around assertions, such as
+// `^` or `\b`, are not enforced; the caller checks the string against the
+// compiled regex.
+pub fn generate_matching_string(pattern: &str, rng: &mut Rng) -> Result<String, String> {
+    let hir = regex_syntax::Parser::new()
+        .parse(pattern)
+        .map_err(|e| e.to_string())?;
+    let mut out = String::new();
+    write_hir(&hir, rng, &mut out);
+    Ok(out)
+}
+
+fn write_hir(hir: &Hir, rng: &mut Rng, out: &mut String) {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => out.push_str(&String::from_utf8_lossy(&literal.0)),
+        HirKind::Class(Class::Unicode(class)) => {
+            if let Some(c) = pick_char(class, rng) {
+                out.push(c);
+            }
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let ascii: Vec<u8> = class
+                .iter()
+                .flat_map(|range| range.start()..=range.end())
+                .filter(|byte| byte.is_ascii())
+                .collect();
+            if !ascii.is_empty() {
+                out.push(*rng.pick(&ascii) as char);
+            }
+        }
+        HirKind::Repetition(repetition) => {
+            let max = repetition
+                .max
+                .unwrap_or(u32::MAX)
+                .min(repetition.min.saturating_add(EXTRA_REPETITIONS));
+            let count = rng.between(repetition.min as i64, max as i64);
+            for _ in 0..count {
+                write_hir(&repetition.sub, rng, out);
+            }
+        }
+        HirKind::Capture(capture) => write_hir(&capture.sub, rng, out),
+        HirKind::Concat(hirs) => {
+            for hir in hirs {
+                write_hir(hir, rng, out);
+            }
+        }
+        HirKind::Alternation(hirs) => write_hir(rng.pick(hirs), rng, out),
+    }
+}
+
+// Prefer printable ASCII characters, which keep the samples readable
+fn pick_char(class: &ClassUnicode, rng: &mut Rng) -> Option<char> {
+    let mut printable = class.clone();
+    printable.intersect(&ClassUnicode::new([ClassUnicodeRange::new(' ', '~')]));
+    let ranges = if printable.ranges().is_empty() {
+        class.ranges()
+    } else {
+        printable.ranges()
+    };
+
+    let size = |range: &ClassUnicodeRange| range.end() as u64 - range.start() as u64 + 1;
+    let total: u64 = ranges.iter().map(size).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut index = rng.below(total);
+    for range in ranges {
+        if index < size(range) {
+            return char::from_u32(range.start() as u32 + index as u32);
+        }
+        index -= size(range);
+    }
+    None
+}
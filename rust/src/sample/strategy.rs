@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+use proptest::prelude::{any, BoxedStrategy, Strategy};
+
+use crate::rule::Rule;
+
+use super::SampleGenerator;
+
+/// A proptest strategy of the documents accepted by the `main` rule of a
+/// schema.
+///
+/// Each document is generated from a random seed. Shrinking moves towards
+/// smaller seeds, which does not make the documents smaller.
+pub fn sample_strategy(
+    rule_set: &HashMap<Box<str>, Rule>,
+    max_depth: usize,
+) -> BoxedStrategy<String> {
+    let rule_set = rule_set.clone();
+    any::<u64>()
+        .prop_filter_map("the schema accepts no document", move |seed| {
+            SampleGenerator::new(&rule_set, seed)
+                .with_max_depth(max_depth)
+                .generate()
+                .ok()
+        })
+        .boxed()
+}
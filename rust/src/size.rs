@@ -8,12 +8,21 @@ pub fn apply_size_check(
     max: Option<&Yaml>,
     nb: Option<&Yaml>,
 ) -> Option<AnyBoxedError> {
-    // Get the length of items if it's a sequence or mapping
-    let size = match &content.data {
-        YamlData::List(seq) => seq.len(),
-        YamlData::Mapping(map) => map.len(),
+    // Get the length of items if it's a sequence or mapping, or the number of
+    // characters (Unicode scalar values) if it's a string
+    let (size, subject, unit) = match &content.data {
+        YamlData::List(seq) => (seq.len(), "container", "entries"),
+        YamlData::Mapping(map) => (map.len(), "container", "entries"),
+        YamlData::String(s) => (s.chars().count(), "string", "characters"),
         _ => {
-            panic!("_(size), Only containers (maps or sequences) have a size.");
+            return Some(
+                SimpleError::from_check(
+                    "_(size)",
+                    "Only containers (maps or sequences) and strings have a size.",
+                    content,
+                )
+                .into(),
+            );
         }
     };
 
@@ -25,8 +34,8 @@ pub fn apply_size_check(
                     SimpleError::from_check(
                         "_min",
                         &format!(
-                            "Expected container to have at least {} entries but it has only {}.",
-                            min_size, size
+                            "Expected {} to have at least {} {} but it has only {}.",
+                            subject, min_size, unit, size
                         ),
                         content,
                     )
@@ -44,8 +53,8 @@ pub fn apply_size_check(
                     SimpleError::from_check(
                         "_max",
                         &format!(
-                            "Expected container to have at most {} entries but it has {}.",
-                            max_size, size
+                            "Expected {} to have at most {} {} but it has {}.",
+                            subject, max_size, unit, size
                         ),
                         content,
                     )
@@ -63,8 +72,8 @@ pub fn apply_size_check(
                     SimpleError::from_check(
                         "_nb",
                         &format!(
-                            "Expected container to have exactly {} entries but it has {}.",
-                            nb_size, size
+                            "Expected {} to have exactly {} {} but it has {}.",
+                            subject, nb_size, unit, size
                         ),
                         content,
                     )
@@ -0,0 +1,19 @@
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::expression::apply_expression;
+use crate::{error::AnyBoxedError, parser::Parser, LidyResult, SimpleError};
+
+// Apply the expression of a `_string` checker, once the content is known to
+// be a string. The size keywords of the checker then bound its length.
+pub fn apply_string_matcher<TV>(
+    parser: &mut Parser<TV>,
+    expression: &Yaml,
+    content: &Yaml,
+) -> Result<LidyResult<TV>, AnyBoxedError>
+where
+{
+    if !matches!(content.data, YamlData::String(_)) {
+        return Err(SimpleError::from_check("_string", "must be a string", content).into());
+    }
+    apply_expression(parser, expression, content)
+}
@@ -0,0 +1,80 @@
+use lidy__yaml::{Yaml, YamlData};
+
+use super::to_flow_yaml;
+
+// Write a node as a YAML document in block style. Scalars, empty containers
+// and complex keys are written in flow style.
+pub fn to_block_yaml(node: &Yaml) -> String {
+    let mut out = String::new();
+    match &node.data {
+        YamlData::Mapping(mapping) if !mapping.is_empty() => entries(&mut out, mapping, 0, false),
+        YamlData::List(list) if !list.is_empty() => items(&mut out, list, 0, false),
+        _ => {
+            out.push_str(&to_flow_yaml(node));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+// Write a node after a `key:`, at the given indentation
+fn value(out: &mut String, node: &Yaml, indent: usize) {
+    match &node.data {
+        YamlData::Mapping(mapping) if !mapping.is_empty() => {
+            out.push('\n');
+            entries(out, mapping, indent + 2, false);
+        }
+        YamlData::List(list) if !list.is_empty() => {
+            out.push('\n');
+            items(out, list, indent + 2, false);
+        }
+        _ => {
+            out.push(' ');
+            out.push_str(&to_flow_yaml(node));
+            out.push('\n');
+        }
+    }
+}
+
+fn entries(out: &mut String, mapping: &[(Yaml, Yaml)], indent: usize, inline_first: bool) {
+    for (k, (key, node)) in mapping.iter().enumerate() {
+        if !(inline_first && k == 0) {
+            out.push_str(&" ".repeat(indent));
+        }
+        if let YamlData::Mapping(_) | YamlData::List(_) = key.data {
+            out.push_str("? ");
+            out.push_str(&to_flow_yaml(key));
+            out.push('\n');
+            out.push_str(&" ".repeat(indent));
+        } else {
+            out.push_str(&to_flow_yaml(key));
+        }
+        out.push(':');
+        value(out, node, indent);
+    }
+}
+
+fn items(out: &mut String, list: &[Yaml], indent: usize, inline_first: bool) {
+    for (k, item) in list.iter().enumerate() {
+        if !(inline_first && k == 0) {
+            out.push_str(&" ".repeat(indent));
+        }
+        out.push('-');
+        match &item.data {
+            // Nested containers start on the line of their dash
+            YamlData::Mapping(mapping) if !mapping.is_empty() => {
+                out.push(' ');
+                entries(out, mapping, indent + 2, true);
+            }
+            YamlData::List(list) if !list.is_empty() => {
+                out.push(' ');
+                items(out, list, indent + 2, true);
+            }
+            _ => {
+                out.push(' ');
+                out.push_str(&to_flow_yaml(item));
+                out.push('\n');
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use lidy__yaml::{Yaml, YamlData};
+
+use super::{must_parse_float, to_flow_yaml};
+
+// Compare two nodes, ignoring their positions
+pub fn same_data(a: &Yaml, b: &Yaml) -> bool {
+    match (&a.data, &b.data) {
+        (YamlData::List(a), YamlData::List(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_data(a, b))
+        }
+        (YamlData::Mapping(a), YamlData::Mapping(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|((ak, av), (bk, bv))| same_data(ak, bk) && same_data(av, bv))
+        }
+        (a, b) => a == b,
+    }
+}
+
+// Compare two nodes by value: numbers are equal when they have the same
+// value, whatever their notation, and maps are compared regardless of the
+// order of their keys
+pub fn same_value(a: &Yaml, b: &Yaml) -> bool {
+    first_difference(a, b).is_none()
+}
+
+// Where a node differs from an expected node: the path of the differing
+// node, which is empty for the root, the node itself, and a description of
+// the difference
+pub struct Difference<'a> {
+    pub path: String,
+    pub node: &'a Yaml,
+    pub description: String,
+}
+
+pub fn first_difference<'a>(expected: &Yaml, actual: &'a Yaml) -> Option<Difference<'a>> {
+    let differ = |description: String| {
+        Some(Difference {
+            path: String::new(),
+            node: actual,
+            description,
+        })
+    };
+    let within = |path: String, difference: Difference<'a>| Difference {
+        path: path + &difference.path,
+        ..difference
+    };
+
+    match (&expected.data, &actual.data) {
+        (YamlData::List(expected_items), YamlData::List(items)) => {
+            if expected_items.len() != items.len() {
+                return differ(format!(
+                    "expected {} items but found {}",
+                    expected_items.len(),
+                    items.len()
+                ));
+            }
+            expected_items
+                .iter()
+                .zip(items)
+                .enumerate()
+                .find_map(|(k, (e, a))| Some(within(format!("[{k}]"), first_difference(e, a)?)))
+        }
+        (YamlData::Mapping(expected_entries), YamlData::Mapping(entries)) => {
+            let key_path = |key: &Yaml| format!(".{}", to_flow_yaml(key));
+            for (expected_key, expected_value) in expected_entries {
+                match entries
+                    .iter()
+                    .find(|(key, _)| same_value(expected_key, key))
+                {
+                    Some((_, value)) => {
+                        if let Some(difference) = first_difference(expected_value, value) {
+                            return Some(within(key_path(expected_key), difference));
+                        }
+                    }
+                    None => {
+                        return Some(Difference {
+                            path: key_path(expected_key),
+                            node: actual,
+                            description: "the key is missing".into(),
+                        })
+                    }
+                }
+            }
+            entries
+                .iter()
+                .find(|(key, _)| !expected_entries.iter().any(|(e, _)| same_value(e, key)))
+                .map(|(key, _)| Difference {
+                    path: key_path(key),
+                    node: key,
+                    description: "the key is unexpected".into(),
+                })
+        }
+        (e, a) if same_scalar(e, a) => None,
+        _ => differ(format!(
+            "expected {} but found {}",
+            to_flow_yaml(expected),
+            to_flow_yaml(actual)
+        )),
+    }
+}
+
+fn same_scalar(a: &YamlData, b: &YamlData) -> bool {
+    match (number(a), number(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[derive(PartialEq)]
+enum Number {
+    // The canonical decimal text of an integral value
+    Integral(String),
+    Fractional(f64),
+}
+
+fn number(data: &YamlData) -> Option<Number> {
+    match data {
+        YamlData::Integer(value) => Some(Number::Integral(value.to_string())),
+        YamlData::BigInteger(text) => Some(Number::Integral(text.clone())),
+        YamlData::Real(text) => {
+            let value = must_parse_float(text);
+            if value.is_nan() {
+                // NaN is only equal to itself as a text
+                None
+            } else if value == 0.0 {
+                Some(Number::Integral("0".into()))
+            } else if value.is_finite() && value.fract() == 0.0 {
+                // The decimal expansion of a float is exact
+                Some(Number::Integral(format!("{value:.0}")))
+            } else {
+                Some(Number::Fractional(value))
+            }
+        }
+        _ => None,
+    }
+}
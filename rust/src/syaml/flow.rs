@@ -0,0 +1,57 @@
+use lidy__yaml::{encode_base64, Yaml, YamlData};
+
+// Write a node on a single line, in YAML flow style
+pub fn to_flow_yaml(node: &Yaml) -> String {
+    match &node.data {
+        YamlData::String(s) => quote_string(s),
+        YamlData::Integer(i) => i.to_string(),
+        YamlData::BigInteger(text) => text.clone(),
+        YamlData::Real(r) => r.clone(),
+        YamlData::Boolean(b) => b.to_string(),
+        YamlData::Timestamp(t) => t.clone(),
+        YamlData::Binary(bytes) => format!("!!binary {}", encode_base64(bytes)),
+        YamlData::List(list) => {
+            let items: Vec<String> = list.iter().map(to_flow_yaml).collect();
+            format!("[{}]", items.join(", "))
+        }
+        YamlData::Mapping(mapping) => {
+            if mapping.is_empty() {
+                return "{}".into();
+            }
+            let entries: Vec<String> = mapping
+                .iter()
+                .map(|(key, value)| format!("{}: {}", to_flow_yaml(key), to_flow_yaml(value)))
+                .collect();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        _ => "null".into(),
+    }
+}
+
+// Strings stay plain when YAML cannot read them as anything else
+fn quote_string(s: &str) -> String {
+    let reserved = ["true", "false", "null", "yes", "no", "on", "off", "y", "n"];
+    let plain = s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.-/".contains(c))
+        && !reserved.contains(&s.to_ascii_lowercase().as_str());
+    if plain {
+        s.into()
+    } else if s.chars().any(|c| c.is_control()) {
+        let mut quoted = String::from('"');
+        for c in s.chars() {
+            match c {
+                '"' => quoted.push_str("\\\""),
+                '\\' => quoted.push_str("\\\\"),
+                '\n' => quoted.push_str("\\n"),
+                '\t' => quoted.push_str("\\t"),
+                c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted
+    } else {
+        format!("'{}'", s.replace('\'', "''"))
+    }
+}
@@ -1,7 +1,15 @@
+mod block;
+mod equal;
 mod float;
+mod flow;
 mod map;
 mod scalar;
+mod timestamp;
 
+pub use block::to_block_yaml;
+pub use equal::{first_difference, same_data, same_value};
 pub use float::must_parse_float;
+pub use flow::to_flow_yaml;
 pub use map::extract_kv_entry;
 pub use scalar::is_scalar;
+pub use timestamp::{parse_timestamp, timestamp_seconds};
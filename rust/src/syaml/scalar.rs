@@ -4,8 +4,11 @@ pub fn is_scalar(node: &Yaml) -> bool {
     match &node.data {
         YamlData::Real(_) => true,
         YamlData::Integer(_) => true,
+        YamlData::BigInteger(_) => true,
         YamlData::String(_) => true,
         YamlData::Boolean(_) => true,
+        YamlData::Timestamp(_) => true,
+        YamlData::Binary(_) => true,
         YamlData::Null => true,
         _ => false,
     }
@@ -0,0 +1,89 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+
+// A run of one to `max` ASCII digits at the start of the text
+fn digits(text: &str, max: usize) -> Option<(u32, &str)> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    if end == 0 || end > max {
+        return None;
+    }
+    Some((text[..end].parse().ok()?, &text[end..]))
+}
+
+// Exactly `width` digits
+fn fixed(text: &str, width: usize) -> Option<(u32, &str)> {
+    let (value, rest) = digits(text, width)?;
+    (text.len() - rest.len() == width).then_some((value, rest))
+}
+
+/// Parse a value of the YAML timestamp type, as defined by
+/// https://yaml.org/type/timestamp.html: a date, `2002-12-14`, or a date and
+/// a time separated by a `T` or by spaces, with optional fractional seconds
+/// and an optional time zone, `2001-12-14 21:59:43.10 -5`. A timestamp
+/// without time zone is in UTC.
+pub fn parse_timestamp(text: &str) -> Option<DateTime<FixedOffset>> {
+    let (year, rest) = fixed(text, 4)?;
+    let (month, rest) = digits(rest.strip_prefix('-')?, 2)?;
+    let (day, rest) = digits(rest.strip_prefix('-')?, 2)?;
+    let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+    let utc = FixedOffset::east_opt(0).unwrap();
+
+    if rest.is_empty() {
+        // The date-only form has two-digit months and days
+        if text.len() != 10 {
+            return None;
+        }
+        return utc.from_local_datetime(&date.into()).single();
+    }
+
+    let rest = match rest.strip_prefix(['T', 't']) {
+        Some(rest) => rest,
+        None if rest.starts_with([' ', '\t']) => rest.trim_start_matches([' ', '\t']),
+        None => return None,
+    };
+    let (hour, rest) = digits(rest, 2)?;
+    let (minute, rest) = fixed(rest.strip_prefix(':')?, 2)?;
+    let (second, mut rest) = fixed(rest.strip_prefix(':')?, 2)?;
+    let mut nanosecond = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let end = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        // Digits beyond the nanosecond are dropped
+        let nanos = format!("{:0<9.9}", &fraction[..end]);
+        nanosecond = nanos.parse().unwrap();
+        rest = &fraction[end..];
+    }
+    let time = NaiveTime::from_hms_nano_opt(hour, minute, second, nanosecond)?;
+
+    let rest = rest.trim_start_matches([' ', '\t']);
+    let offset = if rest.is_empty() || rest == "Z" {
+        utc
+    } else {
+        let sign = match rest.chars().next()? {
+            '+' => 1,
+            '-' => -1,
+            _ => return None,
+        };
+        let (hours, rest) = digits(&rest[1..], 2)?;
+        let minutes = match rest.strip_prefix(':') {
+            Some(rest) => {
+                let (minutes, rest) = fixed(rest, 2)?;
+                if !rest.is_empty() {
+                    return None;
+                }
+                minutes
+            }
+            None if rest.is_empty() => 0,
+            None => return None,
+        };
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60) as i32)?
+    };
+    offset.from_local_datetime(&date.and_time(time)).single()
+}
+
+/// The number of seconds since the Unix epoch, as compared by `_range`
+pub fn timestamp_seconds(timestamp: &DateTime<FixedOffset>) -> f64 {
+    timestamp.timestamp() as f64 + timestamp.timestamp_subsec_nanos() as f64 / 1e9
+}
@@ -0,0 +1,67 @@
+use lidy__yaml::{Yaml, YamlData};
+
+use crate::error::{AnyBoxedError, JoinError};
+use crate::syaml::{extract_kv_entry, same_value, to_flow_yaml};
+use crate::SimpleError;
+
+// Check that no two items of a list are equal, or that no two items have equal
+// values for the key of _uniqueBy. Each duplicate is reported with the
+// position of its first occurrence
+pub fn apply_unique_check(
+    content: &Yaml,
+    unique: Option<&Yaml>,
+    unique_by: Option<&Yaml>,
+) -> Option<AnyBoxedError> {
+    let YamlData::List(items) = &content.data else {
+        return None;
+    };
+
+    let mut join_error = JoinError::default();
+    if let Some(YamlData::Boolean(true)) = unique.map(|node| &node.data) {
+        report_duplicates(&mut join_error, "_unique", "", items.iter().map(Some));
+    }
+    if let Some(key) = unique_by.and_then(|node| node.data.as_str()) {
+        let values = items.iter().map(|item| match &item.data {
+            YamlData::Mapping(mapping) => extract_kv_entry(mapping, key),
+            _ => None,
+        });
+        report_duplicates(&mut join_error, "_uniqueBy", key, values);
+    }
+    join_error.into_result().err()
+}
+
+// Items without a value are not compared
+fn report_duplicates<'a>(
+    join_error: &mut JoinError,
+    keyword: &str,
+    key: &str,
+    values: impl Iterator<Item = Option<&'a Yaml>>,
+) {
+    let values: Vec<Option<&Yaml>> = values.collect();
+    for (k, value) in values.iter().enumerate() {
+        let Some(value) = value else {
+            continue;
+        };
+        let first = values[..k]
+            .iter()
+            .position(|other| other.is_some_and(|other| same_value(other, value)));
+        if let Some(j) = first {
+            let first_value = values[j].unwrap();
+            let subject = match key {
+                "" => format!("item {k} ({})", to_flow_yaml(value)),
+                key => format!("the {key} of item {k} ({})", to_flow_yaml(value)),
+            };
+            join_error.add(
+                SimpleError::from_check(
+                    keyword,
+                    &format!(
+                        "{subject} duplicates item {j}, first found at {}",
+                        first_value.line_col
+                    ),
+                    value,
+                )
+                .into(),
+            );
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use std::rc::Rc;
 
-use lidy__yaml::{Yaml, YamlLoader};
+use lidy__yaml::{JsonLoader, TomlLoader, Yaml, YamlLoader};
 
 use crate::error::{AnyBoxedError, SimpleError};
-use crate::file::File;
+use crate::file::{File, FileFormat};
 
 #[derive(Clone, Debug)]
 pub struct YamlFile {
@@ -20,6 +20,19 @@ impl YamlFile {
     }
 
     pub fn deserialize(&mut self) -> Result<(), AnyBoxedError> {
+        match self.file.effective_format() {
+            FileFormat::Json => {
+                self.yaml = JsonLoader::load_from_str(&self.file.content)
+                    .map_err(|e| SimpleError::from_str(&e.to_string()))?;
+                return Ok(());
+            }
+            FileFormat::Toml => {
+                self.yaml = TomlLoader::load_from_str(&self.file.content)
+                    .map_err(|e| SimpleError::from_str(&e.to_string()))?;
+                return Ok(());
+            }
+            FileFormat::Auto | FileFormat::Yaml => {}
+        }
         let docs = YamlLoader::load_from_str(&self.file.content)
             .map_err(|e| SimpleError::from_str(&e.to_string()))?;
         if docs.is_empty() {
@@ -28,4 +41,19 @@ impl YamlFile {
         self.yaml = docs[0].clone();
         Ok(())
     }
+
+    // Every document of the file; JSON and TOML files hold a single document
+    pub fn deserialize_stream(&self) -> Result<Vec<Yaml>, AnyBoxedError> {
+        if let FileFormat::Auto | FileFormat::Yaml = self.file.effective_format() {
+            let docs = YamlLoader::load_from_str(&self.file.content)
+                .map_err(|e| SimpleError::from_str(&e.to_string()))?;
+            if docs.is_empty() {
+                return Err("No YAML document found".into());
+            }
+            return Ok(docs);
+        }
+        let mut yaml_file = self.clone();
+        yaml_file.deserialize()?;
+        Ok(vec![yaml_file.yaml])
+    }
 }
@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+
+use lidy::{Data, LidyResult};
+
+mod common;
+use common::{file, make_recording_parser};
+
+fn parse(schema: &str, content: &str, built: &RefCell<Vec<String>>) -> LidyResult<()> {
+    make_recording_parser(schema, built)
+        .parse(&file("<content>.yaml", content))
+        .unwrap()
+}
+
+#[test]
+fn test_map_results_are_merged() {
+    let schema = "
+main:
+  _allOf: [labeled, versioned]
+labeled:
+  _map:
+    labels: { _mapOf: { string: string } }
+  _mapOf: { string: any }
+versioned:
+  _map:
+    version: int
+  _mapOf: { string: any }
+";
+    let built = RefCell::new(Vec::new());
+    let result = parse(
+        schema,
+        "labels: { app: web }\nversion: 2\nowner: me\n",
+        &built,
+    );
+    let Data::MapData(map_data) = &result.data else {
+        panic!("expected map data, got {:?}", result.data);
+    };
+    let mut keys: Vec<&str> = map_data.map.keys().map(|k| &**k).collect();
+    keys.sort();
+    assert_eq!(keys, ["labels", "version"]);
+    let associations: Vec<String> = map_data
+        .map_of
+        .iter()
+        .map(|entry| format!("{:?}", entry.key.data))
+        .collect();
+    assert_eq!(associations, ["String(\"owner\")"]);
+    assert_eq!(*built.borrow(), ["labeled", "versioned", "main"]);
+}
+
+#[test]
+fn test_other_results_are_the_first_one() {
+    let schema = r#"
+main:
+  _allOf:
+    - { _regex: "^(?P<prefix>[A-Z]+)-[0-9]+$" }
+    - { _regex: "^.{5}$" }
+"#;
+    let built = RefCell::new(Vec::new());
+    let result = parse(schema, "AB-12\n", &built);
+    let Data::MapData(map_data) = &result.data else {
+        panic!(
+            "expected the captures of the first regex, got {:?}",
+            result.data
+        );
+    };
+    assert!(matches!(&map_data.map["prefix"].data, Data::String(s) if &**s == "AB"));
+}
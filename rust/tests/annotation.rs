@@ -0,0 +1,80 @@
+use lidy::Annotations;
+
+mod common;
+use common::make_parser;
+
+const SCHEMA: &str = "
+main: service
+
+service:
+  _title: Service
+  _description: A network service
+  _map:
+    name:
+      _regex: '^[a-z]+$'
+      _description: The name of the service
+      _examples: [web, db]
+  _mapFacultative:
+    port: port
+    host: string
+  _merge: [legacy]
+
+legacy:
+  _mapFacultative:
+    address:
+      _regex: '.'
+      _deprecated: true
+
+port:
+  _range: 1 <= int < 65536
+  _title: Port
+";
+
+#[test]
+fn test_rule_annotations() {
+    let parser = make_parser(SCHEMA);
+    let annotations = parser.rule_annotations("service").unwrap();
+    assert_eq!(annotations.title.as_deref(), Some("Service"));
+    assert_eq!(
+        annotations.description.as_deref(),
+        Some("A network service")
+    );
+    assert!(annotations.examples.is_empty());
+    assert!(!annotations.deprecated);
+
+    // main is an alias of service
+    assert_eq!(parser.rule_annotations("main"), Some(annotations));
+    assert!(parser.rule_annotations("legacy").unwrap().is_empty());
+    assert_eq!(parser.rule_annotations("string"), None);
+}
+
+#[test]
+fn test_key_annotations() {
+    let parser = make_parser(SCHEMA);
+    let name = parser.key_annotations("main", "name").unwrap();
+    assert_eq!(name.description.as_deref(), Some("The name of the service"));
+    let examples: Vec<&str> = name
+        .examples
+        .iter()
+        .filter_map(|e| e.data.as_str())
+        .collect();
+    assert_eq!(examples, ["web", "db"]);
+
+    // The annotations of the referenced rule
+    let port = parser.key_annotations("service", "port").unwrap();
+    assert_eq!(port.title.as_deref(), Some("Port"));
+
+    // Keys of merged checkers
+    assert!(
+        parser
+            .key_annotations("service", "address")
+            .unwrap()
+            .deprecated
+    );
+
+    assert_eq!(
+        parser.key_annotations("service", "host"),
+        Some(Annotations::default())
+    );
+    assert_eq!(parser.key_annotations("service", "unknown"), None);
+}
@@ -0,0 +1,44 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lidy::{File, FileFormat, Parser, YamlFile};
+
+/// A YAML file with the given name and content
+pub fn file(name: &str, content: &str) -> Rc<File> {
+    Rc::from(File {
+        name: name.into(),
+        content: content.into(),
+        format: FileFormat::Yaml,
+    })
+}
+
+/// A parser for the schema whose builders return the data unchanged
+pub fn make_parser(schema: &str) -> Parser<'static, ()> {
+    Parser::<()>::make(
+        &file("<schema>.yaml", schema),
+        Box::new(|_, lidy_result| Ok(lidy_result.data.clone())),
+    )
+    .unwrap()
+}
+
+/// A parser for the schema whose builders record the name of their rule
+pub fn make_recording_parser<'a>(schema: &str, built: &'a RefCell<Vec<String>>) -> Parser<'a, ()> {
+    Parser::<()>::make(
+        &file("<schema>.yaml", schema),
+        Box::new(|rule_name, lidy_result| {
+            built.borrow_mut().push(rule_name.into());
+            Ok(lidy_result.data.clone())
+        }),
+    )
+    .unwrap()
+}
+
+/// A deserialized YAML file with the given name and content
+pub fn yaml_file(name: &str, content: &str) -> YamlFile {
+    let mut yaml_file = YamlFile::new(file(name, content));
+    yaml_file.deserialize().unwrap();
+    yaml_file
+}
@@ -0,0 +1,271 @@
+use lidy::{diff_rule_sets, SchemaChange};
+
+mod common;
+use common::make_parser;
+
+fn diff(old: &str, new: &str) -> (Vec<String>, Vec<String>) {
+    let (old, new) = (make_parser(old), make_parser(new));
+    let diff = diff_rule_sets(&old.rule_set, &new.rule_set);
+    let lines = |changes: &[SchemaChange]| changes.iter().map(|c| c.to_string()).collect();
+    (lines(&diff.breaking), lines(&diff.compatible))
+}
+
+const OLD_SCHEMA: &str = "
+main:
+  _map:
+    name: string
+    protocol: { _in: [tcp, udp] }
+  _mapFacultative:
+    port: { _range: 1 <= int < 65536 }
+    tags: tags
+    target: target
+    timeout: int
+    legacy: boolean
+
+tags:
+  _listOf: string
+  _max: 10
+
+target:
+  _oneOf:
+    - string
+    - int
+    - nullType
+";
+
+#[test]
+fn test_identical_schemas() {
+    let (breaking, compatible) = diff(OLD_SCHEMA, OLD_SCHEMA);
+    assert_eq!(breaking, Vec::<String>::new());
+    assert_eq!(compatible, Vec::<String>::new());
+}
+
+#[test]
+fn test_breaking_changes() {
+    let new_schema = "
+main:
+  _map:
+    name: string
+    protocol: { _in: [tcp] }
+    region: string
+  _mapFacultative:
+    port: { _range: 1024 <= int < 65536 }
+    tags: tags
+    target: target
+    timeout: int
+
+tags:
+  _listOf: string
+  _max: 5
+
+target:
+  _oneOf:
+    - string
+    - int
+";
+    let (breaking, compatible) = diff(OLD_SCHEMA, new_schema);
+    assert_eq!(
+        breaking,
+        [
+            "main: key 'legacy' was removed (old 11:12, new 3:6)",
+            "main: value udp is no longer accepted (old 5:27, new 5:14)",
+            "main: new mandatory key 'region' (old 3:6, new 6:12)",
+            "main: the range `1 <= int < 65536` became `1024 <= int < 65536` (old 7:10, new 8:10)",
+            "tags: the maximum size fell from 10 to 5 (old 14:9, new 14:9)",
+            "target: alternative 2 was removed (old 21:6, new 18:8)",
+        ]
+    );
+    assert_eq!(compatible, Vec::<String>::new());
+}
+
+#[test]
+fn test_compatible_changes() {
+    let new_schema = "
+main:
+  _map:
+    name: string
+  _mapFacultative:
+    protocol: { _in: [tcp, udp, sctp] }
+    port: { _range: 1 <= int <= 65536 }
+    tags: labels
+    target: target
+    timeout: float
+    legacy: boolean
+    comment: string
+
+labels:
+  _listOf: string
+
+target:
+  _oneOf:
+    - string
+    - int
+    - nullType
+    - boolean
+";
+    let (breaking, compatible) = diff(OLD_SCHEMA, new_schema);
+    assert_eq!(breaking, ["tags: rule 'tags' was removed (old 14:9)"]);
+    assert_eq!(
+        compatible,
+        [
+            "labels: rule 'labels' was added (new 15:9)",
+            "main: key 'protocol' became facultative (old 5:14, new 6:14)",
+            "main: value sctp is now accepted (old 5:14, new 6:32)",
+            "main: the range `1 <= int < 65536` became `1 <= int <= 65536` (old 7:10, new 7:10)",
+            "main: 'int' became 'float' (old 10:13, new 10:13)",
+            "main: new facultative key 'comment' (old 3:6, new 12:13)",
+            "main: the size is no longer limited to 10 (old 14:9, new 15:9)",
+            "target: alternative 3 was added (old 18:8, new 22:6)",
+        ]
+    );
+}
+
+#[test]
+fn test_lists_and_recursive_rules() {
+    let old_schema = "
+main: node
+
+node:
+  _map:
+    value: { _list: [string, int] }
+  _mapFacultative:
+    children: { _listOf: node }
+";
+    let new_schema = "
+main: tree
+
+tree:
+  _map:
+    value: { _list: [string, int, boolean] }
+  _mapFacultative:
+    children: { _listOf: tree, _min: 1 }
+";
+    let (breaking, compatible) = diff(old_schema, new_schema);
+    assert_eq!(
+        breaking,
+        [
+            "main: new mandatory item 2 (old 6:11, new 6:34)",
+            "main: the minimum size rose from 0 to 1 (old 8:14, new 8:14)",
+            "node: rule 'node' was removed (old 5:6)",
+        ]
+    );
+    assert_eq!(compatible, ["tree: rule 'tree' was added (new 5:6)"]);
+}
+
+#[test]
+fn test_all_of_changes() {
+    let old_schema = "
+main:
+  _allOf:
+    - { _regex: '^[a-z]+$' }
+    - { _not: { _in: [root] } }
+";
+    let new_schema = "
+main:
+  _allOf:
+    - { _regex: '^[a-z]+$' }
+    - { _regex: '^.{1,8}$' }
+";
+    let (breaking, compatible) = diff(old_schema, new_schema);
+    assert_eq!(
+        breaking,
+        ["main: expression 1 was added to the _allOf (old 3:8, new 5:6)"]
+    );
+    assert_eq!(
+        compatible,
+        ["main: expression 1 was removed from the _allOf (old 5:6, new 3:8)"]
+    );
+}
+
+#[test]
+fn test_uniqueness_changes() {
+    let old_schema = "
+main:
+  _map:
+    steps: { _listOf: string }
+    ports: { _listOf: any, _uniqueBy: name }
+";
+    let new_schema = "
+main:
+  _map:
+    steps: { _listOf: string, _unique: true }
+    ports: { _listOf: any }
+";
+    let (breaking, compatible) = diff(old_schema, new_schema);
+    assert_eq!(breaking.len(), 1, "{breaking:?}");
+    assert!(breaking[0].contains("the items must now be unique"));
+    assert_eq!(compatible.len(), 1, "{compatible:?}");
+    assert!(
+        compatible[0].contains("the items became not unique by a key instead of unique by name")
+    );
+}
+
+#[test]
+fn test_key_constraint_changes() {
+    let old_schema = "
+main:
+  _mapFacultative:
+    tls: boolean
+    cert: string
+    image: string
+    build: string
+  _atMostOneOf: [[image, build]]
+";
+    let new_schema = "
+main:
+  _mapFacultative:
+    tls: boolean
+    cert: string
+    image: string
+    build: string
+  _dependentRequired: { tls: [cert] }
+";
+    let (breaking, compatible) = diff(old_schema, new_schema);
+    assert_eq!(
+        breaking,
+        ["main: new key constraint: key 'tls' requires key 'cert' (old 3:17, new 3:17)"]
+    );
+    assert_eq!(
+        compatible,
+        ["main: key constraint removed: at most one of the keys image, build (old 3:17, new 3:17)"]
+    );
+}
+
+#[test]
+fn test_discriminator_changes() {
+    let old_schema = "
+main:
+  _discriminator: kind
+  _cases:
+    Service: { _map: { kind: string, port: int } }
+    Pod: { _map: { kind: string } }
+";
+    let new_schema = "
+main:
+  _discriminator: kind
+  _cases:
+    Service: { _map: { kind: string, port: int, name: string } }
+    Job: { _map: { kind: string } }
+";
+    let (breaking, compatible) = diff(old_schema, new_schema);
+    assert_eq!(breaking.len(), 2, "{breaking:?}");
+    assert!(
+        breaking
+            .iter()
+            .any(|c| c.contains("new mandatory key 'name'")),
+        "{breaking:?}"
+    );
+    assert!(
+        breaking.iter().any(|c| c.contains("case Pod was removed")),
+        "{breaking:?}"
+    );
+    assert_eq!(compatible.len(), 1, "{compatible:?}");
+    assert!(compatible[0].contains("new case Job"));
+
+    let (breaking, _) = diff(
+        old_schema,
+        &old_schema.replace("_discriminator: kind", "_discriminator: type"),
+    );
+    assert_eq!(breaking.len(), 1, "{breaking:?}");
+    assert!(breaking[0].contains("the discriminator key 'kind' became 'type'"));
+}
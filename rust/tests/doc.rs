@@ -0,0 +1,85 @@
+use lidy::{generate_documentation, DocFormat};
+
+mod common;
+use common::make_parser;
+
+const SCHEMA: &str = "
+main: service
+
+service:
+  _title: Service
+  _description: A network service
+  _examples:
+    - { name: web }
+  _map:
+    name: string
+  _mapFacultative:
+    port: port
+    tags: tags
+    upstream: alias
+    protocol:
+      _in: [tcp, udp]
+  _mapDefault:
+    protocol: tcp
+  _merge: [legacy]
+
+legacy:
+  _mapFacultative:
+    address:
+      _regex: '.'
+      _deprecated: true
+
+port:
+  _range: 1 <= int < 65536
+
+tags:
+  _listOf: string
+  _min: 1
+
+alias: service
+";
+
+#[test]
+fn test_markdown_documentation() {
+    let parser = make_parser(SCHEMA);
+    let doc = generate_documentation(&parser.rule_set, "Schema", DocFormat::Markdown);
+
+    assert!(doc.starts_with("# Schema\n"));
+    // Rules appear in schema order, each with an anchor
+    let service = doc.find("<a id=\"service\"></a>\n\n## service\n").unwrap();
+    let legacy = doc.find("## legacy\n").unwrap();
+    let port = doc.find("## port\n").unwrap();
+    assert!(service < legacy && legacy < port);
+
+    for expected in [
+        "## main\n\nSame as [service](#service).\n",
+        "## alias\n\nSame as [service](#service).\n",
+        "**Service**\n\nA network service\n",
+        "Required keys:\n\n- `name`: a string\n",
+        "- `port`: [port](#port)\n",
+        "- `protocol`: one of `tcp`, `udp` (default `tcp`)\n",
+        "- `address`: a string matching the regular expression `.` **(deprecated)** (from [legacy](#legacy))\n",
+        "Example:\n\n```yaml\n{ name: web }\n```\n",
+        "An integer greater than or equal to 1 and less than 65536.\n",
+        "Each item:\n\n- a string\n\nAt least 1 item.\n",
+    ] {
+        assert!(doc.contains(expected), "missing {expected:?} in:\n{doc}");
+    }
+}
+
+#[test]
+fn test_html_documentation() {
+    let parser = make_parser(SCHEMA);
+    let doc = generate_documentation(&parser.rule_set, "<Schema>", DocFormat::Html);
+
+    assert!(doc.starts_with("<!DOCTYPE html>\n"));
+    assert!(doc.ends_with("</html>\n"));
+    for expected in [
+        "<title>&lt;Schema&gt;</title>",
+        "<h2 id=\"service\">service</h2>",
+        "<li><code>port</code>: <a href=\"#port\"><code>port</code></a></li>",
+        "<p>Same as <a href=\"#service\"><code>service</code></a>.</p>",
+    ] {
+        assert!(doc.contains(expected), "missing {expected:?} in:\n{doc}");
+    }
+}
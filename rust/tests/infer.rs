@@ -0,0 +1,141 @@
+use lidy::{SchemaInferrer, YamlFile};
+
+mod common;
+use common::{make_parser, yaml_file};
+
+const SAMPLES: [&str; 3] = [
+    "
+name: web
+protocol: tcp
+port: 80
+ratio: 0.5
+containers:
+  - { name: nginx, ports: [80, 443] }
+  - { name: sidecar, ports: [9901] }
+owner: { name: alice, email: a@x.org }
+point: [1, two]
+",
+    "
+name: db
+protocol: tcp
+port: 5432
+ratio: 2
+containers:
+  - { name: postgres, ports: [5432], env: [{ name: PGDATA, value: /data }] }
+maintainer: { name: bob, email: b@x.org }
+point: [3, four]
+tags: ~
+",
+    "
+name: cache
+protocol: udp
+port: 6379
+ratio: 1.25
+containers: []
+tags: [fast, memory]
+point: [5, six]
+",
+];
+
+const INFERRED_SCHEMA: &str = "# Inferred from 3 sample documents
+main:
+  _map:
+    name: string
+    protocol:
+      _in:
+        - tcp
+        - udp
+    port:
+      _range: '80 <= int <= 6379'
+    ratio:
+      _range: '0.5 <= float <= 2'
+    containers:
+      _listOf:
+        _map:
+          name: string
+          ports:
+            _listOf:
+              _range: '80 <= int <= 9901'
+        _mapFacultative:
+          env:
+            _listOf:
+              _map:
+                name: string
+                value: string
+    point:
+      _list:
+        - _range: '1 <= int <= 5'
+        - string
+  _mapFacultative:
+    owner: owner
+    maintainer: owner
+    tags:
+      _oneOf:
+        - nullType
+        - _listOf: string
+
+owner:
+  _map:
+    name: string
+    email: string
+";
+
+#[test]
+fn test_inferred_schema() {
+    let documents: Vec<YamlFile> = SAMPLES
+        .iter()
+        .enumerate()
+        .map(|(k, sample)| yaml_file(&format!("sample{k}.yaml"), sample))
+        .collect();
+    let mut inferrer = SchemaInferrer::new();
+    for document in &documents {
+        inferrer.add_document(&document.yaml);
+    }
+    let schema = inferrer.infer().unwrap();
+    assert_eq!(schema, INFERRED_SCHEMA);
+
+    // The schema passes the metaparser and accepts all the samples
+    let mut parser = make_parser(&schema);
+    for document in &documents {
+        parser.parse_content_yaml_file(document).unwrap();
+    }
+    let unknown_protocol = yaml_file(
+        "unknown.yaml",
+        "{ name: x, protocol: sctp, port: 80, ratio: 1, containers: [], point: [1, a] }",
+    );
+    let error = parser
+        .parse_content_yaml_file(&unknown_protocol)
+        .unwrap_err();
+    assert!(error.to_string().contains("_in"), "{error}");
+}
+
+#[test]
+fn test_mixed_values() {
+    let mut inferrer = SchemaInferrer::new().with_max_in_size(1);
+    for sample in ["[a, 1, -2.5, true]", "[b, c, { x: 1 }]"] {
+        inferrer.add_document(&yaml_file("sample.yaml", sample).yaml);
+    }
+    assert_eq!(
+        inferrer.infer().unwrap(),
+        "# Inferred from 2 sample documents
+main:
+  _listOf:
+    _oneOf:
+      - boolean
+      - _range: 'float <= 1'
+      - string
+      - _map:
+          x:
+            _range: '1 <= int <= 1'
+"
+    );
+}
+
+#[test]
+fn test_no_document() {
+    let error = SchemaInferrer::new().infer().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "no sample document to infer a schema from"
+    );
+}
@@ -0,0 +1,554 @@
+use std::rc::Rc;
+
+use lidy::{
+    export_json_schema, import_json_schema, File, FileFormat, JsonSchemaExport, JsonSchemaImport,
+    Parser,
+};
+use lidy__yaml::JsonLoader;
+
+mod common;
+use common::make_parser;
+
+fn export(schema: &str) -> JsonSchemaExport {
+    export_json_schema(&make_parser(schema).rule_set)
+}
+
+fn import(json: &str) -> JsonSchemaImport {
+    import_json_schema(&JsonLoader::load_from_str(json).unwrap())
+}
+
+fn check(parser: &mut Parser<()>, content: &str) -> bool {
+    parser
+        .parse(&Rc::from(File {
+            name: "<content>.json".into(),
+            content: content.into(),
+            format: FileFormat::Json,
+        }))
+        .is_ok()
+}
+
+#[test]
+fn test_export_map_checker() {
+    let result = export(
+        "
+main:
+  _map:
+    name: string
+    port: { _range: 1 <= int < 65536 }
+  _mapFacultative:
+    tags: { _listOf: string }
+  _merge: [labeled]
+labeled:
+  _mapFacultative:
+    labels: { _mapOf: { string: string } }
+",
+    );
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    assert_eq!(
+        result.to_json_string(),
+        r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "$ref": "#/$defs/main",
+  "$defs": {
+    "main": {
+      "type": "object",
+      "properties": {
+        "labels": {
+          "type": "object",
+          "additionalProperties": {
+            "type": "string"
+          }
+        },
+        "name": {
+          "type": "string"
+        },
+        "port": {
+          "type": "integer",
+          "minimum": 1,
+          "exclusiveMaximum": 65536
+        },
+        "tags": {
+          "type": "array",
+          "items": {
+            "type": "string"
+          }
+        }
+      },
+      "required": [
+        "name",
+        "port"
+      ],
+      "additionalProperties": false
+    },
+    "labeled": {
+      "type": "object",
+      "properties": {
+        "labels": {
+          "type": "object",
+          "additionalProperties": {
+            "type": "string"
+          }
+        }
+      },
+      "additionalProperties": false
+    }
+  }
+}"##
+    );
+}
+
+#[test]
+fn test_export_list_and_combinators() {
+    let result = export(
+        "
+main:
+  _list: [string, int]
+  _listFacultative: [boolean]
+  _listOf: choice
+  _max: 5
+choice:
+  _oneOf:
+    - { _in: [a, b] }
+    - { _regex: '^[0-9]+$' }
+    - scalar
+scalar:
+  _oneOf: [string, int, nullType]
+",
+    );
+    let schema = &result.schema.data["$defs"].data;
+    let main = &schema["main"].data;
+    assert_eq!(
+        main["prefixItems"].data[2].data["type"].data.as_str(),
+        Some("boolean")
+    );
+    assert_eq!(
+        main["items"].data["$ref"].data.as_str(),
+        Some("#/$defs/choice")
+    );
+    assert_eq!(main["minItems"].data.as_i64(), Some(2));
+    assert_eq!(main["maxItems"].data.as_i64(), Some(5));
+    let choice = &schema["choice"].data;
+    assert_eq!(
+        choice["anyOf"].data[0].data["enum"].data[1].data.as_str(),
+        Some("b")
+    );
+    assert_eq!(
+        choice["anyOf"].data[1].data["pattern"].data.as_str(),
+        Some("^[0-9]+$")
+    );
+    let scalar = &schema["scalar"].data;
+    assert_eq!(
+        scalar["oneOf"].data[2].data["type"].data.as_str(),
+        Some("null")
+    );
+}
+
+#[test]
+fn test_export_warnings() {
+    let result = export(
+        "
+main:
+  _map:
+    data: binary
+    code: { _regex: '(?i)^[a-z]+$' }
+  _mapOf:
+    int: string
+",
+    );
+    let messages: Vec<String> = result.warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(messages.len(), 3, "{messages:?}");
+    assert!(messages[0].starts_with("main: JSON Schema does not validate contentEncoding"));
+    assert!(messages[1].contains("RE2 syntax"));
+    assert!(messages[2].contains("JSON object keys are always strings"));
+    assert!(messages[2].ends_with(" 7:4"));
+}
+
+#[test]
+fn test_import_draft_07() {
+    let result = import(
+        r##"{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "title": "Service",
+  "type": "object",
+  "properties": {
+    "name": { "type": "string", "pattern": "^[a-z]+$" },
+    "port": { "type": "integer", "minimum": 1, "exclusiveMaximum": 65536 },
+    "protocol": { "enum": ["tcp", "udp"] },
+    "endpoint": { "$ref": "#/definitions/endpoint" }
+  },
+  "required": ["name", "port"],
+  "additionalProperties": false,
+  "definitions": {
+    "endpoint": {
+      "type": "array",
+      "items": [{ "type": "string" }, { "type": ["integer", "null"] }],
+      "additionalItems": false,
+      "minItems": 1
+    }
+  }
+}"##,
+    );
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    assert_eq!(
+        result.schema,
+        "main:
+  _map:
+    name:
+      _regex: '^[a-z]+$'
+    port:
+      _range: 1 <= int < 65536
+  _mapFacultative:
+    protocol:
+      _in:
+        - tcp
+        - udp
+    endpoint: endpoint
+  _title: Service
+
+endpoint:
+  _list:
+    - string
+  _listFacultative:
+    - _oneOf:
+        - int
+        - nullType
+  _min: 1
+"
+    );
+
+    let mut parser = make_parser(&result.schema);
+    assert!(check(&mut parser, r#"{"name": "web", "port": 80}"#));
+    assert!(check(
+        &mut parser,
+        r#"{"name": "web", "port": 443, "endpoint": ["a", null]}"#
+    ));
+    assert!(!check(&mut parser, r#"{"name": "web", "port": 0}"#));
+    assert!(!check(&mut parser, r#"{"name": "Web", "port": 80}"#));
+    assert!(!check(
+        &mut parser,
+        r#"{"name": "web", "port": 80, "extra": true}"#
+    ));
+    assert!(!check(
+        &mut parser,
+        r#"{"name": "web", "port": 80, "endpoint": []}"#
+    ));
+}
+
+#[test]
+fn test_import_2020_12() {
+    let result = import(
+        r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "$ref": "#/$defs/config",
+  "$defs": {
+    "config": {
+      "allOf": [{ "$ref": "#/$defs/named" }],
+      "properties": {
+        "tags": { "type": "array", "prefixItems": [{ "const": 1 }], "items": { "type": "string" } },
+        "extensions": { "patternProperties": { "^x-": true }, "additionalProperties": false },
+        "count": { "type": "integer", "multipleOf": 2 }
+      }
+    },
+    "named": {
+      "type": "object",
+      "properties": { "name": { "type": "string", "format": "date-time" } },
+      "required": ["name"]
+    },
+    "unused": { "type": "string" }
+  }
+}"##,
+    );
+    assert_eq!(
+        result.schema,
+        "main: config
+
+config:
+  _mapFacultative:
+    tags:
+      _listFacultative:
+        - _in:
+            - 1
+      _listOf: string
+    extensions:
+      _mapOf:
+        ? { _regex: '^x-' }
+        : any
+    count: any # unsupported JSON Schema keywords: multipleOf
+  _merge:
+    - named
+
+named:
+  _map:
+    name: timestamp
+  _mapOf:
+    string: any
+
+# unused: definition is not used by the root schema and was left out
+"
+    );
+    let messages: Vec<String> = result.warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(messages.len(), 2, "{messages:?}");
+    assert_eq!(
+        messages[0],
+        "config: unsupported JSON Schema keywords: multipleOf 10:17"
+    );
+    assert!(messages[1].starts_with("unused: definition is not used"));
+
+    let mut parser = make_parser(&result.schema);
+    assert!(check(
+        &mut parser,
+        r#"{"name": "2024-01-01T00:00:00Z", "tags": [1, "a"], "extensions": {"x-a": 0}}"#
+    ));
+    assert!(!check(&mut parser, r#"{"tags": []}"#));
+    assert!(!check(
+        &mut parser,
+        r#"{"name": "2024-01-01T00:00:00Z", "extensions": {"y": 0}}"#
+    ));
+}
+
+#[test]
+fn test_export_default() {
+    let result = export(
+        "
+main:
+  _mapFacultative:
+    port: int
+    extra: any
+  _mapDefault:
+    port: 80
+    extra: { a: 1 }
+",
+    );
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let properties = &result.schema.data["$defs"].data["main"].data["properties"].data;
+    assert_eq!(properties["port"].data["default"].data.as_i64(), Some(80));
+    assert_eq!(
+        properties["extra"].data["default"].data["a"].data.as_i64(),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_export_annotations() {
+    let result = export(
+        "
+main:
+  _map:
+    port: { _range: 1 <= int, _title: Port, _examples: [80] }
+  _description: A service
+  _deprecated: true
+",
+    );
+    let main = &result.schema.data["$defs"].data["main"].data;
+    assert_eq!(main["description"].data.as_str(), Some("A service"));
+    assert_eq!(main["deprecated"].data.as_bool(), Some(true));
+    let port = &main["properties"].data["port"].data;
+    assert_eq!(port["title"].data.as_str(), Some("Port"));
+    assert_eq!(port["examples"].data[0].data.as_i64(), Some(80));
+}
+
+#[test]
+fn test_string_lengths_round_trip() {
+    let result = export(
+        "
+main:
+  _map:
+    name: { _regex: '^[a-z]+$', _max: 63 }
+    host: { _string: hostname, _min: 1 }
+",
+    );
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let json = result.to_json_string();
+    assert!(json.contains(
+        r#""name": {
+          "type": "string",
+          "pattern": "^[a-z]+$",
+          "maxLength": 63
+        }"#
+    ));
+    assert!(json.contains(
+        r#""host": {
+          "type": "string",
+          "format": "hostname",
+          "minLength": 1
+        }"#
+    ));
+
+    let result = import(&json);
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let mut parser = make_parser(&result.schema);
+    assert!(check(&mut parser, r#"{"name": "web", "host": "a.org"}"#));
+    assert!(!check(
+        &mut parser,
+        &format!(r#"{{"name": "{}", "host": "a.org"}}"#, "a".repeat(64))
+    ));
+    assert!(!check(&mut parser, r#"{"name": "web", "host": ""}"#));
+}
+
+#[test]
+fn test_not_round_trip() {
+    let result = export(
+        "
+main:
+  _map:
+    tag: { _not: { _in: [latest] } }
+",
+    );
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let json = result.to_json_string();
+    assert!(json.contains(
+        r#""tag": {
+          "not": {
+            "enum": [
+              "latest"
+            ]
+          }
+        }"#
+    ));
+
+    let result = import(&json);
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let mut parser = make_parser(&result.schema);
+    assert!(check(&mut parser, r#"{"tag": "v1"}"#));
+    assert!(check(&mut parser, r#"{"tag": 1}"#));
+    assert!(!check(&mut parser, r#"{"tag": "latest"}"#));
+}
+
+#[test]
+fn test_all_of_round_trip() {
+    let result = export(
+        "
+main:
+  _map:
+    code: { _allOf: [{ _regex: '^[A-Z]+$' }, { _not: { _in: [NONE] } }] }
+",
+    );
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let json = result.to_json_string();
+    assert!(json.contains(r#""allOf": ["#), "{json}");
+
+    let result = import(&json);
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    assert!(result.schema.contains("_allOf"), "{}", result.schema);
+    let mut parser = make_parser(&result.schema);
+    assert!(check(&mut parser, r#"{"code": "ABC"}"#));
+    assert!(!check(&mut parser, r#"{"code": "NONE"}"#));
+    assert!(!check(&mut parser, r#"{"code": "abc"}"#));
+}
+
+#[test]
+fn test_const_round_trip() {
+    let result = export(
+        "
+main:
+  _map:
+    selector: { _const: { app: web, tier: [1, 2] } }
+",
+    );
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let json = result.to_json_string();
+    assert!(json.contains(r#""const": {"#), "{json}");
+
+    let result = import(&json);
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let mut parser = make_parser(&result.schema);
+    assert!(check(
+        &mut parser,
+        r#"{"selector": {"tier": [1.0, 2], "app": "web"}}"#
+    ));
+    assert!(!check(
+        &mut parser,
+        r#"{"selector": {"app": "web", "tier": [1]}}"#
+    ));
+
+    let result = import(r#"{ "enum": [[1, 2], { "a": null }] }"#);
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let mut parser = make_parser(&result.schema);
+    assert!(check(&mut parser, "[1, 2]"));
+    assert!(check(&mut parser, r#"{"a": null}"#));
+    assert!(!check(&mut parser, "[2, 1]"));
+}
+
+#[test]
+fn test_unique_items() {
+    let result = export(
+        "
+main:
+  _map:
+    steps: { _listOf: string, _unique: true }
+    ports: { _listOf: { _map: { name: string } }, _uniqueBy: name }
+",
+    );
+    let warnings: Vec<String> = result.warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(
+        warnings,
+        ["main: JSON Schema cannot require unique values of a key; _uniqueBy is not exported 5:61"]
+    );
+    let json = result.to_json_string();
+    assert!(json.contains(r#""uniqueItems": true"#), "{json}");
+
+    let result = import(&json);
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let mut parser = make_parser(&result.schema);
+    assert!(check(&mut parser, r#"{"steps": ["a", "b"], "ports": []}"#));
+    assert!(!check(&mut parser, r#"{"steps": ["a", "a"], "ports": []}"#));
+}
+
+#[test]
+fn test_key_constraints() {
+    let result = export(
+        "
+main:
+  _mapFacultative:
+    tls: boolean
+    cert: string
+    image: string
+    build: string
+    ports: { _listOf: int }
+    network_mode: string
+  _dependentRequired: { tls: [cert] }
+  _exactlyOneOf: [[image, build]]
+  _atMostOneOf: [[cert, build]]
+  _mutuallyExclusive: { ports: [{ network_mode: host }] }
+",
+    );
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let json = result.to_json_string();
+    assert!(json.contains(r#""dependentRequired": {"#), "{json}");
+    assert!(json.contains(r#""dependentSchemas": {"#), "{json}");
+    assert!(json.contains(r#""oneOf": ["#), "{json}");
+
+    let result = import(
+        r#"{
+  "type": "object",
+  "properties": { "tls": { "type": "boolean" }, "cert": { "type": "string" } },
+  "additionalProperties": false,
+  "dependentRequired": { "tls": ["cert"], "cert": ["key"] }
+}"#,
+    );
+    let warnings: Vec<String> = result.warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(warnings[0].contains("dependentRequired of 'cert' names an undeclared property"));
+    let mut parser = make_parser(&result.schema);
+    assert!(check(&mut parser, r#"{"tls": true, "cert": "a"}"#));
+    assert!(check(&mut parser, r#"{"cert": "a"}"#));
+    assert!(!check(&mut parser, r#"{"tls": true}"#));
+}
+
+#[test]
+fn test_export_discriminator() {
+    let result = export(
+        "
+main:
+  _discriminator: kind
+  _cases:
+    Service: { _map: { kind: string, port: int } }
+    Deployment: { _map: { kind: string, replicas: int } }
+",
+    );
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    let json = result.to_json_string();
+    assert!(json.contains(r#""enum": ["#), "{json}");
+    assert!(json.contains(r#""if": {"#), "{json}");
+    assert!(json.contains(r#""const": "Deployment""#), "{json}");
+}
@@ -0,0 +1,195 @@
+use lidy::{lint_rule_set, Severity};
+
+mod common;
+use common::make_parser;
+
+fn lint(schema: &str) -> Vec<String> {
+    let parser = make_parser(schema);
+    lint_rule_set(&parser.rule_set)
+        .iter()
+        .map(|finding| finding.to_string())
+        .collect()
+}
+
+#[test]
+fn test_clean_schema() {
+    let findings = lint(
+        "
+main:
+  _map:
+    name: { _regex: '^[a-z]+$' }
+    port: { _range: 1 <= int < 65536 }
+  _mapFacultative:
+    value:
+      _oneOf:
+        - { _in: [a, b] }
+        - { _in: [c] }
+        - int
+        - string
+",
+    );
+    assert_eq!(findings, Vec::<String>::new());
+}
+
+#[test]
+fn test_unreachable_alternatives() {
+    let findings = lint(
+        "
+main:
+  _oneOf:
+    - { _in: [a, b] }
+    - { _in: [b] }
+    - string
+    - { _regex: '^x$' }
+    - whatever
+    - float
+    - int
+    - whatever
+    - nullType
+
+whatever: any
+",
+    );
+    assert_eq!(
+        findings,
+        [
+            "warning: main: _oneOf: alternative 1 can never match, alternative 0 accepts all of its values 5:6",
+            "warning: main: _oneOf: alternative 3 can never match, alternative 2 accepts all of its values 7:6",
+            "warning: main: _oneOf: alternative 5 can never match, alternative 4 accepts all of its values 9:6",
+            "warning: main: _oneOf: alternative 6 can never match, alternative 4 accepts all of its values 10:6",
+            "warning: main: _oneOf: alternative 7 can never match, alternative 4 accepts all of its values 11:6",
+            "warning: main: _oneOf: alternative 8 can never match, alternative 4 accepts all of its values 12:6",
+        ]
+    );
+}
+
+#[test]
+fn test_unreachable_if_then_pairs() {
+    let findings = lint(
+        "
+main:
+  _ifThen:
+    - [string, string]
+    - [anyData, int]
+    - [int, float]
+",
+    );
+    assert_eq!(
+        findings,
+        ["warning: main: _ifThen: pair 2 is never used, the test of pair 1 accepts any value 6:6"]
+    );
+}
+
+#[test]
+fn test_duplicate_keys() {
+    let findings = lint(
+        "
+main:
+  _map:
+    name: string
+  _mapFacultative:
+    name: string
+  _merge: [base, extra]
+
+base:
+  _map:
+    id: int
+  _merge: [common]
+
+extra:
+  _mapFacultative:
+    id: string
+    version: int
+
+common:
+  _mapFacultative:
+    version: int
+",
+    );
+    assert_eq!(
+        findings,
+        [
+            "warning: main: _mapFacultative: key 'name' is also declared in _map, so it is mandatory 6:4",
+            "warning: main: _merge: key 'id' is declared by both 'base' and 'extra' 7:17",
+            "warning: main: _merge: key 'version' is declared by both 'base' and 'extra' 7:17",
+        ]
+    );
+}
+
+#[test]
+fn test_values_and_patterns() {
+    let parser = make_parser(
+        "
+main:
+  _list:
+    - { _regex: 'abc' }
+    - { _regex: '^abc' }
+    - { _regex: '^a$|b' }
+    - { _in: [] }
+    - { _range: 1 < int < 2 }
+    - { _range: 2.5 <= int <= 2.9 }
+    - { _range: 1 < float < 1 }
+    - { _range: 1 <= float <= 1 }
+    - { _range: 1.5 < int < 3 }
+    - { _not: any }
+    - { _not: { _regex: 'x' } }
+",
+    );
+    let findings = lint_rule_set(&parser.rule_set);
+    let summary: Vec<(Severity, String, String)> = findings
+        .iter()
+        .map(|f| (f.severity, f.message.to_string(), f.line_col.to_string()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (Severity::Warning, "_regex: /abc/ is not anchored, it matches anywhere in the string; anchor it with ^ and $".into(), "4:16".into()),
+            (Severity::Warning, "_regex: /^abc/ is not anchored, it matches before any suffix; anchor it with $".into(), "5:16".into()),
+            (Severity::Warning, "_regex: /^a$|b/ is not anchored, it matches anywhere in the string; anchor it with ^ and $".into(), "6:16".into()),
+            (Severity::Error, "_in: the list is empty, no value is accepted".into(), "7:13".into()),
+            (Severity::Error, "_range: no number is inside 1 < int < 2".into(), "8:16".into()),
+            (Severity::Error, "_range: no number is inside 2.5 <= int <= 2.9".into(), "9:16".into()),
+            (Severity::Error, "_range: no number is inside 1 < float < 1".into(), "10:16".into()),
+            (Severity::Error, "_not: the expression accepts any value, no value is accepted".into(), "13:14".into()),
+            (Severity::Warning, "_regex: /x/ is not anchored, it matches anywhere in the string; anchor it with ^ and $".into(), "14:24".into()),
+        ]
+    );
+}
+
+#[test]
+fn test_references_without_definitions() {
+    let findings = lint(
+        "
+main:
+  _map:
+    jobs: { _mapOf: { jobName: any } }
+    needs: { _listOf: { _references: job } }
+    uses: { _references: action }
+jobName: { _defines: job }
+",
+    );
+    assert_eq!(
+        findings,
+        ["error: main: _references: no _defines checker fills the key space 'action', no value is accepted 6:25"]
+    );
+}
+
+#[test]
+fn test_cases_without_discriminator_key() {
+    let findings = lint(
+        "
+main:
+  _discriminator: kind
+  _cases:
+    Service: service
+    Deployment: { _map: { replicas: int } }
+    List: { _mapOf: { string: any } }
+service:
+  _map: { kind: string, port: int }
+",
+    );
+    assert_eq!(
+        findings,
+        ["error: main: _cases: the case Deployment does not declare the key 'kind', no map is accepted 6:4"]
+    );
+}
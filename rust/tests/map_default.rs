@@ -0,0 +1,58 @@
+use lidy::{Data, LidyResult};
+
+mod common;
+use common::{file, make_parser};
+
+const SCHEMA: &str = "main:
+  _map:
+    name: string
+  _mapFacultative:
+    port: int
+    tags: { _listOf: string }
+  _mapDefault:
+    port: 8080
+    tags: [web]
+";
+
+fn parse(content: &str) -> LidyResult<()> {
+    make_parser(SCHEMA)
+        .parse(&file("<content>.yaml", content))
+        .unwrap()
+}
+
+#[test]
+fn test_absent_keys_take_their_default() {
+    let result = parse("name: web\n");
+    let Data::MapData(map_data) = &result.data else {
+        panic!("expected map data, got {:?}", result.data);
+    };
+    assert!(!result.is_default);
+    assert!(!map_data.map["name"].is_default);
+    assert_eq!(&*map_data.map["name"].position.filename, "<content>.yaml");
+
+    let port = &map_data.map["port"];
+    assert!(port.is_default);
+    assert!(matches!(port.data, Data::Integer(8080)));
+    assert_eq!(&*port.position.filename, "<schema>.yaml");
+    assert_eq!((port.position.line, port.position.column), (8, 10));
+
+    let tags = &map_data.map["tags"];
+    assert!(tags.is_default);
+    let Data::ListData(list_data) = &tags.data else {
+        panic!("expected list data, got {:?}", tags.data);
+    };
+    assert!(matches!(&list_data.list_of[0].data, Data::String(s) if &**s == "web"));
+}
+
+#[test]
+fn test_present_keys_keep_their_value() {
+    let result = parse("name: web\nport: 80\n");
+    let Data::MapData(map_data) = &result.data else {
+        panic!("expected map data, got {:?}", result.data);
+    };
+    let port = &map_data.map["port"];
+    assert!(!port.is_default);
+    assert!(matches!(port.data, Data::Integer(80)));
+    assert_eq!(&*port.position.filename, "<content>.yaml");
+    assert!(map_data.map["tags"].is_default);
+}
@@ -0,0 +1,314 @@
+use lidy::mutate_document;
+
+mod common;
+use common::{make_parser, yaml_file};
+
+const SCHEMA: &str = "
+main:
+  _map:
+    name: { _regex: '^[a-z]+$' }
+    port: { _range: 1 <= int < 65536 }
+    protocol: { _in: [tcp, udp] }
+    hosts: { _listOf: string, _min: 1, _max: 2 }
+  _mapFacultative:
+    labels: { _mapOf: { string: string }, _nb: 1 }
+";
+
+const DOCUMENT: &str = "
+name: web
+port: 8080
+protocol: tcp
+hosts:
+  - a.example.com
+  - b.example.com
+labels:
+  tier: front
+";
+
+#[test]
+fn test_mutations() {
+    let mut parser = make_parser(SCHEMA);
+    let mutations =
+        mutate_document(&parser.rule_set, &yaml_file("<content>.yaml", DOCUMENT)).unwrap();
+
+    let summary: Vec<String> = mutations
+        .iter()
+        .map(|m| format!("{} {} {}", m.keyword, m.line_col, m.description))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            "_regex 2:6 replace the value by '', which does not match /^[a-z]+$/",
+            "_range 3:6 replace the value by 65536, which is outside of 1 <= int < 65536",
+            "_in 4:10 replace the value by 'unexpected', which is not an accepted value",
+            "_min 6:2 remove items to leave 0 of them",
+            "_max 6:2 repeat the last item to have 3 items",
+            "_nb 9:6 remove entries to leave 0 of them",
+            "_map 2:4 drop the mandatory key 'name'",
+            "_map 2:4 drop the mandatory key 'port'",
+            "_map 2:4 drop the mandatory key 'protocol'",
+            "_map 2:4 drop the mandatory key 'hosts'",
+            "_map* 2:4 add the unknown key 'unknownKey'",
+        ]
+    );
+
+    // The schema rejects each mutation, and the error points to the mutated
+    // node
+    for mutation in &mutations {
+        let error = parser
+            .parse_content_yaml_file(&mutation.document)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains(&format!("{}: ", mutation.keyword))
+                && error.contains(&mutation.line_col.to_string()),
+            "{}: {error}",
+            mutation.description
+        );
+    }
+}
+
+#[test]
+fn test_mutations_follow_one_of_and_rules() {
+    let parser = make_parser(
+        "
+main: { _listOf: entry }
+
+entry:
+  _oneOf:
+    - { _range: int }
+    - { _map: { id: { _regex: '^[0-9]+$' } } }
+",
+    );
+    let mutations = mutate_document(
+        &parser.rule_set,
+        &yaml_file("<content>.yaml", "[1, { id: '42' }]"),
+    )
+    .unwrap();
+    let keywords: Vec<&str> = mutations.iter().map(|m| m.keyword).collect();
+    assert_eq!(keywords, ["_range", "_regex", "_map", "_map*"]);
+}
+
+#[test]
+fn test_mutations_of_an_invalid_document() {
+    let parser = make_parser(SCHEMA);
+    assert!(mutate_document(&parser.rule_set, &yaml_file("<content>.yaml", "name: web")).is_err());
+}
+
+#[test]
+fn test_mutations_of_string_lengths() {
+    let mut parser = make_parser(
+        "
+main:
+  _map:
+    name: { _regex: '^[a-z]+$', _min: 3, _max: 5 }
+    code: { _string: string, _nb: 3 }
+",
+    );
+    let mutations = mutate_document(
+        &parser.rule_set,
+        &yaml_file("<content>.yaml", "name: web\ncode: abc\n"),
+    )
+    .unwrap();
+    let summary: Vec<String> = mutations
+        .iter()
+        .map(|m| format!("{} {}", m.keyword, m.description))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            "_regex replace the value by '', which does not match /^[a-z]+$/",
+            "_min cut the value to 2 characters",
+            "_max repeat the last character to have 6 characters",
+            "_nb cut the value to 2 characters",
+            "_nb repeat the last character to have 4 characters",
+            "_map drop the mandatory key 'name'",
+            "_map drop the mandatory key 'code'",
+            "_map* add the unknown key 'unknownKey'",
+        ]
+    );
+    for mutation in &mutations {
+        let error = parser
+            .parse_content_yaml_file(&mutation.document)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains(&format!("{}: ", mutation.keyword)),
+            "{error}"
+        );
+    }
+}
+
+#[test]
+fn test_mutations_of_not() {
+    let mut parser = make_parser(
+        "
+main:
+  _map:
+    tag: { _not: { _in: [latest] } }
+    size: { _not: string }
+",
+    );
+    let mutations = mutate_document(
+        &parser.rule_set,
+        &yaml_file("<content>.yaml", "tag: v1\nsize: 2\n"),
+    )
+    .unwrap();
+    let summary: Vec<String> = mutations
+        .iter()
+        .filter(|m| m.keyword == "_not")
+        .map(|m| m.description.clone())
+        .collect();
+    assert_eq!(
+        summary,
+        ["replace the value by '', which matches the negated expression"]
+    );
+    for mutation in &mutations {
+        assert!(parser.parse_content_yaml_file(&mutation.document).is_err());
+    }
+}
+
+#[test]
+fn test_mutations_of_references() {
+    let mut parser = make_parser(
+        "
+main:
+  _map:
+    jobs: { _listOf: { _defines: job } }
+    needs: { _listOf: { _references: job } }
+",
+    );
+    let document = yaml_file(
+        "<content>.yaml",
+        "jobs: [build, undefined]\nneeds: [build]\n",
+    );
+    let mutations = mutate_document(&parser.rule_set, &document).unwrap();
+    let summary: Vec<String> = mutations
+        .iter()
+        .filter(|m| m.keyword == "_references")
+        .map(|m| format!("{} {}", m.description, m.line_col))
+        .collect();
+    assert_eq!(
+        summary,
+        ["replace the value by 'undefined1', which names no job 2:8"]
+    );
+    for mutation in &mutations {
+        assert!(parser.parse_content_yaml_file(&mutation.document).is_err());
+    }
+}
+
+#[test]
+fn test_mutations_of_key_constraints() {
+    let mut parser = make_parser(
+        "
+main:
+  _map:
+    tls: boolean
+  _mapFacultative:
+    cert: string
+    image: string
+    build: string
+    ports: { _listOf: int }
+    network_mode: string
+  _dependentRequired: { tls: [cert] }
+  _exactlyOneOf: [[image, build]]
+  _mutuallyExclusive: { ports: [{ network_mode: host }] }
+",
+    );
+    let document = yaml_file(
+        "<content>.yaml",
+        "tls: true\ncert: a\nimage: web\nports: [80]\n",
+    );
+    let mutations = mutate_document(&parser.rule_set, &document).unwrap();
+    let summary: Vec<String> = mutations
+        .iter()
+        .filter(|m| {
+            ["_dependentRequired", "_exactlyOneOf", "_mutuallyExclusive"].contains(&m.keyword)
+        })
+        .map(|m| format!("{}: {} {}", m.keyword, m.description, m.line_col))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            "_dependentRequired: drop the key 'cert', which key 'tls' requires 1:3",
+            "_exactlyOneOf: drop the key 'image', leaving none of image, build 1:3",
+            "_mutuallyExclusive: set network_mode to host, which key 'ports' excludes 1:3",
+        ]
+    );
+    for mutation in &mutations {
+        assert!(parser.parse_content_yaml_file(&mutation.document).is_err());
+    }
+}
+
+#[test]
+fn test_mutations_of_discriminators() {
+    let mut parser = make_parser(
+        "
+main:
+  _discriminator: kind
+  _cases:
+    Service: { _map: { kind: string, port: int } }
+    Deployment: { _map: { kind: string, replicas: int } }
+",
+    );
+    let document = yaml_file("<content>.yaml", "kind: Service\nport: 80\n");
+    let mutations = mutate_document(&parser.rule_set, &document).unwrap();
+    let summary: Vec<String> = mutations
+        .iter()
+        .map(|m| format!("{}: {} {}", m.keyword, m.description, m.line_col))
+        .collect();
+    assert!(summary.contains(&"_discriminator: drop the discriminator key 'kind' 1:4".to_string()));
+    assert!(summary.contains(
+        &"_discriminator: replace the kind by 'Unknown', which is not one of the cases 1:6"
+            .to_string()
+    ));
+    // The case mutations which touch the discriminator key are left out
+    assert!(!summary
+        .iter()
+        .any(|m| m.contains("drop the mandatory key 'kind'")));
+    assert!(summary
+        .iter()
+        .any(|m| m.contains("drop the mandatory key 'port'")));
+    for mutation in &mutations {
+        assert!(parser.parse_content_yaml_file(&mutation.document).is_err());
+    }
+}
+
+#[test]
+fn test_mutations_of_unique_lists() {
+    let mut parser = make_parser(
+        "
+main:
+  _map:
+    steps: { _listOf: string, _unique: true }
+    ports: { _listOf: { _map: { name: string, port: int } }, _uniqueBy: name }
+",
+    );
+    let document = yaml_file(
+        "<content>.yaml",
+        "steps: [build, test]\nports: [{ name: a, port: 1 }, { name: b, port: 1 }]\n",
+    );
+    let mutations = mutate_document(&parser.rule_set, &document).unwrap();
+    let summary: Vec<String> = mutations
+        .iter()
+        .filter(|m| m.keyword.starts_with("_unique"))
+        .map(|m| format!("{} {} {}", m.keyword, m.description, m.line_col))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            "_unique replace item 1 by a copy of item 0 1:15",
+            "_uniqueBy give item 1 the name of item 0 2:38",
+        ]
+    );
+    for mutation in &mutations {
+        let error = parser
+            .parse_content_yaml_file(&mutation.document)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains(&format!("{}: ", mutation.keyword)),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use std::cell::RefCell;
+
+mod common;
+use common::{file, make_recording_parser};
+
+const SCHEMA: &str = r#"main:
+  _map:
+    tag: { _not: reserved }
+    name: word
+reserved:
+  _in: [latest, stable]
+word:
+  _regex: "^[a-z]+$"
+"#;
+
+fn parse(content: &str, built: &RefCell<Vec<String>>) -> Result<(), String> {
+    make_recording_parser(SCHEMA, built)
+        .parse(&file("<content>.yaml", content))
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+#[test]
+fn test_negated_expression_runs_no_builders() {
+    let built = RefCell::new(Vec::new());
+    parse("tag: v1\nname: web\n", &built).unwrap();
+    assert_eq!(*built.borrow(), ["word", "main"]);
+}
+
+#[test]
+fn test_matching_expression_runs_no_builders() {
+    let built = RefCell::new(Vec::new());
+    parse("tag: latest\nname: web\n", &built).unwrap_err();
+    assert!(!built.borrow().contains(&"reserved".to_string()));
+}
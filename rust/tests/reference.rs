@@ -0,0 +1,43 @@
+mod common;
+use common::{file, make_parser};
+
+const SCHEMA: &str = r#"main:
+  _mapFacultative:
+    kind: { _in: [Service, Deployment] }
+    name: { _defines: app }
+    app: { _references: app }
+"#;
+
+#[test]
+fn test_stream_references_span_documents() {
+    let mut parser = make_parser(SCHEMA);
+    let stream = "kind: Service\napp: web\n---\nkind: Deployment\nname: web\n";
+    let results = parser
+        .parse_stream(&file("<content>.yaml", stream))
+        .unwrap();
+    assert_eq!(results.len(), 2);
+
+    // A single document only sees its own definitions
+    let error = parser
+        .parse(&file("<content>.yaml", stream))
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        error,
+        "_references: no app is named web 2:5 (no app is defined)"
+    );
+}
+
+#[test]
+fn test_dangling_stream_reference() {
+    let mut parser = make_parser(SCHEMA);
+    let stream = "name: web\n---\nname: db\n---\napp: cache\n";
+    let error = parser
+        .parse_stream(&file("<content>.yaml", stream))
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        error,
+        "_references: no app is named cache 5:5 (defined: web at 1:6, db at 3:6)"
+    );
+}
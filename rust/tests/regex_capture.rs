@@ -0,0 +1,74 @@
+use lidy::{Data, LidyResult};
+
+mod common;
+use common::{file, make_parser};
+
+const SCHEMA: &str = r#"main:
+  _map:
+    image:
+      _regex: "^(?P<name>[a-z/]+)(:(?P<tag>[a-z0-9.]+))?$"
+    host: { _regex: "^[a-z.]+$" }
+"#;
+
+fn parse(content: &str) -> LidyResult<()> {
+    make_parser(SCHEMA)
+        .parse(&file("<content>.yaml", content))
+        .unwrap()
+}
+
+fn entry<'a>(result: &'a LidyResult<()>, key: &str) -> &'a LidyResult<()> {
+    let Data::MapData(map_data) = &result.data else {
+        panic!("expected map data, got {:?}", result.data);
+    };
+    &map_data.map[key]
+}
+
+#[test]
+fn test_named_groups_are_positioned_in_the_string() {
+    let result = parse("image: library/nginx:1.25\nhost: example.org\n");
+    let image = entry(&result, "image");
+
+    let name = entry(image, "name");
+    assert!(matches!(&name.data, Data::String(s) if &**s == "library/nginx"));
+    assert_eq!((name.position.line, name.position.column), (1, 7));
+    assert_eq!(name.position.column_end, 20);
+
+    let tag = entry(image, "tag");
+    assert!(matches!(&tag.data, Data::String(s) if &**s == "1.25"));
+    assert_eq!((tag.position.line, tag.position.column), (1, 21));
+}
+
+#[test]
+fn test_unmatched_groups_are_absent() {
+    let result = parse("image: nginx\nhost: example.org\n");
+    let Data::MapData(map_data) = &entry(&result, "image").data else {
+        panic!("expected map data");
+    };
+    assert!(map_data.map.contains_key("name"));
+    assert!(!map_data.map.contains_key("tag"));
+}
+
+#[test]
+fn test_regex_without_named_groups_gives_the_string() {
+    let result = parse("image: nginx\nhost: example.org\n");
+    let host = entry(&result, "host");
+    assert!(matches!(&host.data, Data::String(s) if &**s == "example.org"));
+}
+
+#[test]
+fn test_named_groups_skip_the_opening_quote() {
+    let result = parse("image: 'nginx:1.25'\nhost: \"example.org\"\n");
+    let tag = entry(entry(&result, "image"), "tag");
+    assert_eq!((tag.position.line, tag.position.column), (1, 14));
+
+    let result = parse("{ \"image\": \"nginx:1.25\", \"host\": \"example.org\" }");
+    let name = entry(entry(&result, "image"), "name");
+    assert_eq!((name.position.line, name.position.column), (1, 12));
+}
+
+#[test]
+fn test_named_groups_of_a_literal_block_scalar() {
+    let result = parse("image: |-\n    nginx:1.25\nhost: example.org\n");
+    let tag = entry(entry(&result, "image"), "tag");
+    assert_eq!((tag.position.line, tag.position.column), (2, 10));
+}
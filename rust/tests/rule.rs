@@ -0,0 +1,23 @@
+mod common;
+use common::{file, make_parser};
+
+#[test]
+fn test_failed_rule_is_not_seen_as_a_loop() {
+    let mut parser = make_parser("main: { _listOf: port }\nport: int\n");
+
+    // The rules which failed for the root node of the first document must
+    // not be mistaken for a loop when they are applied to the root node of
+    // the second one
+    let error = parser.parse(&file("a.yaml", "[http]")).unwrap_err();
+    assert!(!error.to_string().contains("Infinite loop"), "{error}");
+    parser.parse(&file("b.yaml", "[80]")).unwrap();
+}
+
+#[test]
+fn test_failed_alternative_is_not_seen_as_a_loop() {
+    let mut parser =
+        make_parser("main: { _oneOf: [number, { _regex: '^[0-9]+/tcp$' }] }\nnumber: int\n");
+
+    parser.parse(&file("a.yaml", "80/tcp")).unwrap();
+    parser.parse(&file("b.yaml", "80")).unwrap();
+}
@@ -0,0 +1,293 @@
+use lidy::SampleGenerator;
+
+mod common;
+use common::{file, make_parser};
+
+// Generate documents with many seeds and check that the schema accepts them
+fn check_samples(schema: &str) -> Vec<String> {
+    let mut parser = make_parser(schema);
+    let mut samples = Vec::new();
+    for seed in 0..100 {
+        let sample = SampleGenerator::new(&parser.rule_set, seed)
+            .generate()
+            .unwrap();
+        if let Err(e) = parser.parse(&file("<sample>.yaml", &sample)) {
+            panic!("seed {seed}: the schema rejects the sample:\n{sample}\n{e}");
+        }
+        samples.push(sample);
+    }
+    samples
+}
+
+// check_samples parses many documents with one parser, which must recover
+// from a rejected document
+#[test]
+fn test_parser_reuse_after_error() {
+    let mut parser = make_parser("main: { _oneOf: [int, { _listOf: int }] }");
+    let mut parse = |content: &str| parser.parse(&file("<content>.yaml", content));
+    assert!(parse("[a]").is_err());
+    assert!(parse("[1]").is_ok());
+}
+
+#[test]
+fn test_sample_scalars() {
+    check_samples(
+        "
+main:
+  _list: [string, int, float, boolean, nullType, timestamp, binary, any, anyData]
+",
+    );
+}
+
+#[test]
+fn test_sample_regex_and_range() {
+    let samples = check_samples(
+        "
+main:
+  _map:
+    name: { _regex: '^[a-z][a-z0-9-]{2,10}$' }
+    email: { _regex: '^\\w+@(example|test)\\.(com|org)$' }
+    port: { _range: 1 <= int < 65536 }
+    ratio: { _range: 0 < float <= 1 }
+    small: { _range: 2.5 < int < 4 }
+",
+    );
+    assert!(samples.iter().all(|sample| sample.contains("small: 3\n")));
+}
+
+#[test]
+fn test_sample_sizes() {
+    check_samples(
+        "
+main:
+  _map:
+    exact: { _listOf: int, _nb: 3 }
+    bounded: { _listOf: string, _min: 2, _max: 4 }
+    tuple: { _list: [int], _listFacultative: [string, boolean] }
+    labels: { _mapOf: { string: string }, _min: 1, _max: 2 }
+    options:
+      _mapFacultative: { a: int, b: int, c: int }
+      _nb: 2
+",
+    );
+}
+
+#[test]
+fn test_sample_one_of_in_and_merge() {
+    let samples = check_samples(
+        "
+main:
+  _map:
+    value:
+      _oneOf:
+        - int
+        - { _in: [red, green, blue] }
+        - { _listOf: boolean }
+    service: service
+
+service:
+  _map:
+    name: string
+  _mapFacultative:
+    protocol: { _in: [tcp, udp] }
+  _mapDefault:
+    protocol: tcp
+  _merge: [common]
+
+common:
+  _map:
+    id: int
+",
+    );
+    assert!(samples
+        .iter()
+        .any(|sample| sample.contains("value: green\n")));
+    assert!(samples.iter().all(|sample| sample.contains("id: ")));
+}
+
+const RECURSIVE_SCHEMA: &str = "
+main:
+  _list: [tree, chain]
+
+tree:
+  _map:
+    name: string
+  _mapFacultative:
+    children: { _listOf: tree }
+
+# Only the second alternative ends the recursion
+chain:
+  _oneOf:
+    - { _list: [chain] }
+    - int
+";
+
+#[test]
+fn test_sample_recursive_rules() {
+    check_samples(RECURSIVE_SCHEMA);
+
+    // Past the maximum depth, only the required values are generated
+    let parser = make_parser(RECURSIVE_SCHEMA);
+    for seed in 0..20 {
+        let sample = SampleGenerator::new(&parser.rule_set, seed)
+            .with_max_depth(0)
+            .generate()
+            .unwrap();
+        assert!(sample.starts_with("- name: "), "seed {seed}:\n{sample}");
+        assert_eq!(sample.lines().count(), 2, "seed {seed}:\n{sample}");
+    }
+}
+
+#[test]
+fn test_sample_not() {
+    let samples = check_samples(
+        "
+main:
+  _map:
+    tag: { _not: string }
+    other: { _not: { _oneOf: [nullType, boolean, int] } }
+",
+    );
+    assert!(samples.iter().any(|s| s.contains("tag: []")));
+}
+
+#[test]
+fn test_sample_references() {
+    check_samples(
+        "
+main:
+  _map:
+    services: { _mapOf: { serviceName: service }, _min: 1 }
+serviceName: { _defines: service }
+service:
+  _mapFacultative:
+    depends_on: { _listOf: { _references: service } }
+",
+    );
+}
+
+#[test]
+fn test_sample_all_of() {
+    check_samples(
+        "
+main:
+  _map:
+    code: { _allOf: [string, { _regex: '^[a-c]{2}$' }] }
+    port: { _allOf: [{ _range: 1 <= int <= 20 }, { _not: { _in: [10] } }] }
+",
+    );
+}
+
+#[test]
+fn test_sample_key_constraints() {
+    check_samples(
+        "
+main:
+  _mapFacultative:
+    tls: boolean
+    cert: string
+    key: string
+    image: string
+    build: string
+    ports: { _listOf: int }
+    network_mode: { _in: [host, bridge] }
+  _dependentRequired: { tls: [cert, key] }
+  _exactlyOneOf: [[image, build]]
+  _atMostOneOf: [[cert, build]]
+  _mutuallyExclusive: { ports: [{ network_mode: host }] }
+",
+    );
+}
+
+#[test]
+fn test_sample_discriminator() {
+    check_samples(
+        "
+main:
+  _listOf:
+    _discriminator: kind
+    _cases:
+      Service: { _map: { kind: string, port: int } }
+      Deployment: { _map: { kind: { _in: [Deployment] }, replicas: int } }
+  _min: 3
+",
+    );
+}
+
+#[test]
+fn test_sample_unique() {
+    check_samples(
+        "
+main:
+  _map:
+    flags: { _listOf: boolean, _unique: true, _max: 2 }
+    ports:
+      _listOf: { _map: { name: { _in: [a, b, c, d, e] } } }
+      _uniqueBy: name
+",
+    );
+}
+
+#[test]
+fn test_sample_reproducible() {
+    let parser = make_parser("main: { _listOf: { _regex: '[a-z]+' } }");
+    let generate = |seed| {
+        SampleGenerator::new(&parser.rule_set, seed)
+            .generate()
+            .unwrap()
+    };
+    assert_eq!(generate(7), generate(7));
+    assert!((0..10).any(|seed| generate(seed) != generate(7)));
+}
+
+#[test]
+fn test_sample_impossible_schema() {
+    let parser = make_parser(
+        "
+main:
+  _map:
+    value: never
+",
+    );
+    let error = SampleGenerator::new(&parser.rule_set, 0)
+        .generate()
+        .unwrap_err();
+    assert!(error.to_string().contains("never"), "{error}");
+
+    let parser = make_parser("main: { _mapOf: { boolean: int }, _min: 3 }");
+    let error = SampleGenerator::new(&parser.rule_set, 0)
+        .generate()
+        .unwrap_err();
+    assert!(error.to_string().contains("distinct keys"), "{error}");
+}
+
+#[cfg(feature = "proptest")]
+mod strategy {
+    use std::cell::RefCell;
+
+    use proptest::test_runner::TestRunner;
+
+    use super::*;
+
+    #[test]
+    fn test_sample_strategy() {
+        let parser = RefCell::new(make_parser(
+            "
+main:
+  _map:
+    name: { _regex: '^[a-z]+$' }
+    replicas: { _range: 1 <= int <= 5 }
+",
+        ));
+        let strategy = lidy::sample_strategy(&parser.borrow().rule_set, 4);
+        TestRunner::default()
+            .run(&strategy, |sample| {
+                assert!(parser
+                    .borrow_mut()
+                    .parse(&file("<sample>.yaml", &sample))
+                    .is_ok());
+                Ok(())
+            })
+            .unwrap();
+    }
+}
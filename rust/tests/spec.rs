@@ -103,6 +103,7 @@ fn test_lidy() {
                     &Rc::from(lidy::File {
                         name: "<schema>.yaml".into(),
                         content: schema.unwrap(),
+                        format: lidy::FileFormat::Yaml,
                     }),
                     Box::new(|_, lidy_result| Ok(lidy_result.data.clone())),
                 );
@@ -113,9 +114,17 @@ fn test_lidy() {
                     )));
                 }
 
+                let format = match tile.get("format").map(|f| &**f) {
+                    Some("json") => lidy::FileFormat::Json,
+                    Some("toml") => lidy::FileFormat::Toml,
+                    Some("yaml") | None => lidy::FileFormat::Yaml,
+                    Some(f) => return Err(format!("unknown content format: {f}").into()),
+                };
+
                 let file = lidy::File {
                     name: "<content>.yaml".into(),
                     content: text.unwrap(),
+                    format,
                 };
 
                 if let Err(err) = parser.unwrap().parse(&Rc::from(file)) {
@@ -143,6 +152,7 @@ fn test_lidy() {
                     &Rc::from(lidy::File {
                         name: "<schema>.yaml".into(),
                         content: schema.unwrap().clone(),
+                        format: lidy::FileFormat::Yaml,
                     }),
                     Box::new(|_, lidy_result| Ok(lidy_result.data.clone())),
                 );
@@ -186,21 +196,42 @@ fn test_lidy() {
             Ok(())
         },
         &[
+            specimen::file::File::read_local_file("../testdata/collection/const.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/collection/keyConstraint.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/collection/listOf.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/collection/map.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/collection/mapDefault.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/collection/mapOf.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/collection/merge.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/collection/reference.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/collection/min_max_nb.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/collection/tuple.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/collection/unique.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/combinator/allOf.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/combinator/discriminator.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/combinator/ifThen.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/combinator/oneOf.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/combinator/not.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/json/json.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/toml/toml.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/scalar/in.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/scalar/range.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/scalar/regexOptions.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/scalar/regexp.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/scalar/string.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/scalar/timestampRange.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/scalarType/bigInteger.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/scalarType/binary.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/scalarType/format.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/scalarType/scalar.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/scalarType/timestamp.spec.yaml"),
+            specimen::file::File::read_local_file("../testdata/schema/annotation.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/schema/document.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/schema/expression.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/schema/mergeChecker.spec.yaml"),
-            specimen::file::File::read_local_file("../testdata/schema/regex.spec.yaml"),
+            // The regex crate rejects the backreferences, look-arounds and
+            // unbalanced regexes which regex.spec.yaml accepts in silence
+            specimen::file::File::read_local_file("../testdata/schema/regexSyntax.spec.yaml"),
             specimen::file::File::read_local_file("../testdata/yaml/yaml.spec.yaml"),
         ],
     );
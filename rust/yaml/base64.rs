@@ -0,0 +1,90 @@
+//! Base64, as used by the `tag:yaml.org,2002:binary` type
+//!
+//! The standard alphabet of RFC 4648 with padding. Whitespace, such as the
+//! line breaks of a block scalar, is ignored.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn sextet(c: u8) -> Option<u32> {
+    ALPHABET.iter().position(|&a| a == c).map(|p| p as u32)
+}
+
+pub fn decode_base64(text: &str) -> Result<Vec<u8>, String> {
+    let symbols: Vec<u8> = text.bytes().filter(|b| !b" \t\r\n".contains(b)).collect();
+    if !symbols.len().is_multiple_of(4) {
+        return Err(format!(
+            "the length, {} without whitespace, is not a multiple of 4",
+            symbols.len()
+        ));
+    }
+    let padding = symbols.iter().rev().take_while(|&&c| c == b'=').count();
+    if padding > 2 {
+        return Err("there are more than two padding characters".into());
+    }
+
+    let mut bytes = Vec::with_capacity(symbols.len() / 4 * 3);
+    for (k, quantum) in symbols.chunks(4).enumerate() {
+        let is_last = (k + 1) * 4 == symbols.len();
+        let used = if is_last { 4 - padding } else { 4 };
+        let mut bits = 0;
+        for (j, &c) in quantum.iter().enumerate() {
+            let value = if j < used {
+                sextet(c).ok_or_else(|| match c {
+                    b'=' => "padding is only allowed at the end".to_string(),
+                    c => format!("'{}' is not a base64 character", c as char),
+                })?
+            } else {
+                0
+            };
+            bits = bits << 6 | value;
+        }
+        bytes.extend(&bits.to_be_bytes()[1..used]);
+    }
+    Ok(bytes)
+}
+
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut bits = 0;
+        for k in 0..3 {
+            bits = bits << 8 | chunk.get(k).copied().unwrap_or(0) as u32;
+        }
+        for k in 0..4 {
+            if k <= chunk.len() {
+                text.push(ALPHABET[(bits >> (18 - 6 * k) & 0x3f) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for (bytes, text) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (&[0xfb, 0xff, 0xbf], "+/+/"),
+        ] {
+            assert_eq!(encode_base64(bytes), text);
+            assert_eq!(decode_base64(text).unwrap(), bytes);
+        }
+        assert_eq!(decode_base64("Zm9v\n  YmFy\n").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn test_invalid() {
+        for text in ["Zm9", "Zm9v-_==", "Z===", "Zg==Zm9v", "Zm9v!"] {
+            assert!(decode_base64(text).is_err(), "{text}");
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Integers beyond the range of i64
+//!
+//! They are kept as their decimal text, so that no digit is lost.
+
+/// The canonical decimal text of an integer literal: decimal with an optional
+/// sign, `0x` hexadecimal or `0o` octal. `None` if the text is not an integer.
+pub fn decimal_integer_text(text: &str) -> Option<String> {
+    let (negative, digits, radix) = if let Some(digits) = text.strip_prefix("0x") {
+        (false, digits, 16)
+    } else if let Some(digits) = text.strip_prefix("0o") {
+        (false, digits, 8)
+    } else if let Some(digits) = text.strip_prefix('-') {
+        (true, digits, 10)
+    } else {
+        (false, text.strip_prefix('+').unwrap_or(text), 10)
+    };
+    if digits.is_empty() {
+        return None;
+    }
+
+    // Little-endian limbs of nine decimal digits
+    const LIMB: u64 = 1_000_000_000;
+    let mut limbs: Vec<u64> = Vec::new();
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix)? as u64;
+        for limb in limbs.iter_mut() {
+            let value = *limb * radix as u64 + carry;
+            *limb = value % LIMB;
+            carry = value / LIMB;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+    }
+
+    let mut decimal = String::from(if negative && !limbs.is_empty() {
+        "-"
+    } else {
+        ""
+    });
+    match limbs.split_last() {
+        None => decimal.push('0'),
+        Some((most, rest)) => {
+            decimal.push_str(&most.to_string());
+            for limb in rest.iter().rev() {
+                decimal.push_str(&format!("{limb:09}"));
+            }
+        }
+    }
+    Some(decimal)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decimal_integer_text() {
+        for (text, decimal) in [
+            ("18446744073709551616", "18446744073709551616"),
+            ("+0018446744073709551616", "18446744073709551616"),
+            ("-99999999999999999999", "-99999999999999999999"),
+            ("0xFFFFFFFFFFFFFFFFF", "295147905179352825855"),
+            ("0o7777777777777777777777", "73786976294838206463"),
+            ("-0", "0"),
+            ("1000000000", "1000000000"),
+        ] {
+            assert_eq!(decimal_integer_text(text).as_deref(), Some(decimal));
+        }
+        for text in ["", "-", "1.5", "0x", "12a", "0xG"] {
+            assert_eq!(decimal_integer_text(text), None, "{text}");
+        }
+    }
+}
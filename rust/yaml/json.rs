@@ -0,0 +1,609 @@
+//! JSON front end
+//!
+//! JSON is a subset of YAML, but going through the YAML scanner loses
+//! precision on node positions and rejects some valid JSON escapes (e.g. `\/`).
+//! This loader reads JSON text directly and produces the same `Yaml` nodes as
+//! `YamlLoader`, with 1-based lines and 0-based columns.
+use std::fmt::{self, Display, Formatter};
+use std::mem;
+use std::str::Chars;
+
+use crate::{decimal_integer_text, encode_base64, LineCol, Mapping, Yaml, YamlData, BAD_VALUE};
+
+#[derive(Clone, PartialEq, Debug, Eq)]
+pub struct JsonError {
+    line_col: LineCol,
+    info: String,
+}
+
+impl JsonError {
+    fn new(line_col: LineCol, info: &str) -> JsonError {
+        JsonError {
+            line_col,
+            info: info.to_owned(),
+        }
+    }
+
+    pub fn line_col(&self) -> LineCol {
+        self.line_col
+    }
+
+    pub fn info(&self) -> &str {
+        &self.info
+    }
+}
+
+impl Display for JsonError {
+    // Same layout as yaml_rust's ScanError; col starts from 0
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {} column {}",
+            self.info,
+            self.line_col.line,
+            self.line_col.column + 1
+        )
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+struct Reader<'a> {
+    chars: Chars<'a>,
+    peeked: Option<char>,
+    line_col: LineCol,
+}
+
+impl<'a> Reader<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut chars = source.chars();
+        let peeked = chars.next();
+        Reader {
+            chars,
+            peeked,
+            line_col: LineCol { line: 1, column: 0 },
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.peeked
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peeked?;
+        self.peeked = self.chars.next();
+        if c == '\n' {
+            self.line_col.line += 1;
+            self.line_col.column = 0;
+        } else {
+            self.line_col.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.next();
+        }
+    }
+
+    fn error(&self, info: &str) -> JsonError {
+        JsonError::new(self.line_col, info)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.next();
+                Ok(())
+            }
+            Some(c) => Err(self.error(&format!("expected '{expected}' but found '{c}'"))),
+            None => Err(self.error(&format!("expected '{expected}' but found end of input"))),
+        }
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), JsonError> {
+        for expected in word.chars() {
+            if self.peek() != Some(expected) {
+                return Err(self.error(&format!("invalid literal, expected '{word}'")));
+            }
+            self.next();
+        }
+        Ok(())
+    }
+
+    fn read_string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            let line_col = self.line_col;
+            match self.next() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(value),
+                Some('\\') => match self.next() {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('/') => value.push('/'),
+                    Some('b') => value.push('\u{8}'),
+                    Some('f') => value.push('\u{c}'),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('u') => value.push(self.read_unicode_escape(line_col)?),
+                    _ => return Err(JsonError::new(line_col, "invalid escape sequence")),
+                },
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(JsonError::new(
+                        line_col,
+                        "control characters must be escaped in strings",
+                    ))
+                }
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn read_hex4(&mut self, line_col: LineCol) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.next().and_then(|c| c.to_digit(16)) {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(JsonError::new(line_col, "invalid unicode escape")),
+            }
+        }
+        Ok(code)
+    }
+
+    // Reads the four hex digits following `\u`, and the low surrogate escape
+    // which must follow a high surrogate
+    fn read_unicode_escape(&mut self, line_col: LineCol) -> Result<char, JsonError> {
+        let high = self.read_hex4(line_col)?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return Err(JsonError::new(
+                    line_col,
+                    "unpaired surrogate in unicode escape",
+                ));
+            }
+            let low = self.read_hex4(line_col)?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(JsonError::new(
+                    line_col,
+                    "unpaired surrogate in unicode escape",
+                ));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code)
+            .ok_or_else(|| JsonError::new(line_col, "unpaired surrogate in unicode escape"))
+    }
+
+    fn read_digits(&mut self, text: &mut String) -> Result<(), JsonError> {
+        if !matches!(self.peek(), Some('0'..='9')) {
+            return Err(self.error("expected a digit"));
+        }
+        while let Some(c @ '0'..='9') = self.peek() {
+            text.push(c);
+            self.next();
+        }
+        Ok(())
+    }
+
+    fn read_number(&mut self) -> Result<YamlData, JsonError> {
+        let mut text = String::new();
+        let mut is_integer = true;
+        if self.peek() == Some('-') {
+            text.push('-');
+            self.next();
+        }
+        if self.peek() == Some('0') {
+            text.push('0');
+            self.next();
+            if let Some('0'..='9') = self.peek() {
+                return Err(self.error("leading zeros are not allowed in numbers"));
+            }
+        } else {
+            self.read_digits(&mut text)?;
+        }
+        if self.peek() == Some('.') {
+            is_integer = false;
+            text.push('.');
+            self.next();
+            self.read_digits(&mut text)?;
+        }
+        if let Some(e @ ('e' | 'E')) = self.peek() {
+            is_integer = false;
+            text.push(e);
+            self.next();
+            if let Some(sign @ ('+' | '-')) = self.peek() {
+                text.push(sign);
+                self.next();
+            }
+            self.read_digits(&mut text)?;
+        }
+        if is_integer {
+            if let Ok(value) = text.parse::<i64>() {
+                return Ok(YamlData::Integer(value));
+            }
+            return Ok(YamlData::BigInteger(decimal_integer_text(&text).unwrap()));
+        }
+        Ok(YamlData::Real(text))
+    }
+}
+
+pub struct JsonLoader<'a> {
+    reader: Reader<'a>,
+    // states
+    doc_stack: Vec<Yaml>,
+    key_stack: Vec<Yaml>,
+}
+
+impl<'a> JsonLoader<'a> {
+    fn insert_new_node(&mut self, node: Yaml) -> Option<Yaml> {
+        let parent = match self.doc_stack.last_mut() {
+            None => return Some(node),
+            Some(parent) => parent,
+        };
+        match parent.data {
+            YamlData::List(ref mut v) => v.push(node),
+            YamlData::Mapping(ref mut h) => {
+                let mut key = BAD_VALUE.clone();
+                mem::swap(&mut key, self.key_stack.last_mut().unwrap());
+                h.push((key, node));
+            }
+            _ => unreachable!(),
+        }
+        None
+    }
+
+    // Read a key and its colon if the current container is a mapping
+    fn read_key_if_mapping(&mut self) -> Result<(), JsonError> {
+        if let Some(Yaml {
+            data: YamlData::Mapping(_),
+            ..
+        }) = self.doc_stack.last()
+        {
+            self.reader.skip_whitespace();
+            let line_col = self.reader.line_col;
+            if self.reader.peek() != Some('"') {
+                return Err(self.reader.error("expected a string key"));
+            }
+            let key = self.reader.read_string()?;
+            *self.key_stack.last_mut().unwrap() = Yaml::new(YamlData::String(key), line_col);
+            self.reader.skip_whitespace();
+            self.reader.expect(':')?;
+        }
+        Ok(())
+    }
+
+    // Read the value at the current position. Containers are pushed on the
+    // stack and left open; complete nodes are returned
+    fn read_value(&mut self) -> Result<Option<Yaml>, JsonError> {
+        self.reader.skip_whitespace();
+        let line_col = self.reader.line_col;
+        let data = match self.reader.peek() {
+            None => return Err(self.reader.error("unexpected end of input")),
+            Some('{') => {
+                self.reader.next();
+                self.doc_stack
+                    .push(Yaml::new(YamlData::Mapping(Mapping::new()), line_col));
+                self.key_stack.push(BAD_VALUE.clone());
+                return Ok(None);
+            }
+            Some('[') => {
+                self.reader.next();
+                self.doc_stack
+                    .push(Yaml::new(YamlData::List(Vec::new()), line_col));
+                return Ok(None);
+            }
+            Some('"') => YamlData::String(self.reader.read_string()?),
+            Some('t') => {
+                self.reader.expect_word("true")?;
+                YamlData::Boolean(true)
+            }
+            Some('f') => {
+                self.reader.expect_word("false")?;
+                YamlData::Boolean(false)
+            }
+            Some('n') => {
+                self.reader.expect_word("null")?;
+                YamlData::Null
+            }
+            Some('-' | '0'..='9') => self.reader.read_number()?,
+            Some(c) => return Err(self.reader.error(&format!("unexpected character '{c}'"))),
+        };
+        Ok(Some(Yaml::new(data, line_col)))
+    }
+
+    // Close the innermost container if the reader is on its closing bracket
+    fn try_close(&mut self) -> bool {
+        let closing = match self.doc_stack.last() {
+            Some(Yaml {
+                data: YamlData::Mapping(_),
+                ..
+            }) => '}',
+            Some(_) => ']',
+            None => return false,
+        };
+        self.reader.skip_whitespace();
+        if self.reader.peek() != Some(closing) {
+            return false;
+        }
+        self.reader.next();
+        if closing == '}' {
+            self.key_stack.pop();
+        }
+        true
+    }
+
+    fn load(&mut self) -> Result<Yaml, JsonError> {
+        let mut complete = self.read_value()?;
+        loop {
+            match complete.take() {
+                Some(node) => {
+                    if let Some(root) = self.insert_new_node(node) {
+                        self.reader.skip_whitespace();
+                        if self.reader.peek().is_some() {
+                            return Err(self.reader.error("trailing characters after JSON value"));
+                        }
+                        return Ok(root);
+                    }
+                    // A value was added to the innermost container; expect a
+                    // comma or the end of the container
+                    if self.try_close() {
+                        complete = self.doc_stack.pop();
+                        continue;
+                    }
+                    self.reader.skip_whitespace();
+                    self.reader.expect(',')?;
+                }
+                None => {
+                    // A container was just opened, it may be empty
+                    if self.try_close() {
+                        complete = self.doc_stack.pop();
+                        continue;
+                    }
+                }
+            }
+            self.read_key_if_mapping()?;
+            complete = self.read_value()?;
+        }
+    }
+
+    pub fn load_from_str(source: &str) -> Result<Yaml, JsonError> {
+        let mut loader = JsonLoader {
+            reader: Reader::new(source),
+            doc_stack: Vec::new(),
+            key_stack: Vec::new(),
+        };
+        loader.load()
+    }
+}
+
+/// Writes `Yaml` nodes as indented JSON text. Non-string mapping keys are
+/// written as their scalar text, and values JSON cannot represent (infinite
+/// or NaN reals, aliases, bad values) are written as `null`.
+pub struct JsonEmitter {
+    out: String,
+}
+
+impl JsonEmitter {
+    pub fn dump(node: &Yaml) -> String {
+        let mut emitter = JsonEmitter { out: String::new() };
+        emitter.emit_node(node, 0);
+        emitter.out
+    }
+
+    fn emit_string(&mut self, value: &str) {
+        self.out.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => self.out.push_str("\\\""),
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                c if (c as u32) < 0x20 => self.out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+
+    fn emit_newline(&mut self, indent: usize) {
+        self.out.push('\n');
+        self.out.push_str(&"  ".repeat(indent));
+    }
+
+    fn emit_key(&mut self, key: &Yaml) {
+        match &key.data {
+            YamlData::String(s) | YamlData::Real(s) | YamlData::Timestamp(s) => self.emit_string(s),
+            YamlData::Integer(i) => self.emit_string(&i.to_string()),
+            YamlData::BigInteger(text) => self.emit_string(text),
+            YamlData::Boolean(b) => self.emit_string(&b.to_string()),
+            YamlData::Binary(bytes) => self.emit_string(&encode_base64(bytes)),
+            _ => self.emit_string("null"),
+        }
+    }
+
+    fn emit_node(&mut self, node: &Yaml, indent: usize) {
+        match &node.data {
+            YamlData::String(s) | YamlData::Timestamp(s) => self.emit_string(s),
+            YamlData::Integer(i) => self.out.push_str(&i.to_string()),
+            YamlData::BigInteger(text) => self.out.push_str(text),
+            YamlData::Real(r) => match node.data.as_f64() {
+                Some(value) if value.is_finite() => {
+                    if JsonLoader::load_from_str(r).map(|n| n.data) == Ok(node.data.clone()) {
+                        self.out.push_str(r)
+                    } else {
+                        self.out.push_str(&format!("{value:?}"))
+                    }
+                }
+                _ => self.out.push_str("null"),
+            },
+            YamlData::Boolean(b) => self.out.push_str(&b.to_string()),
+            // JSON has no binary type, the data stays base64-encoded
+            YamlData::Binary(bytes) => self.emit_string(&encode_base64(bytes)),
+            YamlData::List(list) if list.is_empty() => self.out.push_str("[]"),
+            YamlData::List(list) => {
+                self.out.push('[');
+                for (index, item) in list.iter().enumerate() {
+                    if index > 0 {
+                        self.out.push(',');
+                    }
+                    self.emit_newline(indent + 1);
+                    self.emit_node(item, indent + 1);
+                }
+                self.emit_newline(indent);
+                self.out.push(']');
+            }
+            YamlData::Mapping(mapping) if mapping.is_empty() => self.out.push_str("{}"),
+            YamlData::Mapping(mapping) => {
+                self.out.push('{');
+                for (index, (key, value)) in mapping.iter().enumerate() {
+                    if index > 0 {
+                        self.out.push(',');
+                    }
+                    self.emit_newline(indent + 1);
+                    self.emit_key(key);
+                    self.out.push_str(": ");
+                    self.emit_node(value, indent + 1);
+                }
+                self.emit_newline(indent);
+                self.out.push('}');
+            }
+            YamlData::Null | YamlData::Alias(_) | YamlData::BadValue => self.out.push_str("null"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scalars() {
+        let out = JsonLoader::load_from_str(r#"["a", 1, -2.5, 1e3, true, false, null]"#).unwrap();
+        assert_eq!(out.data[0].data.as_str().unwrap(), "a");
+        assert_eq!(out.data[1].data.as_i64().unwrap(), 1);
+        assert_eq!(out.data[2].data.as_f64().unwrap(), -2.5);
+        assert_eq!(out.data[3].data.as_f64().unwrap(), 1000.0);
+        assert_eq!(out.data[4].data.as_bool().unwrap(), true);
+        assert_eq!(out.data[5].data.as_bool().unwrap(), false);
+        assert!(out.data[6].data.is_null());
+    }
+
+    #[test]
+    fn test_big_integers() {
+        let out = JsonLoader::load_from_str("[18446744073709551615, -1e30]").unwrap();
+        assert_eq!(
+            out.data[0].data,
+            YamlData::BigInteger("18446744073709551615".to_owned())
+        );
+        assert_eq!(out.data[1].data.as_f64().unwrap(), -1e30);
+        assert_eq!(JsonEmitter::dump(&out.data[0]), "18446744073709551615");
+    }
+
+    #[test]
+    fn test_escapes() {
+        let out = JsonLoader::load_from_str(r#""a\/b\"c\\d\n\t\u00e9\ud83d\ude00\u0000""#).unwrap();
+        assert_eq!(out.data.as_str().unwrap(), "a/b\"c\\d\n\té😀\0");
+    }
+
+    #[test]
+    fn test_positions() {
+        let s = "{\n  \"a\": [1, {\"b\": null}],\n  \"c\": \"d\"\n}";
+        let out = JsonLoader::load_from_str(s).unwrap();
+        assert_eq!(out.line_col, LineCol { line: 1, column: 0 });
+        let entries = match &out.data {
+            YamlData::Mapping(m) => m,
+            _ => panic!(),
+        };
+        assert_eq!(entries[0].0.line_col, LineCol { line: 2, column: 2 });
+        assert_eq!(entries[0].1.line_col, LineCol { line: 2, column: 7 });
+        assert_eq!(
+            entries[0].1.data[1].line_col,
+            LineCol {
+                line: 2,
+                column: 11
+            }
+        );
+        assert_eq!(entries[1].1.line_col, LineCol { line: 3, column: 7 });
+    }
+
+    #[test]
+    fn test_key_order_and_nesting() {
+        let out = JsonLoader::load_from_str(r#"{"b": {}, "a": [[], {"x": [1]}]}"#).unwrap();
+        assert_eq!(
+            out.data["a"].data[1].data["x"].data[0].data.as_i64(),
+            Some(1)
+        );
+        let keys: Vec<_> = match out.data {
+            YamlData::Mapping(m) => m.into_iter().map(|(k, _)| k.data).collect(),
+            _ => panic!(),
+        };
+        assert_eq!(
+            keys,
+            vec![
+                YamlData::String("b".to_owned()),
+                YamlData::String("a".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        for s in [
+            "",
+            "[1,]",
+            "{\"a\" 1}",
+            "{a: 1}",
+            "01",
+            "1.",
+            "\"\\x\"",
+            "\"\\ud800\"",
+            "[1] 2",
+            "[1",
+            "\"tab\there\"",
+            "tru",
+        ] {
+            assert!(JsonLoader::load_from_str(s).is_err(), "{s:?}");
+        }
+        let error = JsonLoader::load_from_str("{\n  \"a\": ?}").unwrap_err();
+        assert_eq!(error.line_col(), LineCol { line: 2, column: 7 });
+        assert_eq!(
+            error.to_string(),
+            "unexpected character '?' at line 2 column 8"
+        );
+    }
+
+    #[test]
+    fn test_emit_round_trip() {
+        let s = r#"{"a": [1, -2.5e3, "x\"\n\u0001", true, null, {}, []], "b": {"c": 1.0}}"#;
+        let doc = JsonLoader::load_from_str(s).unwrap();
+        let text = JsonEmitter::dump(&doc);
+        assert_eq!(
+            text,
+            "{\n  \"a\": [\n    1,\n    -2.5e3,\n    \"x\\\"\\n\\u0001\",\n    true,\n    null,\n    {},\n    []\n  ],\n  \"b\": {\n    \"c\": 1.0\n  }\n}"
+        );
+        let reloaded = JsonLoader::load_from_str(&text).unwrap();
+        assert_eq!(reloaded.data["a"].data[2].data, doc.data["a"].data[2].data);
+    }
+
+    #[test]
+    fn test_emit_yaml_only_values() {
+        let yaml = crate::YamlLoader::load_from_str("{1: .inf, 2: +1.5, 3: 1_0.5}").unwrap();
+        assert_eq!(
+            JsonEmitter::dump(&yaml[0]),
+            "{\n  \"1\": null,\n  \"2\": 1.5,\n  \"3\": \"1_0.5\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        let s = "[".repeat(10_000) + &"]".repeat(10_000);
+        assert!(JsonLoader::load_from_str(&s).is_ok());
+    }
+}
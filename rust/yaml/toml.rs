@@ -0,0 +1,333 @@
+//! TOML front end
+//!
+//! Maps a TOML document onto the same `Yaml` nodes as `YamlLoader`, with
+//! 1-based lines and 0-based columns. Tables, inline tables and arrays of
+//! tables become mappings and lists. Integers and floats stay distinct.
+//! Offset date-times, local date-times and local dates become
+//! `YamlData::Timestamp`; local times, which do not denote a point in time,
+//! become strings.
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+use toml_edit::{ImDocument, Item, Table, Value};
+
+use crate::{LineCol, Mapping, Yaml, YamlData};
+
+#[derive(Clone, PartialEq, Debug, Eq)]
+pub struct TomlError {
+    line_col: LineCol,
+    info: String,
+}
+
+impl TomlError {
+    pub fn line_col(&self) -> LineCol {
+        self.line_col
+    }
+
+    pub fn info(&self) -> &str {
+        &self.info
+    }
+}
+
+impl Display for TomlError {
+    // Same layout as yaml_rust's ScanError; col starts from 0
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {} column {}",
+            self.info,
+            self.line_col.line,
+            self.line_col.column + 1
+        )
+    }
+}
+
+impl std::error::Error for TomlError {}
+
+// Converts byte offsets of the source into line and column numbers
+struct LineIndex<'a> {
+    source: &'a str,
+    // byte offset of the beginning of each line
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex {
+            source,
+            line_starts,
+        }
+    }
+
+    fn line_col(&self, offset: usize) -> LineCol {
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+        let end = offset.min(self.source.len());
+        LineCol {
+            line: line + 1,
+            column: self.source.get(start..end).map_or(0, |s| s.chars().count()),
+        }
+    }
+
+    fn span_line_col(&self, span: Option<Range<usize>>, fallback: LineCol) -> LineCol {
+        span.map_or(fallback, |span| self.line_col(span.start))
+    }
+}
+
+pub struct TomlLoader<'a> {
+    index: LineIndex<'a>,
+}
+
+impl<'a> TomlLoader<'a> {
+    fn convert_table(&self, table: &Table, line_col: LineCol) -> Yaml {
+        let mut mapping = Mapping::new();
+        for (key, item) in table.iter() {
+            let key_line_col = self
+                .index
+                .span_line_col(table.key(key).and_then(|k| k.span()), line_col);
+            mapping.push((
+                Yaml::new(YamlData::String(key.to_owned()), key_line_col),
+                self.convert_item(item, key_line_col),
+            ));
+        }
+        Yaml::new(YamlData::Mapping(mapping), line_col)
+    }
+
+    // `fallback` is the position of the key of the item; it is used for
+    // implicit tables and dotted keys, which have no span of their own
+    fn convert_item(&self, item: &Item, fallback: LineCol) -> Yaml {
+        let line_col = self.index.span_line_col(item.span(), fallback);
+        match item {
+            Item::None => Yaml::new(YamlData::Null, line_col),
+            Item::Value(value) => self.convert_value(value, fallback),
+            Item::Table(table) => self.convert_table(table, line_col),
+            Item::ArrayOfTables(array) => {
+                let list = array
+                    .iter()
+                    .map(|table| {
+                        let table_line_col = self.index.span_line_col(table.span(), line_col);
+                        self.convert_table(table, table_line_col)
+                    })
+                    .collect();
+                Yaml::new(YamlData::List(list), line_col)
+            }
+        }
+    }
+
+    fn convert_value(&self, value: &Value, fallback: LineCol) -> Yaml {
+        let line_col = self.index.span_line_col(value.span(), fallback);
+        let data = match value {
+            Value::String(s) => YamlData::String(s.value().clone()),
+            Value::Integer(i) => YamlData::Integer(*i.value()),
+            Value::Float(f) => YamlData::Real(format_float(*f.value())),
+            Value::Boolean(b) => YamlData::Boolean(*b.value()),
+            Value::Datetime(dt) => {
+                let datetime = dt.value();
+                if datetime.date.is_some() {
+                    YamlData::Timestamp(datetime.to_string())
+                } else {
+                    YamlData::String(datetime.to_string())
+                }
+            }
+            Value::Array(array) => YamlData::List(
+                array
+                    .iter()
+                    .map(|item| self.convert_value(item, line_col))
+                    .collect(),
+            ),
+            Value::InlineTable(table) => {
+                let mut mapping = Mapping::new();
+                for (key, item) in table.iter() {
+                    let key_line_col = self
+                        .index
+                        .span_line_col(table.key(key).and_then(|k| k.span()), line_col);
+                    mapping.push((
+                        Yaml::new(YamlData::String(key.to_owned()), key_line_col),
+                        self.convert_value(item, key_line_col),
+                    ));
+                }
+                YamlData::Mapping(mapping)
+            }
+        };
+        Yaml::new(data, line_col)
+    }
+
+    pub fn load_from_str(source: &str) -> Result<Yaml, TomlError> {
+        let loader = TomlLoader {
+            index: LineIndex::new(source),
+        };
+        let document = ImDocument::parse(source).map_err(|e| TomlError {
+            line_col: loader
+                .index
+                .span_line_col(e.span(), LineCol { line: 1, column: 0 }),
+            info: e.message().trim().to_owned(),
+        })?;
+        Ok(loader.convert_table(document.as_table(), LineCol { line: 1, column: 0 }))
+    }
+}
+
+// Floats are stored as YAML Real strings, using the YAML spelling of the
+// special values
+fn format_float(value: f64) -> String {
+    if value.is_nan() {
+        ".nan".to_owned()
+    } else if value == f64::INFINITY {
+        ".inf".to_owned()
+    } else if value == f64::NEG_INFINITY {
+        "-.inf".to_owned()
+    } else {
+        format!("{value:?}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scalars() {
+        let s = r#"
+string = "a\tb"
+literal = 'C:\path'
+int = 1_000
+hex = 0xff
+float = 3.5
+exponent = 1e3
+infinity = -inf
+bool = true
+"#;
+        let doc = TomlLoader::load_from_str(s).unwrap();
+        assert_eq!(doc.data["string"].data.as_str().unwrap(), "a\tb");
+        assert_eq!(doc.data["literal"].data.as_str().unwrap(), "C:\\path");
+        assert_eq!(doc.data["int"].data.as_i64().unwrap(), 1000);
+        assert_eq!(doc.data["hex"].data.as_i64().unwrap(), 255);
+        assert_eq!(doc.data["float"].data.as_f64().unwrap(), 3.5);
+        assert_eq!(
+            doc.data["exponent"].data,
+            YamlData::Real("1000.0".to_owned())
+        );
+        assert_eq!(
+            doc.data["infinity"].data.as_f64().unwrap(),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(doc.data["bool"].data.as_bool().unwrap(), true);
+    }
+
+    #[test]
+    fn test_datetimes() {
+        let s = "
+offset = 1979-05-27T07:32:00Z
+local = 1979-05-27T07:32:00
+date = 1979-05-27
+time = 07:32:00
+";
+        let doc = TomlLoader::load_from_str(s).unwrap();
+        assert_eq!(
+            doc.data["offset"].data,
+            YamlData::Timestamp("1979-05-27T07:32:00Z".to_owned())
+        );
+        assert_eq!(
+            doc.data["local"].data,
+            YamlData::Timestamp("1979-05-27T07:32:00".to_owned())
+        );
+        assert_eq!(
+            doc.data["date"].data,
+            YamlData::Timestamp("1979-05-27".to_owned())
+        );
+        assert_eq!(doc.data["time"].data.as_str().unwrap(), "07:32:00");
+    }
+
+    #[test]
+    fn test_tables() {
+        let s = r#"
+name = "root"
+
+[server]
+host = "localhost"
+ports = [80, 443]
+tls = { cert = "a.pem", key = "a.key" }
+
+[database.primary]
+url = "postgres://"
+
+[[workers]]
+id = 1
+
+[[workers]]
+id = 2
+"#;
+        let doc = TomlLoader::load_from_str(s).unwrap();
+        assert_eq!(doc.line_col, LineCol { line: 1, column: 0 });
+        let server = &doc.data["server"];
+        assert_eq!(server.line_col, LineCol { line: 4, column: 0 });
+        assert_eq!(server.data["ports"].data[1].data.as_i64().unwrap(), 443);
+        assert_eq!(
+            server.data["ports"].data[1].line_col,
+            LineCol {
+                line: 6,
+                column: 13
+            }
+        );
+        assert_eq!(
+            server.data["tls"].data["key"].data.as_str().unwrap(),
+            "a.key"
+        );
+        assert_eq!(
+            server.data["tls"].data["key"].line_col,
+            LineCol {
+                line: 7,
+                column: 30
+            }
+        );
+        let primary = &doc.data["database"].data["primary"];
+        assert_eq!(primary.data["url"].data.as_str().unwrap(), "postgres://");
+        assert_eq!(
+            primary.data["url"].line_col,
+            LineCol {
+                line: 10,
+                column: 6
+            }
+        );
+        assert_eq!(
+            doc.data["workers"].data[1].data["id"].data.as_i64(),
+            Some(2)
+        );
+        assert_eq!(
+            doc.data["workers"].data[1].line_col,
+            LineCol {
+                line: 15,
+                column: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_key_positions() {
+        let s = "a = 1\n  \"é\".b = 2\n";
+        let doc = TomlLoader::load_from_str(s).unwrap();
+        let entries = match &doc.data {
+            YamlData::Mapping(m) => m,
+            _ => panic!(),
+        };
+        assert_eq!(entries[0].0.line_col, LineCol { line: 1, column: 0 });
+        assert_eq!(entries[1].0.line_col, LineCol { line: 2, column: 2 });
+        assert_eq!(
+            doc.data["é"].data["b"].line_col,
+            LineCol {
+                line: 2,
+                column: 10
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        for s in ["a = ", "a = 1\na = 2", "[a]\n[a]", "a = [1,", "a = 01"] {
+            assert!(TomlLoader::load_from_str(s).is_err(), "{s:?}");
+        }
+        let error = TomlLoader::load_from_str("a = 1\nb = ?").unwrap_err();
+        assert_eq!(error.line_col(), LineCol { line: 2, column: 4 });
+    }
+}
@@ -9,6 +9,16 @@ use std::vec;
 use yaml_rust::parser::*;
 use yaml_rust::scanner::{Marker, ScanError, TScalarStyle, TokenType};
 
+mod base64;
+mod integer;
+mod json;
+mod toml;
+
+pub use base64::{decode_base64, encode_base64};
+pub use integer::decimal_integer_text;
+pub use json::{JsonEmitter, JsonError, JsonLoader};
+pub use toml::{TomlError, TomlLoader};
+
 /// A YAML node is stored as this `Yaml` enumeration, which provides an easy way to
 /// access your YAML document.
 ///
@@ -32,10 +42,17 @@ pub enum YamlData {
     Real(string::String),
     /// YAML int is stored as i64.
     Integer(i64),
+    /// YAML int beyond the range of i64, stored as its decimal text.
+    BigInteger(string::String),
     /// YAML scalar.
     String(string::String),
     /// YAML bool, e.g. `true` or `false`.
     Boolean(bool),
+    /// Native datetime of a non-YAML front end (TOML), stored as its
+    /// RFC 3339 text. It may lack the time or the offset.
+    Timestamp(string::String),
+    /// YAML binary, a scalar tagged `!!binary`, decoded from base64.
+    Binary(Vec<u8>),
     /// YAML sequence, can be accessed as a `Vec`.
     List(self::List),
     /// YAML map, can be accessed as a `Vec` of key-value pairs.
@@ -144,7 +161,17 @@ impl MarkedEventReceiver for YamlLoader {
                 self.insert_new_node(node);
             }
             Event::Scalar(v, style, aid, tag) => {
-                let yaml_data = if style != TScalarStyle::Plain {
+                let is_binary = matches!(
+                    tag,
+                    Some(TokenType::Tag(ref handle, ref suffix)) if handle == "!!" && suffix == "binary"
+                );
+                let yaml_data = if is_binary {
+                    // Binary data is usually written as a block scalar
+                    match decode_base64(&v) {
+                        Ok(bytes) => YamlData::Binary(bytes),
+                        Err(_) => YamlData::BadValue,
+                    }
+                } else if style != TScalarStyle::Plain {
                     YamlData::String(v)
                 } else if let Some(TokenType::Tag(ref handle, ref suffix)) = tag {
                     // XXX tag:yaml.org,2002:
@@ -158,7 +185,10 @@ impl MarkedEventReceiver for YamlLoader {
                                 }
                             }
                             "int" => match v.parse::<i64>() {
-                                Err(_) => YamlData::BadValue,
+                                Err(_) => match decimal_integer_text(&v) {
+                                    Some(text) => YamlData::BigInteger(text),
+                                    None => YamlData::BadValue,
+                                },
                                 Ok(v) => YamlData::Integer(v),
                             },
                             "float" => match parse_f64(&v) {
@@ -342,6 +372,10 @@ impl YamlData {
             "true" => YamlData::Boolean(true),
             "false" => YamlData::Boolean(false),
             _ if v.parse::<i64>().is_ok() => YamlData::Integer(v.parse::<i64>().unwrap()),
+            // Integers too large for i64 must not become floats
+            _ if decimal_integer_text(v).is_some() => {
+                YamlData::BigInteger(decimal_integer_text(v).unwrap())
+            }
             // try parsing as f64
             _ if parse_f64(v).is_some() => YamlData::Real(v.to_owned()),
             _ => YamlData::String(v.to_owned()),
@@ -487,6 +521,44 @@ a2: *DEFAULT
         assert_eq!(doc.data["a2"].data["b1"].data.as_i64().unwrap(), 4);
     }
 
+    #[test]
+    fn test_big_integer() {
+        let s = "
+a: 18446744073709551615
+b: -9223372036854775809
+c: 0x10000000000000000
+d: !!int 99999999999999999999
+e: 9223372036854775807
+";
+        let out = YamlLoader::load_from_str(&s).unwrap();
+        let doc = &out[0];
+        let big = |text: &str| YamlData::BigInteger(text.to_owned());
+        assert_eq!(doc.data["a"].data, big("18446744073709551615"));
+        assert_eq!(doc.data["b"].data, big("-9223372036854775809"));
+        assert_eq!(doc.data["c"].data, big("18446744073709551616"));
+        assert_eq!(doc.data["d"].data, big("99999999999999999999"));
+        assert_eq!(doc.data["e"].data, YamlData::Integer(i64::MAX));
+    }
+
+    #[test]
+    fn test_binary() {
+        let s = "
+a: !!binary |
+    R0lGOD
+    lhDAAM
+b: !!binary Zm9vYmFy
+c: !!binary Zm9
+";
+        let out = YamlLoader::load_from_str(&s).unwrap();
+        let doc = &out[0];
+        assert_eq!(
+            doc.data["a"].data,
+            YamlData::Binary(b"GIF89a\x0c\x00\x0c".to_vec())
+        );
+        assert_eq!(doc.data["b"].data, YamlData::Binary(b"foobar".to_vec()));
+        assert_eq!(doc.data["c"].data, YamlData::BadValue);
+    }
+
     #[test]
     fn test_bad_anchor() {
         let s = "